@@ -0,0 +1,407 @@
+use std::{
+    collections::BTreeMap,
+    path::{Path, PathBuf},
+};
+
+use hyper::{header::CONTENT_TYPE, Body, HeaderMap, Response, StatusCode};
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+
+use crate::{multipart, request::ReceivedRequest};
+
+//
+pub const REDACTED: &str = "REDACTED";
+
+const SECRET_KEYS: &[&str] = &[
+    "oauth_consumer_key",
+    "oauth_nonce",
+    "oauth_signature",
+    "oauth_timestamp",
+    "oauth_token",
+    "oauth_token_secret",
+    "oauth_verifier",
+    "access_token",
+    "refresh_token",
+    "client_secret",
+    "code_verifier",
+];
+
+const RECORDED_RESPONSE_HEADERS: &[&str] = &[
+    "content-type",
+    "x-rate-limit-limit",
+    "x-rate-limit-remaining",
+    "x-rate-limit-reset",
+];
+
+//
+#[derive(Debug, Default)]
+pub(crate) enum Mode {
+    #[default]
+    Mock,
+    Record {
+        path: PathBuf,
+        cassette: Cassette,
+        upstream: reqwest::Client,
+    },
+    Replay {
+        cassette: Cassette,
+        used: Vec<bool>,
+    },
+}
+
+impl Mode {
+    pub(crate) fn unused_interactions(&self) -> Vec<Interaction> {
+        match self {
+            Self::Replay { cassette, used } => cassette
+                .interactions
+                .iter()
+                .zip(used)
+                .filter(|(_, used)| !**used)
+                .map(|(x, _)| x.to_owned())
+                .collect(),
+            _ => vec![],
+        }
+    }
+}
+
+//
+#[derive(Deserialize, Serialize, Debug, Clone, Default)]
+pub struct Cassette {
+    pub interactions: Vec<Interaction>,
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone)]
+pub struct Interaction {
+    pub request: CassetteRequest,
+    pub response: CassetteResponse,
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone)]
+pub struct CassetteRequest {
+    pub method: String,
+    pub url: String,
+    #[serde(default)]
+    pub headers: BTreeMap<String, String>,
+    pub body: CassetteBody,
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone)]
+pub struct CassetteResponse {
+    pub status: u16,
+    #[serde(default)]
+    pub headers: BTreeMap<String, String>,
+    pub body: CassetteBody,
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum CassetteBody {
+    Empty,
+    Text { text: String },
+    Json { json: Value },
+    Form { form: Vec<(String, String)> },
+    Multipart { parts: Vec<CassettePart> },
+    Binary { size: usize },
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq, Eq)]
+pub struct CassettePart {
+    pub name: String,
+    pub size: usize,
+}
+
+//
+impl Cassette {
+    pub fn load(path: impl AsRef<Path>) -> Result<Self, std::io::Error> {
+        let bytes = std::fs::read(path)?;
+        serde_json::from_slice(&bytes).map_err(std::io::Error::other)
+    }
+
+    pub fn save(&self, path: impl AsRef<Path>) -> Result<(), std::io::Error> {
+        let bytes = serde_json::to_vec_pretty(self).map_err(std::io::Error::other)?;
+        std::fs::write(path, bytes)
+    }
+}
+
+impl CassetteRequest {
+    pub fn from_received(request: &ReceivedRequest) -> Self {
+        let mut query = redact_pairs(request.query.to_owned());
+        query.sort();
+        let mut url = format!("https://{}{}", request.host, request.path);
+        if !query.is_empty() {
+            url.push('?');
+            url.push_str(&serde_urlencoded::to_string(&query).unwrap_or_default());
+        }
+
+        let mut headers = BTreeMap::new();
+        if let Some(content_type) = request.content_type() {
+            let content_type = content_type.split(';').next().unwrap_or_default();
+            headers.insert("content-type".into(), content_type.to_owned());
+        }
+        if let Some(authorization) = request.authorization() {
+            headers.insert("authorization".into(), redact_authorization(authorization));
+        }
+
+        Self {
+            method: request.method.to_string(),
+            url,
+            headers,
+            body: CassetteBody::new(request.content_type(), &request.body),
+        }
+    }
+
+    pub fn matches(&self, other: &Self) -> bool {
+        self.method == other.method && self.url == other.url && self.body == other.body
+    }
+}
+
+impl CassetteResponse {
+    pub fn new(status: StatusCode, headers: &HeaderMap, body: &[u8]) -> Self {
+        let content_type = headers.get(CONTENT_TYPE).and_then(|x| x.to_str().ok());
+
+        Self {
+            status: status.as_u16(),
+            headers: headers
+                .iter()
+                .filter(|(k, _)| RECORDED_RESPONSE_HEADERS.contains(&k.as_str()))
+                .filter_map(|(k, v)| Some((k.to_string(), v.to_str().ok()?.to_owned())))
+                .collect(),
+            body: CassetteBody::new(content_type, body),
+        }
+    }
+
+    pub fn to_response(&self) -> Response<Body> {
+        let mut builder =
+            Response::builder().status(StatusCode::from_u16(self.status).unwrap_or(StatusCode::OK));
+        for (k, v) in &self.headers {
+            builder = builder.header(k, v);
+        }
+        builder
+            .body(self.body.to_vec().into())
+            .expect("cassette response headers must be valid")
+    }
+}
+
+impl CassetteBody {
+    pub fn new(content_type: Option<&str>, body: &[u8]) -> Self {
+        let content_type = content_type.unwrap_or_default();
+
+        if body.is_empty() {
+            Self::Empty
+        } else if content_type.starts_with("multipart/form-data") {
+            Self::Multipart {
+                parts: multipart::parse(content_type, body)
+                    .into_iter()
+                    .map(|(name, data)| CassettePart {
+                        name,
+                        size: data.len(),
+                    })
+                    .collect(),
+            }
+        } else if content_type.starts_with("application/x-www-form-urlencoded") {
+            Self::Form {
+                form: redact_pairs(serde_urlencoded::from_bytes(body).unwrap_or_default()),
+            }
+        } else if let Ok(mut json) = serde_json::from_slice::<Value>(body) {
+            redact_json(&mut json);
+            Self::Json { json }
+        } else if let Ok(text) = std::str::from_utf8(body) {
+            // The OAuth endpoints answer urlencoded tokens as text/html.
+            match serde_urlencoded::from_str::<Vec<(String, String)>>(text) {
+                Ok(pairs) if pairs.iter().any(|(k, _)| SECRET_KEYS.contains(&k.as_str())) => {
+                    Self::Text {
+                        text: serde_urlencoded::to_string(redact_pairs(pairs)).unwrap_or_default(),
+                    }
+                }
+                _ => Self::Text { text: text.into() },
+            }
+        } else {
+            Self::Binary { size: body.len() }
+        }
+    }
+
+    pub fn to_vec(&self) -> Vec<u8> {
+        match self {
+            Self::Empty | Self::Multipart { .. } | Self::Binary { .. } => vec![],
+            Self::Text { text } => text.as_bytes().to_owned(),
+            Self::Json { json } => json.to_string().into_bytes(),
+            Self::Form { form } => serde_urlencoded::to_string(form)
+                .unwrap_or_default()
+                .into_bytes(),
+        }
+    }
+}
+
+//
+pub(crate) fn replay(
+    cassette: &Cassette,
+    used: &mut [bool],
+    request: &ReceivedRequest,
+) -> Response<Body> {
+    let request = CassetteRequest::from_received(request);
+
+    let found = cassette
+        .interactions
+        .iter()
+        .enumerate()
+        .find(|(i, x)| !used[*i] && x.request.matches(&request));
+
+    match found {
+        Some((i, interaction)) => {
+            used[i] = true;
+            interaction.response.to_response()
+        }
+        None => Response::builder()
+            .status(StatusCode::INTERNAL_SERVER_ERROR)
+            .header(CONTENT_TYPE, "application/problem+json")
+            .body(
+                json!({
+                    "title": "Cassette Mismatch",
+                    "detail": format!("No unused interaction matches {} {}", request.method, request.url),
+                    "type": "about:blank",
+                    "status": 500,
+                })
+                .to_string()
+                .into(),
+            )
+            .expect("static response must be valid"),
+    }
+}
+
+pub(crate) async fn forward(
+    client: &reqwest::Client,
+    request: &ReceivedRequest,
+) -> Result<(Response<Body>, Interaction), reqwest::Error> {
+    let mut url = format!("https://{}{}", request.host, request.path);
+    if !request.query.is_empty() {
+        url.push('?');
+        url.push_str(&serde_urlencoded::to_string(&request.query).unwrap_or_default());
+    }
+
+    let mut headers = request.headers.to_owned();
+    for name in ["host", "content-length", "connection", "proxy-connection"] {
+        headers.remove(name);
+    }
+
+    let response = client
+        .request(request.method.to_owned(), url)
+        .headers(headers)
+        .body(request.body.to_owned())
+        .send()
+        .await?;
+    let status = response.status();
+    let headers = response.headers().to_owned();
+    let body = response.bytes().await?;
+
+    let mut builder = Response::builder().status(status);
+    for (k, v) in headers.iter() {
+        if !["transfer-encoding", "content-length", "connection"].contains(&k.as_str()) {
+            builder = builder.header(k, v);
+        }
+    }
+
+    Ok((
+        builder
+            .body(body.to_vec().into())
+            .expect("upstream response headers must be valid"),
+        Interaction {
+            request: CassetteRequest::from_received(request),
+            response: CassetteResponse::new(status, &headers, &body),
+        },
+    ))
+}
+
+//
+pub fn redact_authorization(value: &str) -> String {
+    match value.split_once(' ') {
+        Some(("OAuth", params)) => {
+            let params = params
+                .split(',')
+                .map(|param| match param.trim().split_once('=') {
+                    Some((k, _)) if SECRET_KEYS.contains(&k) => format!(r#"{k}="{REDACTED}""#),
+                    _ => param.trim().to_owned(),
+                })
+                .collect::<Vec<_>>();
+            format!("OAuth {}", params.join(", "))
+        }
+        Some((scheme, _)) => format!("{scheme} {REDACTED}"),
+        None => REDACTED.into(),
+    }
+}
+
+fn redact_pairs(pairs: Vec<(String, String)>) -> Vec<(String, String)> {
+    pairs
+        .into_iter()
+        .map(|(k, v)| {
+            if SECRET_KEYS.contains(&k.as_str()) {
+                (k, REDACTED.into())
+            } else {
+                (k, v)
+            }
+        })
+        .collect()
+}
+
+fn redact_json(value: &mut Value) {
+    match value {
+        Value::Object(map) => {
+            for (k, v) in map.iter_mut() {
+                if SECRET_KEYS.contains(&k.as_str()) {
+                    *v = REDACTED.into();
+                } else {
+                    redact_json(v);
+                }
+            }
+        }
+        Value::Array(array) => array.iter_mut().for_each(redact_json),
+        _ => {}
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_redact() {
+        assert_eq!(
+            redact_authorization(
+                r#"OAuth oauth_consumer_key="foo", oauth_nonce="n", oauth_signature="s%3D", oauth_signature_method="HMAC-SHA1", oauth_timestamp="1", oauth_token="t", oauth_version="1.0""#
+            ),
+            r#"OAuth oauth_consumer_key="REDACTED", oauth_nonce="REDACTED", oauth_signature="REDACTED", oauth_signature_method="HMAC-SHA1", oauth_timestamp="REDACTED", oauth_token="REDACTED", oauth_version="1.0""#
+        );
+        assert_eq!(redact_authorization("Bearer xxx"), "Bearer REDACTED");
+
+        //
+        assert_eq!(
+            CassetteBody::new(
+                Some("text/html; charset=utf-8"),
+                include_bytes!("../../oauth1-twitter/tests/response_body_files/access_token.txt")
+            ),
+            CassetteBody::Text {
+                text: "oauth_token=REDACTED&oauth_token_secret=REDACTED&user_id=6253282&screen_name=twitterapi".into()
+            }
+        );
+        assert_eq!(
+            CassetteBody::new(
+                Some("application/json"),
+                br#"{"access_token":"ACCESS_TOKEN","nested":[{"refresh_token":"x"}]}"#
+            ),
+            CassetteBody::Json {
+                json: json!({"access_token": "REDACTED", "nested": [{"refresh_token": "REDACTED"}]})
+            }
+        );
+        assert_eq!(
+            CassetteBody::new(
+                Some("application/x-www-form-urlencoded"),
+                b"command=INIT&oauth_verifier=v"
+            ),
+            CassetteBody::Form {
+                form: vec![
+                    ("command".into(), "INIT".into()),
+                    ("oauth_verifier".into(), "REDACTED".into())
+                ]
+            }
+        );
+    }
+}
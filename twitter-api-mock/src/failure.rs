@@ -0,0 +1,14 @@
+//
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum Failure {
+    // 429 with x-rate-limit-* headers, reset is a unix timestamp in seconds
+    TooManyRequests { reset: u64 },
+    // 5xx with a v1 style error body
+    ServerError(u16),
+    // processing_info.state = failed, only for FINALIZE and STATUS
+    ProcessingFailed,
+    Respond { status: u16, body: String },
+    // answered as usual, lets a failure be scripted for a later request
+    Pass,
+}
@@ -0,0 +1,39 @@
+//
+pub(crate) const OAUTH_REQUEST_TOKEN: &str =
+    include_str!("../../oauth1-twitter/tests/response_body_files/request_token.txt");
+pub(crate) const OAUTH_REQUEST_TOKEN_400: &str =
+    include_str!("../../oauth1-twitter/tests/response_body_files/request_token__400.json");
+pub(crate) const OAUTH_ACCESS_TOKEN: &str =
+    include_str!("../../oauth1-twitter/tests/response_body_files/access_token.txt");
+pub(crate) const OAUTH_INVALIDATE_TOKEN: &str =
+    include_str!("../../oauth1-twitter/tests/response_body_files/invalidate_access_token.json");
+
+//
+pub(crate) const MEDIA_UPLOAD_IMAGE_OK: &str = include_str!(
+    "../../twitter-api-v1/tests/response_body_json_files/media__upload_image__ok.json"
+);
+pub(crate) const MEDIA_UPLOAD_INIT_OK: &str =
+    include_str!("../../twitter-api-v1/tests/response_body_json_files/media__upload_init__ok.json");
+pub(crate) const MEDIA_UPLOAD_FINALIZE_OK_ASYNC: &str = include_str!(
+    "../../twitter-api-v1/tests/response_body_json_files/media__upload_finalize__ok_async.json"
+);
+pub(crate) const MEDIA_UPLOAD_FINALIZE_OK_SYNC: &str = include_str!(
+    "../../twitter-api-v1/tests/response_body_json_files/media__upload_finalize__ok_sync.json"
+);
+pub(crate) const MEDIA_GET_UPLOAD_STATUS_OK_IN_PROGRESS: &str = include_str!(
+    "../../twitter-api-v1/tests/response_body_json_files/media__get_upload_status__ok_in_progress.json"
+);
+pub(crate) const MEDIA_GET_UPLOAD_STATUS_OK_SUCCEEDED: &str = include_str!(
+    "../../twitter-api-v1/tests/response_body_json_files/media__get_upload_status__ok_succeeded.json"
+);
+pub(crate) const MEDIA_GET_UPLOAD_STATUS_OK_FAILED: &str = include_str!(
+    "../../twitter-api-v1/tests/response_body_json_files/media__get_upload_status__ok_failed.json"
+);
+
+//
+pub(crate) const TWEETS_CREATE_ERR: &str = include_str!(
+    "../../twitter-api-v2/tests/response_body_json_files/tweets__manage_tweets__create__err.json"
+);
+pub(crate) const USERS_LOOKUP_OPTIONAL_FIELDS: &str = include_str!(
+    "../../twitter-api-v2/tests/response_body_json_files/users__lookup__me__optional_fields.json"
+);
@@ -0,0 +1,1024 @@
+use std::collections::{BTreeMap, HashMap, VecDeque};
+
+use hyper::{header::CONTENT_TYPE, Body, Response, StatusCode};
+use serde_json::{json, Value};
+
+use crate::{cassette::Mode, failure::Failure, fixtures, request::ReceivedRequest, route::Route};
+
+//
+#[derive(Debug, Clone)]
+pub(crate) struct Options {
+    pub(crate) processing_steps: usize,
+    pub(crate) check_after_secs: usize,
+}
+
+impl Default for Options {
+    fn default() -> Self {
+        Self {
+            processing_steps: 2,
+            check_after_secs: 1,
+        }
+    }
+}
+
+//
+#[derive(Debug, Default)]
+pub(crate) struct State {
+    pub(crate) options: Options,
+    pub(crate) mode: Mode,
+    pub(crate) requests: Vec<ReceivedRequest>,
+    pub(crate) failures: HashMap<Route, VecDeque<Failure>>,
+    pub(crate) media: HashMap<u64, MediaSession>,
+    pub(crate) tweets: BTreeMap<u64, Value>,
+    pub(crate) assets: HashMap<(String, String), Asset>,
+    pub(crate) last_id: u64,
+}
+
+impl State {
+    pub(crate) fn new(options: Options, mode: Mode) -> Self {
+        Self {
+            options,
+            mode,
+            last_id: 1579338012403453952,
+            ..Default::default()
+        }
+    }
+
+    fn next_id(&mut self) -> u64 {
+        self.last_id += 1;
+        self.last_id
+    }
+}
+
+#[derive(Debug, Default)]
+pub(crate) struct MediaSession {
+    pub(crate) total_bytes: usize,
+    pub(crate) media_category: String,
+    pub(crate) segments: BTreeMap<usize, usize>,
+    pub(crate) finalized: bool,
+    pub(crate) processing: bool,
+    pub(crate) processing_failed: bool,
+    pub(crate) status_checks: usize,
+    pub(crate) subtitles: BTreeMap<String, u64>,
+}
+
+impl MediaSession {
+    fn is_async(&self) -> bool {
+        matches!(
+            self.media_category.as_str(),
+            "tweet_video" | "tweet_gif" | "dm_video" | "dm_gif" | "amplify_video"
+        )
+    }
+
+    fn media_key_prefix(&self) -> &'static str {
+        if self.media_category.ends_with("_video") {
+            "7"
+        } else if self.media_category.ends_with("_gif") {
+            "16"
+        } else {
+            "3"
+        }
+    }
+
+    fn is_usable(&self, processing_steps: usize) -> bool {
+        self.finalized
+            && !self.processing_failed
+            && (!self.processing || self.status_checks >= processing_steps)
+    }
+}
+
+// Served on any host, e.g. a CDN the media is uploaded from.
+#[derive(Debug, Clone)]
+pub(crate) struct Asset {
+    pub(crate) content_type: Option<String>,
+    pub(crate) body: Vec<u8>,
+    // Sent with Transfer-Encoding: chunked instead of Content-Length.
+    pub(crate) chunked: bool,
+}
+
+//
+pub(crate) fn respond(state: &mut State, request: &ReceivedRequest) -> Response<Body> {
+    let route = match request.route {
+        Some(x) => x,
+        None => {
+            if let Some(asset) = state
+                .assets
+                .get(&(request.host.to_owned(), request.path.to_owned()))
+            {
+                return asset_response(asset, request);
+            }
+            return v2_problem(
+                StatusCode::NOT_FOUND,
+                "Not Found Error",
+                "The requested resource is not handled by the mock server.",
+                "about:blank",
+            );
+        }
+    };
+
+    if request.authorization().is_none() {
+        return raw(
+            StatusCode::BAD_REQUEST,
+            "application/json; charset=utf-8",
+            fixtures::OAUTH_REQUEST_TOKEN_400,
+        );
+    }
+
+    let mut processing_failed = false;
+    match state.failures.get_mut(&route).and_then(|x| x.pop_front()) {
+        Some(Failure::TooManyRequests { reset }) => {
+            let mut response = if request.path.starts_with("/2/") {
+                v2_problem(
+                    StatusCode::TOO_MANY_REQUESTS,
+                    "Too Many Requests",
+                    "Too Many Requests",
+                    "about:blank",
+                )
+            } else {
+                v1_error(StatusCode::TOO_MANY_REQUESTS, 88, "Rate limit exceeded")
+            };
+            let headers = response.headers_mut();
+            headers.insert("x-rate-limit-limit", 15.into());
+            headers.insert("x-rate-limit-remaining", 0.into());
+            headers.insert("x-rate-limit-reset", reset.into());
+            return response;
+        }
+        Some(Failure::ServerError(status)) => {
+            return v1_error(
+                StatusCode::from_u16(status).unwrap_or(StatusCode::INTERNAL_SERVER_ERROR),
+                131,
+                "Internal error",
+            )
+        }
+        Some(Failure::Respond { status, body }) => {
+            return raw(
+                StatusCode::from_u16(status).unwrap_or(StatusCode::INTERNAL_SERVER_ERROR),
+                "application/json; charset=utf-8",
+                body,
+            )
+        }
+        Some(Failure::ProcessingFailed) => processing_failed = true,
+        Some(Failure::Pass) | None => {}
+    }
+
+    match route {
+        Route::OauthRequestToken => raw(
+            StatusCode::OK,
+            "text/html; charset=utf-8",
+            fixtures::OAUTH_REQUEST_TOKEN,
+        ),
+        Route::OauthAccessToken => raw(
+            StatusCode::OK,
+            "text/html; charset=utf-8",
+            fixtures::OAUTH_ACCESS_TOKEN,
+        ),
+        Route::OauthInvalidateToken => raw(
+            StatusCode::OK,
+            "application/json; charset=utf-8",
+            fixtures::OAUTH_INVALIDATE_TOKEN,
+        ),
+        //
+        Route::MediaUpload => media_upload(state, request),
+        Route::MediaUploadInit => media_upload_init(state, request),
+        Route::MediaUploadAppend => media_upload_append(state, request),
+        Route::MediaUploadFinalize => media_upload_finalize(state, request, processing_failed),
+        Route::MediaUploadStatus => media_upload_status(state, request, processing_failed),
+        Route::MediaMetadataCreate => media_metadata_create(state, request),
+        Route::MediaSubtitlesCreate => media_subtitles(state, request, true),
+        Route::MediaSubtitlesDelete => media_subtitles(state, request, false),
+        Route::V2MediaUploadInitialize => v2_media_upload_initialize(state, request),
+        Route::V2MediaUploadAppend => v2_media_upload_append(state, request),
+        Route::V2MediaUploadFinalize => v2_media_upload_finalize(state, request, processing_failed),
+        Route::V2MediaUploadStatus => v2_media_upload_status(state, request, processing_failed),
+        Route::V2MediaMetadataCreate => v2_media_metadata_create(state, request),
+        //
+        Route::TweetsCreate => tweets_create(state, request),
+        Route::TweetsDelete => tweets_delete(state, request),
+        Route::TweetsLookup => tweets_lookup(state, request),
+        Route::TweetsLookupById => tweets_lookup_by_id(state, request),
+        Route::UsersLookupById | Route::UsersLookupByUsername => users_lookup(route, request),
+    }
+}
+
+//
+fn media_upload(state: &mut State, request: &ReceivedRequest) -> Response<Body> {
+    let media_category = request
+        .query_param("media_category")
+        .unwrap_or("tweet_image")
+        .to_owned();
+    let media = match request.multipart_part("media") {
+        Some(x) => x,
+        None => return v1_error(StatusCode::BAD_REQUEST, 38, "media parameter is missing."),
+    };
+
+    let media_id = state.next_id();
+    let session = MediaSession {
+        total_bytes: media.len(),
+        media_category,
+        segments: vec![(0, media.len())].into_iter().collect(),
+        finalized: true,
+        ..Default::default()
+    };
+    let body = media_json(fixtures::MEDIA_UPLOAD_IMAGE_OK, media_id, &session);
+    state.media.insert(media_id, session);
+
+    json_response(StatusCode::OK, &body)
+}
+
+fn media_upload_init(state: &mut State, request: &ReceivedRequest) -> Response<Body> {
+    let total_bytes = match request
+        .param("total_bytes")
+        .and_then(|x| x.parse::<usize>().ok())
+    {
+        Some(x) => x,
+        None => {
+            return v1_error(
+                StatusCode::BAD_REQUEST,
+                38,
+                "total_bytes parameter is missing.",
+            )
+        }
+    };
+    if request.param("media_type").is_none() {
+        return v1_error(
+            StatusCode::BAD_REQUEST,
+            38,
+            "media_type parameter is missing.",
+        );
+    }
+
+    let body = init_media(state, total_bytes, request.param("media_category"));
+
+    json_response(StatusCode::ACCEPTED, &body)
+}
+
+fn media_upload_append(state: &mut State, request: &ReceivedRequest) -> Response<Body> {
+    let media_id = match media_id_param(state, request) {
+        Ok(x) => x,
+        Err(err) => return err.v1(),
+    };
+    let segment_index = request
+        .param("segment_index")
+        .and_then(|x| x.parse::<usize>().ok());
+
+    match append_media(
+        state,
+        media_id,
+        segment_index,
+        request.multipart_part("media"),
+    ) {
+        Ok(()) => Response::builder()
+            .status(StatusCode::NO_CONTENT)
+            .body(Body::empty())
+            .expect("static response must be valid"),
+        Err(err) => err.v1(),
+    }
+}
+
+fn media_upload_finalize(
+    state: &mut State,
+    request: &ReceivedRequest,
+    processing_failed: bool,
+) -> Response<Body> {
+    match media_id_param(state, request)
+        .and_then(|media_id| finalize_media(state, media_id, processing_failed))
+    {
+        Ok(body) => json_response(StatusCode::OK, &body),
+        Err(err) => err.v1(),
+    }
+}
+
+fn media_upload_status(
+    state: &mut State,
+    request: &ReceivedRequest,
+    processing_failed: bool,
+) -> Response<Body> {
+    match media_id_param(state, request)
+        .and_then(|media_id| media_status(state, media_id, processing_failed))
+    {
+        Ok(body) => json_response(StatusCode::OK, &body),
+        Err(err) => err.v1(),
+    }
+}
+
+//
+// The v2 endpoints share the sessions with v1.1, only the shapes differ.
+fn v2_media_upload_initialize(state: &mut State, request: &ReceivedRequest) -> Response<Body> {
+    let json = match request.json() {
+        Some(x) => x,
+        None => return v2_invalid_request("body", Value::Null, "Invalid JSON body."),
+    };
+    let total_bytes = match json["total_bytes"].as_u64() {
+        Some(x) => x as usize,
+        None => {
+            return v2_invalid_request(
+                "total_bytes",
+                json["total_bytes"].to_owned(),
+                "total_bytes is required.",
+            )
+        }
+    };
+    if json["media_type"].as_str().is_none() {
+        return v2_invalid_request(
+            "media_type",
+            json["media_type"].to_owned(),
+            "media_type is required.",
+        );
+    }
+
+    let body = init_media(
+        state,
+        total_bytes,
+        json["media_category"].as_str().map(|x| x.to_owned()),
+    );
+
+    v2_media_response(&body)
+}
+
+fn v2_media_upload_append(state: &mut State, request: &ReceivedRequest) -> Response<Body> {
+    let media_id = match v2_media_id_path(state, request) {
+        Ok(x) => x,
+        Err(err) => return err.v2(),
+    };
+    let segment_index = request
+        .multipart_part("segment_index")
+        .and_then(|x| String::from_utf8(x).ok())
+        .and_then(|x| x.parse::<usize>().ok());
+
+    match append_media(
+        state,
+        media_id,
+        segment_index,
+        request.multipart_part("media"),
+    ) {
+        Ok(()) => json_response(StatusCode::OK, &json!({ "data": { "expires_at": 0 } })),
+        Err(err) => err.v2(),
+    }
+}
+
+fn v2_media_upload_finalize(
+    state: &mut State,
+    request: &ReceivedRequest,
+    processing_failed: bool,
+) -> Response<Body> {
+    match v2_media_id_path(state, request)
+        .and_then(|media_id| finalize_media(state, media_id, processing_failed))
+    {
+        Ok(body) => v2_media_response(&body),
+        Err(err) => err.v2(),
+    }
+}
+
+fn v2_media_upload_status(
+    state: &mut State,
+    request: &ReceivedRequest,
+    processing_failed: bool,
+) -> Response<Body> {
+    match media_id_param(state, request)
+        .and_then(|media_id| media_status(state, media_id, processing_failed))
+    {
+        Ok(body) => v2_media_response(&body),
+        Err(err) => err.v2(),
+    }
+}
+
+fn v2_media_metadata_create(state: &mut State, request: &ReceivedRequest) -> Response<Body> {
+    let json = match request.json() {
+        Some(x) => x,
+        None => return v2_invalid_request("body", Value::Null, "Invalid JSON body."),
+    };
+    match json["id"].as_str().and_then(|x| x.parse::<u64>().ok()) {
+        Some(x) if state.media.contains_key(&x) => {}
+        _ => return v2_invalid_request("id", json["id"].to_owned(), "Your media IDs are invalid."),
+    }
+    match json["metadata"]["alt_text"]["text"].as_str() {
+        Some(text) if text.chars().count() <= 1000 => {}
+        _ => {
+            return v2_invalid_request(
+                "metadata.alt_text.text",
+                json["metadata"]["alt_text"]["text"].to_owned(),
+                "alt_text.text is invalid.",
+            )
+        }
+    }
+
+    json_response(
+        StatusCode::OK,
+        &json!({
+            "data": {
+                "id": json["id"],
+                "associated_metadata": true,
+            }
+        }),
+    )
+}
+
+fn v2_media_response(v1_body: &Value) -> Response<Body> {
+    let mut data = v1_body.to_owned();
+    if let Some(data) = data.as_object_mut() {
+        data.remove("media_id");
+        if let Some(id) = data.remove("media_id_string") {
+            data.insert("id".into(), id);
+        }
+    }
+    json_response(StatusCode::OK, &json!({ "data": data }))
+}
+
+//
+struct MediaError {
+    status: StatusCode,
+    code: i64,
+    message: &'static str,
+}
+
+impl MediaError {
+    fn new(code: i64, message: &'static str) -> Self {
+        Self {
+            status: StatusCode::BAD_REQUEST,
+            code,
+            message,
+        }
+    }
+
+    fn v1(self) -> Response<Body> {
+        v1_error(self.status, self.code, self.message)
+    }
+
+    fn v2(self) -> Response<Body> {
+        v2_problem(
+            self.status,
+            "Invalid Request",
+            self.message,
+            "https://api.twitter.com/2/problems/invalid-request",
+        )
+    }
+}
+
+fn init_media(state: &mut State, total_bytes: usize, media_category: Option<String>) -> Value {
+    let media_id = state.next_id();
+    let session = MediaSession {
+        total_bytes,
+        media_category: media_category.unwrap_or_else(|| "tweet_image".into()),
+        ..Default::default()
+    };
+    let body = media_json(fixtures::MEDIA_UPLOAD_INIT_OK, media_id, &session);
+    state.media.insert(media_id, session);
+    body
+}
+
+fn append_media(
+    state: &mut State,
+    media_id: u64,
+    segment_index: Option<usize>,
+    media: Option<Vec<u8>>,
+) -> Result<(), MediaError> {
+    let session = media_session(state, media_id)?;
+    let segment_index = match segment_index {
+        Some(x) if x <= 999 => x,
+        _ => return Err(MediaError::new(38, "segment_index is invalid.")),
+    };
+    let media = media.ok_or_else(|| MediaError::new(38, "media parameter is missing."))?;
+    if session.finalized {
+        return Err(MediaError::new(324, "Media is already finalized."));
+    }
+
+    session.segments.insert(segment_index, media.len());
+    Ok(())
+}
+
+fn finalize_media(
+    state: &mut State,
+    media_id: u64,
+    processing_failed: bool,
+) -> Result<Value, MediaError> {
+    let processing_steps = state.options.processing_steps;
+    let check_after_secs = state.options.check_after_secs;
+
+    let session = media_session(state, media_id)?;
+    if session.segments.values().sum::<usize>() != session.total_bytes {
+        return Err(MediaError::new(
+            324,
+            "Segments do not add up to provided total file size.",
+        ));
+    }
+
+    session.finalized = true;
+    session.processing = session.is_async() && (processing_steps > 0 || processing_failed);
+    session.processing_failed = processing_failed;
+
+    let body = if session.processing {
+        let mut body = media_json(fixtures::MEDIA_UPLOAD_FINALIZE_OK_ASYNC, media_id, session);
+        body["processing_info"]["check_after_secs"] = check_after_secs.into();
+        body
+    } else if session.is_async() {
+        media_json(fixtures::MEDIA_UPLOAD_FINALIZE_OK_SYNC, media_id, session)
+    } else {
+        media_json(fixtures::MEDIA_UPLOAD_IMAGE_OK, media_id, session)
+    };
+
+    Ok(body)
+}
+
+fn media_status(
+    state: &mut State,
+    media_id: u64,
+    processing_failed: bool,
+) -> Result<Value, MediaError> {
+    let processing_steps = state.options.processing_steps;
+    let check_after_secs = state.options.check_after_secs;
+
+    let session = media_session(state, media_id)?;
+    if !session.finalized {
+        return Err(MediaError::new(324, "Media is not finalized."));
+    }
+
+    session.processing_failed |= processing_failed;
+    session.status_checks += 1;
+
+    let body = if session.processing_failed {
+        media_json(
+            fixtures::MEDIA_GET_UPLOAD_STATUS_OK_FAILED,
+            media_id,
+            session,
+        )
+    } else if !session.processing || session.status_checks >= processing_steps {
+        media_json(
+            fixtures::MEDIA_GET_UPLOAD_STATUS_OK_SUCCEEDED,
+            media_id,
+            session,
+        )
+    } else {
+        let mut body = media_json(
+            fixtures::MEDIA_GET_UPLOAD_STATUS_OK_IN_PROGRESS,
+            media_id,
+            session,
+        );
+        body["processing_info"]["check_after_secs"] = check_after_secs.into();
+        body["processing_info"]["progress_percent"] =
+            (session.status_checks * 100 / processing_steps).into();
+        body
+    };
+
+    Ok(body)
+}
+fn media_metadata_create(state: &mut State, request: &ReceivedRequest) -> Response<Body> {
+    let json = match request.json() {
+        Some(x) => x,
+        None => return v1_error(StatusCode::BAD_REQUEST, 38, "Invalid JSON body."),
+    };
+    let media_id = match json["media_id"]
+        .as_str()
+        .and_then(|x| x.parse::<u64>().ok())
+    {
+        Some(x) => x,
+        None => {
+            return v1_error(
+                StatusCode::BAD_REQUEST,
+                38,
+                "media_id parameter is missing.",
+            )
+        }
+    };
+    if !state.media.contains_key(&media_id) {
+        return v1_error(
+            StatusCode::BAD_REQUEST,
+            324,
+            "The validation of media ids failed.",
+        );
+    }
+    match json["alt_text"]["text"].as_str() {
+        Some(text) if text.chars().count() <= 1000 => {}
+        _ => {
+            return v1_error(
+                StatusCode::BAD_REQUEST,
+                44,
+                "alt_text.text parameter is invalid.",
+            )
+        }
+    }
+
+    raw(StatusCode::OK, "text/plain; charset=utf-8", "")
+}
+
+fn media_subtitles(state: &mut State, request: &ReceivedRequest, create: bool) -> Response<Body> {
+    let json = match request.json() {
+        Some(x) => x,
+        None => return v1_error(StatusCode::BAD_REQUEST, 38, "Invalid JSON body."),
+    };
+    let invalid_media = || {
+        v1_error(
+            StatusCode::BAD_REQUEST,
+            324,
+            "The validation of media ids failed.",
+        )
+    };
+    let media_id = match json["media_id"]
+        .as_str()
+        .and_then(|x| x.parse::<u64>().ok())
+    {
+        Some(x) if state.media.contains_key(&x) => x,
+        _ => return invalid_media(),
+    };
+    let subtitles = match json["subtitle_info"]["subtitles"].as_array() {
+        Some(x) if !x.is_empty() => x,
+        _ => {
+            return v1_error(
+                StatusCode::BAD_REQUEST,
+                38,
+                "subtitle_info parameter is missing.",
+            )
+        }
+    };
+
+    let mut changes = vec![];
+    for subtitle in subtitles {
+        let language_code = match subtitle["language_code"].as_str() {
+            Some(x) => x.to_uppercase(),
+            None => {
+                return v1_error(
+                    StatusCode::BAD_REQUEST,
+                    38,
+                    "language_code parameter is missing.",
+                )
+            }
+        };
+        if !create {
+            changes.push((language_code, None));
+            continue;
+        }
+        let subtitle_media_id = subtitle["media_id"]
+            .as_str()
+            .and_then(|x| x.parse::<u64>().ok())
+            .filter(|x| {
+                matches!(state.media.get(x), Some(media) if media.media_category == "subtitles")
+            });
+        match (subtitle_media_id, subtitle["display_name"].as_str()) {
+            (Some(subtitle_media_id), Some(_)) => {
+                changes.push((language_code, Some(subtitle_media_id)))
+            }
+            _ => return invalid_media(),
+        }
+    }
+
+    let session = state.media.get_mut(&media_id).expect("checked above");
+    for (language_code, subtitle_media_id) in changes {
+        match subtitle_media_id {
+            Some(x) => {
+                session.subtitles.insert(language_code, x);
+            }
+            None => {
+                if session.subtitles.remove(&language_code).is_none() {
+                    return v1_error(
+                        StatusCode::NOT_FOUND,
+                        34,
+                        "Sorry, that subtitle does not exist.",
+                    );
+                }
+            }
+        }
+    }
+
+    raw(StatusCode::OK, "text/plain; charset=utf-8", "")
+}
+
+fn media_id_param(state: &State, request: &ReceivedRequest) -> Result<u64, MediaError> {
+    let media_id = request
+        .param("media_id")
+        .and_then(|x| x.parse::<u64>().ok())
+        .ok_or_else(|| MediaError::new(38, "media_id parameter is missing."))?;
+    validate_media_id(state, media_id)
+}
+
+// /2/media/upload/{id}/append and /2/media/upload/{id}/finalize
+fn v2_media_id_path(state: &State, request: &ReceivedRequest) -> Result<u64, MediaError> {
+    let segments = request
+        .path
+        .trim_matches('/')
+        .split('/')
+        .collect::<Vec<_>>();
+    let media_id = segments
+        .get(3)
+        .and_then(|x| x.parse::<u64>().ok())
+        .ok_or_else(|| MediaError::new(38, "media_id parameter is missing."))?;
+    validate_media_id(state, media_id)
+}
+
+fn validate_media_id(state: &State, media_id: u64) -> Result<u64, MediaError> {
+    if state.media.contains_key(&media_id) {
+        Ok(media_id)
+    } else {
+        Err(MediaError::new(324, "The validation of media ids failed."))
+    }
+}
+
+fn media_session(state: &mut State, media_id: u64) -> Result<&mut MediaSession, MediaError> {
+    state
+        .media
+        .get_mut(&media_id)
+        .ok_or_else(|| MediaError::new(324, "The validation of media ids failed."))
+}
+fn media_json(fixture: &str, media_id: u64, session: &MediaSession) -> Value {
+    let mut body: Value = serde_json::from_str(fixture).expect("invalid fixture");
+    body["media_id"] = media_id.into();
+    body["media_id_string"] = media_id.to_string().into();
+    body["media_key"] = format!("{}_{media_id}", session.media_key_prefix()).into();
+    if body.get("size").is_some() {
+        body["size"] = session.total_bytes.into();
+    }
+    body
+}
+
+//
+fn tweets_create(state: &mut State, request: &ReceivedRequest) -> Response<Body> {
+    let body = match request.json() {
+        Some(Value::Object(x)) => x,
+        _ => {
+            return raw(
+                StatusCode::BAD_REQUEST,
+                "application/problem+json",
+                fixtures::TWEETS_CREATE_ERR,
+            )
+        }
+    };
+
+    let text = body.get("text").and_then(|x| x.as_str());
+    let media_ids = body
+        .get("media")
+        .and_then(|x| x.get("media_ids"))
+        .and_then(|x| x.as_array())
+        .cloned()
+        .unwrap_or_default();
+
+    if text.is_none() && media_ids.is_empty() {
+        return v2_invalid_request("text", Value::Null, "Text or media is required.");
+    }
+    if let Some(poll) = body.get("poll").filter(|_| !media_ids.is_empty()) {
+        return v2_invalid_request(
+            "poll",
+            poll.to_owned(),
+            "poll and media are mutually exclusive.",
+        );
+    }
+
+    let processing_steps = state.options.processing_steps;
+    for media_id in &media_ids {
+        let usable = media_id
+            .as_str()
+            .and_then(|x| x.parse::<u64>().ok())
+            .and_then(|x| state.media.get(&x))
+            .map(|x| x.is_usable(processing_steps))
+            == Some(true);
+        if !usable {
+            return v2_invalid_request(
+                "media.media_ids",
+                media_id.to_owned(),
+                "Your media IDs are invalid.",
+            );
+        }
+    }
+
+    let id = state.next_id();
+    state.tweets.insert(id, Value::Object(body.to_owned()));
+
+    json_response(
+        StatusCode::CREATED,
+        &json!({
+            "data": {
+                "id": id.to_string(),
+                "text": text.unwrap_or_default(),
+            }
+        }),
+    )
+}
+
+fn tweets_delete(state: &mut State, request: &ReceivedRequest) -> Response<Body> {
+    let id = request
+        .path
+        .trim_end_matches('/')
+        .rsplit('/')
+        .next()
+        .and_then(|x| x.parse::<u64>().ok());
+
+    match id.and_then(|x| state.tweets.remove(&x)) {
+        Some(_) => json_response(StatusCode::OK, &json!({ "data": { "deleted": true } })),
+        None => v2_problem(
+            StatusCode::NOT_FOUND,
+            "Not Found Error",
+            "Could not find tweet.",
+            "https://api.twitter.com/2/problems/resource-not-found",
+        ),
+    }
+}
+
+fn tweets_lookup(state: &State, request: &ReceivedRequest) -> Response<Body> {
+    let ids = request.query_param("ids").unwrap_or_default();
+    let parsed = ids
+        .split(',')
+        .map(|x| x.parse::<u64>())
+        .collect::<Result<Vec<_>, _>>();
+    let ids = match parsed {
+        Ok(x) if (1..=100).contains(&x.len()) => x,
+        _ => {
+            return v2_invalid_request(
+                "ids",
+                ids.into(),
+                "The `ids` query parameter value must be 1 to 100 comma separated tweet ids.",
+            )
+        }
+    };
+
+    let (found, missing): (Vec<_>, Vec<_>) =
+        ids.into_iter().partition(|x| state.tweets.contains_key(x));
+    let mut body = json!({});
+    if !found.is_empty() {
+        body["data"] = found
+            .into_iter()
+            .map(|x| tweet_json(x, &state.tweets[&x]))
+            .collect();
+    }
+    if !missing.is_empty() {
+        body["errors"] = missing.into_iter().map(tweet_not_found).collect();
+    }
+    json_response(StatusCode::OK, &body)
+}
+
+fn tweets_lookup_by_id(state: &State, request: &ReceivedRequest) -> Response<Body> {
+    let id = request
+        .path
+        .trim_end_matches('/')
+        .rsplit('/')
+        .next()
+        .and_then(|x| x.parse::<u64>().ok())
+        .unwrap_or_default();
+
+    let body = match state.tweets.get(&id) {
+        Some(tweet) => json!({ "data": tweet_json(id, tweet) }),
+        None => json!({ "errors": [tweet_not_found(id)] }),
+    };
+    json_response(StatusCode::OK, &body)
+}
+
+// `tweet` is the body it was created with.
+fn tweet_json(id: u64, tweet: &Value) -> Value {
+    json!({
+        "id": id.to_string(),
+        "text": tweet.get("text").and_then(|x| x.as_str()).unwrap_or_default(),
+        "edit_history_tweet_ids": [id.to_string()],
+    })
+}
+
+fn tweet_not_found(id: u64) -> Value {
+    json!({
+        "value": id.to_string(),
+        "detail": format!("Could not find tweet with ids: [{id}]."),
+        "title": "Not Found Error",
+        "resource_type": "tweet",
+        "parameter": "ids",
+        "resource_id": id.to_string(),
+        "type": "https://api.twitter.com/2/problems/resource-not-found",
+    })
+}
+
+//
+fn users_lookup(route: Route, request: &ReceivedRequest) -> Response<Body> {
+    let mut body: Value =
+        serde_json::from_str(fixtures::USERS_LOOKUP_OPTIONAL_FIELDS).expect("invalid fixture");
+    let last_segment = request.path.trim_end_matches('/').rsplit('/').next();
+
+    match (route, last_segment) {
+        (Route::UsersLookupById, Some(id)) => body["data"]["id"] = id.into(),
+        (Route::UsersLookupByUsername, Some(username)) => {
+            body["data"]["username"] = username.into()
+        }
+        _ => {}
+    }
+    body["data"]["profile_image_url"] =
+        "https://pbs.twimg.com/profile_images/1445764922474827784/W2zEPN7U_normal.jpg".into();
+    if request
+        .query_param("expansions")
+        .is_some_and(|x| x.split(',').any(|x| x == "pinned_tweet_id"))
+    {
+        let pinned_tweet_id = body["data"]["pinned_tweet_id"].to_owned();
+        body["includes"] = json!({
+            "tweets": [{
+                "id": pinned_tweet_id,
+                "text": "pinned",
+                "edit_history_tweet_ids": [pinned_tweet_id],
+            }]
+        });
+    }
+
+    json_response(StatusCode::OK, &body)
+}
+
+//
+// Ranges (bytes=N-) are honored unless the asset is chunked.
+fn asset_response(asset: &Asset, request: &ReceivedRequest) -> Response<Body> {
+    let total = asset.body.len();
+    let range_start = request
+        .headers
+        .get("range")
+        .and_then(|x| x.to_str().ok())
+        .and_then(|x| x.strip_prefix("bytes="))
+        .and_then(|x| x.strip_suffix('-'))
+        .and_then(|x| x.parse::<usize>().ok())
+        .filter(|_| !asset.chunked);
+
+    let mut builder = Response::builder().status(StatusCode::OK);
+    if let Some(content_type) = &asset.content_type {
+        builder = builder.header(CONTENT_TYPE, content_type);
+    }
+    match range_start {
+        Some(start) if start >= total => {
+            return Response::builder()
+                .status(StatusCode::RANGE_NOT_SATISFIABLE)
+                .header("content-range", format!("bytes */{total}"))
+                .body(Body::empty())
+                .expect("content-range header must be valid")
+        }
+        Some(start) => {
+            return builder
+                .status(StatusCode::PARTIAL_CONTENT)
+                .header(
+                    "content-range",
+                    format!("bytes {start}-{}/{total}", total - 1),
+                )
+                .body(asset.body[start..].to_vec().into())
+                .expect("asset headers must be valid")
+        }
+        None if !asset.chunked => {
+            return builder
+                .body(asset.body.to_owned().into())
+                .expect("asset headers must be valid")
+        }
+        None => {}
+    }
+
+    let (mut sender, body) = Body::channel();
+    let chunks = asset
+        .body
+        .chunks(64 * 1024)
+        .map(hyper::body::Bytes::copy_from_slice)
+        .collect::<Vec<_>>();
+    tokio::spawn(async move {
+        for chunk in chunks {
+            if sender.send_data(chunk).await.is_err() {
+                break;
+            }
+        }
+    });
+    builder.body(body).expect("asset headers must be valid")
+}
+
+//
+fn raw(status: StatusCode, content_type: &str, body: impl Into<Body>) -> Response<Body> {
+    Response::builder()
+        .status(status)
+        .header(CONTENT_TYPE, content_type)
+        .body(body.into())
+        .expect("content type header must be valid")
+}
+
+fn json_response(status: StatusCode, body: &Value) -> Response<Body> {
+    raw(status, "application/json; charset=utf-8", body.to_string())
+}
+
+fn v1_error(status: StatusCode, code: i64, message: &str) -> Response<Body> {
+    json_response(
+        status,
+        &json!({
+            "errors": [{ "code": code, "message": message }]
+        }),
+    )
+}
+
+fn v2_problem(status: StatusCode, title: &str, detail: &str, r#type: &str) -> Response<Body> {
+    raw(
+        status,
+        "application/problem+json",
+        json!({
+            "title": title,
+            "detail": detail,
+            "type": r#type,
+            "status": status.as_u16(),
+        })
+        .to_string(),
+    )
+}
+
+fn v2_invalid_request(parameter: &str, value: Value, message: &str) -> Response<Body> {
+    raw(
+        StatusCode::BAD_REQUEST,
+        "application/problem+json",
+        json!({
+            "errors": [{
+                "parameters": { parameter: [value] },
+                "message": message,
+            }],
+            "title": "Invalid Request",
+            "detail": "One or more parameters to your request was invalid.",
+            "type": "https://api.twitter.com/2/problems/invalid-request",
+        })
+        .to_string(),
+    )
+}
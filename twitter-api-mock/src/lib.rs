@@ -0,0 +1,465 @@
+//
+pub mod cassette;
+pub mod failure;
+pub mod request;
+pub mod route;
+
+pub use cassette::{Cassette, Interaction};
+pub use failure::Failure;
+pub use request::ReceivedRequest;
+pub use route::Route;
+
+//
+mod fixtures;
+mod handler;
+mod multipart;
+mod tls;
+
+use std::{
+    convert::Infallible,
+    net::SocketAddr,
+    path::PathBuf,
+    sync::{Arc, Mutex, MutexGuard},
+};
+
+use hyper::{server::conn::Http, service::service_fn, Body, Method, Request, Response, StatusCode};
+use tokio::{net::TcpListener, sync::oneshot};
+use tokio_rustls::TlsAcceptor;
+
+use crate::{
+    cassette::Mode,
+    handler::{Asset, Options, State},
+};
+
+//
+#[derive(Debug, Default, Clone)]
+pub struct MockServerBuilder {
+    options: Options,
+    cassette: Option<CassetteMode>,
+    upstream: Option<reqwest::Client>,
+}
+
+#[derive(Debug, Clone)]
+enum CassetteMode {
+    Record(PathBuf),
+    Replay(PathBuf),
+}
+
+pub const RECORD_ENV: &str = "TWITTER_API_MOCK_RECORD";
+
+impl MockServerBuilder {
+    // Number of STATUS checks before async media processing succeeds, 0 makes FINALIZE synchronous.
+    pub fn processing_steps(mut self, processing_steps: usize) -> Self {
+        self.options.processing_steps = processing_steps;
+        self
+    }
+
+    pub fn check_after_secs(mut self, check_after_secs: usize) -> Self {
+        self.options.check_after_secs = check_after_secs;
+        self
+    }
+
+    // Forwards every request to the real API and writes the redacted interactions to `path`.
+    pub fn record(mut self, path: impl Into<PathBuf>) -> Self {
+        self.cassette = Some(CassetteMode::Record(path.into()));
+        self
+    }
+
+    // Answers every request from the cassette at `path`, scripted failures are not applied.
+    pub fn replay(mut self, path: impl Into<PathBuf>) -> Self {
+        self.cassette = Some(CassetteMode::Replay(path.into()));
+        self
+    }
+
+    // Records when the cassette is missing or `TWITTER_API_MOCK_RECORD` is set, replays otherwise.
+    pub fn cassette(self, path: impl Into<PathBuf>) -> Self {
+        let path = path.into();
+        if !path.exists() || std::env::var_os(RECORD_ENV).is_some() {
+            self.record(path)
+        } else {
+            self.replay(path)
+        }
+    }
+
+    // The client used to reach the real API in record mode.
+    pub fn upstream(mut self, client: reqwest::Client) -> Self {
+        self.upstream = Some(client);
+        self
+    }
+
+    pub async fn start(self) -> Result<MockServer, MockServerError> {
+        let mode = match self.cassette {
+            None => Mode::Mock,
+            Some(CassetteMode::Record(path)) => Mode::Record {
+                path,
+                cassette: Cassette::default(),
+                upstream: match self.upstream {
+                    Some(x) => x,
+                    None => reqwest::Client::builder()
+                        .no_proxy()
+                        .build()
+                        .map_err(MockServerError::MakeUpstreamClientFailed)?,
+                },
+            },
+            Some(CassetteMode::Replay(path)) => {
+                let cassette = Cassette::load(path).map_err(MockServerError::LoadCassetteFailed)?;
+                Mode::Replay {
+                    used: vec![false; cassette.interactions.len()],
+                    cassette,
+                }
+            }
+        };
+
+        MockServer::start_with_state(State::new(self.options, mode)).await
+    }
+}
+
+//
+#[derive(Debug)]
+pub struct MockServer {
+    addr: SocketAddr,
+    cert_pem: String,
+    state: Arc<Mutex<State>>,
+    shutdown_tx: Option<oneshot::Sender<()>>,
+}
+
+impl MockServer {
+    pub fn builder() -> MockServerBuilder {
+        MockServerBuilder::default()
+    }
+
+    pub async fn start() -> Result<Self, MockServerError> {
+        Self::builder().start().await
+    }
+
+    async fn start_with_state(state: State) -> Result<Self, MockServerError> {
+        let (acceptor, cert_pem) = tls::acceptor()?;
+
+        let listener = TcpListener::bind("127.0.0.1:0")
+            .await
+            .map_err(MockServerError::BindFailed)?;
+        let addr = listener.local_addr().map_err(MockServerError::BindFailed)?;
+
+        let state = Arc::new(Mutex::new(state));
+        let (shutdown_tx, mut shutdown_rx) = oneshot::channel::<()>();
+
+        let state_for_accept = state.clone();
+        tokio::spawn(async move {
+            loop {
+                tokio::select! {
+                    ret = listener.accept() => {
+                        let (stream, _) = match ret {
+                            Ok(x) => x,
+                            Err(_) => continue,
+                        };
+                        let state = state_for_accept.clone();
+                        let acceptor = acceptor.clone();
+                        tokio::spawn(async move {
+                            let service = service_fn(move |req| {
+                                serve_proxy(req, state.clone(), acceptor.clone())
+                            });
+                            let _ = Http::new()
+                                .http1_only(true)
+                                .serve_connection(stream, service)
+                                .with_upgrades()
+                                .await;
+                        });
+                    }
+                    _ = &mut shutdown_rx => break,
+                }
+            }
+        });
+
+        Ok(Self {
+            addr,
+            cert_pem,
+            state,
+            shutdown_tx: Some(shutdown_tx),
+        })
+    }
+
+    pub fn addr(&self) -> SocketAddr {
+        self.addr
+    }
+
+    pub fn proxy_url(&self) -> String {
+        format!("http://{}", self.addr)
+    }
+
+    // The CA the certificate of every proxied host is signed with.
+    pub fn certificate_pem(&self) -> &str {
+        &self.cert_pem
+    }
+
+    pub fn client_builder(&self) -> reqwest::ClientBuilder {
+        reqwest::Client::builder()
+            .proxy(reqwest::Proxy::all(self.proxy_url()).expect("invalid proxy url"))
+            .add_root_certificate(
+                reqwest::Certificate::from_pem(self.cert_pem.as_bytes())
+                    .expect("invalid certificate"),
+            )
+    }
+
+    pub fn client(&self) -> reqwest::Client {
+        self.client_builder()
+            .build()
+            .expect("failed to build client")
+    }
+
+    // For endpoints rendered as http requests, e.g. the ones from oauth1-twitter.
+    pub async fn send(
+        &self,
+        request: Request<Vec<u8>>,
+    ) -> Result<Response<Vec<u8>>, reqwest::Error> {
+        let (parts, body) = request.into_parts();
+
+        let response = self
+            .client()
+            .request(parts.method, parts.uri.to_string())
+            .headers(parts.headers)
+            .body(body)
+            .send()
+            .await?;
+
+        let mut builder = Response::builder().status(response.status());
+        if let Some(headers) = builder.headers_mut() {
+            *headers = response.headers().to_owned();
+        }
+        let body = response.bytes().await?;
+
+        Ok(builder
+            .body(body.to_vec())
+            .expect("response headers from reqwest must be valid"))
+    }
+
+    //
+    pub fn fail_next(&self, route: Route, failure: Failure) {
+        self.fail_times(route, failure, 1)
+    }
+
+    pub fn fail_times(&self, route: Route, failure: Failure, times: usize) {
+        let mut state = lock(&self.state);
+        let failures = state.failures.entry(route).or_default();
+        failures.extend(std::iter::repeat_n(failure, times));
+    }
+
+    // Serves `body` at `url` on any host, for sources the media is uploaded from.
+    pub fn serve_asset(&self, url: &str, content_type: Option<&str>, body: impl Into<Vec<u8>>) {
+        self.insert_asset(url, content_type, body.into(), false)
+    }
+
+    // Like `serve_asset`, without Content-Length.
+    pub fn serve_asset_chunked(
+        &self,
+        url: &str,
+        content_type: Option<&str>,
+        body: impl Into<Vec<u8>>,
+    ) {
+        self.insert_asset(url, content_type, body.into(), true)
+    }
+
+    fn insert_asset(&self, url: &str, content_type: Option<&str>, body: Vec<u8>, chunked: bool) {
+        let url = url::Url::parse(url).expect("invalid asset url");
+        lock(&self.state).assets.insert(
+            (
+                url.host_str().unwrap_or_default().to_owned(),
+                url.path().to_owned(),
+            ),
+            Asset {
+                content_type: content_type.map(|x| x.to_owned()),
+                body,
+                chunked,
+            },
+        );
+    }
+
+    //
+    pub fn received_requests(&self) -> Vec<ReceivedRequest> {
+        lock(&self.state).requests.to_owned()
+    }
+
+    pub fn received_requests_for(&self, route: Route) -> Vec<ReceivedRequest> {
+        lock(&self.state)
+            .requests
+            .iter()
+            .filter(|x| x.route == Some(route))
+            .cloned()
+            .collect()
+    }
+
+    #[track_caller]
+    pub fn assert_received(&self, route: Route, times: usize) {
+        let n = self.received_requests_for(route).len();
+        assert_eq!(
+            n, times,
+            "expected {times} request(s) for {route:?}, received {n}"
+        );
+    }
+
+    pub fn clear_received_requests(&self) {
+        lock(&self.state).requests.clear();
+    }
+
+    //
+    // Interactions of the replayed cassette that no request matched yet.
+    pub fn unused_interactions(&self) -> Vec<Interaction> {
+        lock(&self.state).mode.unused_interactions()
+    }
+
+    #[track_caller]
+    pub fn assert_cassette_exhausted(&self) {
+        let unused = self.unused_interactions();
+        assert!(
+            unused.is_empty(),
+            "{} interaction(s) not replayed, first: {} {}",
+            unused.len(),
+            unused[0].request.method,
+            unused[0].request.url
+        );
+    }
+}
+
+impl Drop for MockServer {
+    fn drop(&mut self) {
+        if let Some(shutdown_tx) = self.shutdown_tx.take() {
+            let _ = shutdown_tx.send(());
+        }
+    }
+}
+
+//
+fn lock(state: &Mutex<State>) -> MutexGuard<'_, State> {
+    state.lock().expect("mock server state poisoned")
+}
+
+async fn serve_proxy(
+    req: Request<Body>,
+    state: Arc<Mutex<State>>,
+    acceptor: TlsAcceptor,
+) -> Result<Response<Body>, Infallible> {
+    if req.method() == Method::CONNECT {
+        let host = match req.uri().host() {
+            Some(x) => x.to_owned(),
+            None => {
+                return Ok(Response::builder()
+                    .status(StatusCode::BAD_REQUEST)
+                    .body(Body::empty())
+                    .expect("static response must be valid"))
+            }
+        };
+
+        tokio::spawn(async move {
+            let upgraded = match hyper::upgrade::on(req).await {
+                Ok(x) => x,
+                Err(_) => return,
+            };
+            let stream = match acceptor.accept(upgraded).await {
+                Ok(x) => x,
+                Err(_) => return,
+            };
+            let service = service_fn(move |req| serve(req, host.clone(), state.clone()));
+            let _ = Http::new()
+                .http1_only(true)
+                .serve_connection(stream, service)
+                .await;
+        });
+
+        return Ok(Response::new(Body::empty()));
+    }
+
+    // Plain http through the proxy, the uri is in absolute-form.
+    let host = req.uri().host().unwrap_or_default().to_owned();
+    serve(req, host, state).await
+}
+
+async fn serve(
+    req: Request<Body>,
+    host: String,
+    state: Arc<Mutex<State>>,
+) -> Result<Response<Body>, Infallible> {
+    let (parts, body) = req.into_parts();
+    let body = hyper::body::to_bytes(body)
+        .await
+        .map(|x| x.to_vec())
+        .unwrap_or_default();
+
+    let mut request = ReceivedRequest {
+        route: None,
+        method: parts.method,
+        host,
+        path: parts.uri.path().to_owned(),
+        query: parts
+            .uri
+            .query()
+            .map(|x| {
+                url::form_urlencoded::parse(x.as_bytes())
+                    .into_owned()
+                    .collect()
+            })
+            .unwrap_or_default(),
+        headers: parts.headers,
+        body,
+    };
+    request.route = Route::recognize(
+        &request.method,
+        &request.host,
+        &request.path,
+        request.param("command").as_deref(),
+    );
+
+    let upstream = match &lock(&state).mode {
+        Mode::Record { upstream, .. } => Some(upstream.to_owned()),
+        _ => None,
+    };
+
+    let response = match upstream {
+        Some(upstream) => match cassette::forward(&upstream, &request).await {
+            Ok((response, interaction)) => {
+                let mut state = lock(&state);
+                if let Mode::Record { path, cassette, .. } = &mut state.mode {
+                    cassette.interactions.push(interaction);
+                    match cassette.save(path) {
+                        Ok(()) => response,
+                        Err(err) => Response::builder()
+                            .status(StatusCode::INTERNAL_SERVER_ERROR)
+                            .body(format!("save cassette failed, err: {err}").into())
+                            .expect("static response must be valid"),
+                    }
+                } else {
+                    response
+                }
+            }
+            Err(err) => Response::builder()
+                .status(StatusCode::BAD_GATEWAY)
+                .body(err.to_string().into())
+                .expect("static response must be valid"),
+        },
+        None => {
+            let mut state = lock(&state);
+            let state = &mut *state;
+            match &mut state.mode {
+                Mode::Replay { cassette, used } => cassette::replay(cassette, used, &request),
+                _ => handler::respond(state, &request),
+            }
+        }
+    };
+
+    lock(&state).requests.push(request);
+
+    Ok(response)
+}
+
+//
+#[derive(Debug)]
+pub enum MockServerError {
+    MakeCertificateFailed(String),
+    BindFailed(std::io::Error),
+    MakeUpstreamClientFailed(reqwest::Error),
+    LoadCassetteFailed(std::io::Error),
+}
+impl core::fmt::Display for MockServerError {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        write!(f, "{self:?}")
+    }
+}
+impl std::error::Error for MockServerError {}
@@ -0,0 +1,69 @@
+// Just enough of multipart/form-data to read what reqwest sends.
+pub(crate) fn parse(content_type: &str, body: &[u8]) -> Vec<(String, Vec<u8>)> {
+    let mut parts = vec![];
+
+    let boundary = match content_type
+        .split(';')
+        .map(|x| x.trim())
+        .find_map(|x| x.strip_prefix("boundary="))
+    {
+        Some(x) => x.trim_matches('"'),
+        None => return parts,
+    };
+    let delimiter = format!("--{boundary}");
+    let delimiter_with_crlf = format!("\r\n{delimiter}");
+
+    let mut rest = match find(body, delimiter.as_bytes()) {
+        Some(i) => &body[i + delimiter.len()..],
+        None => return parts,
+    };
+
+    while !rest.starts_with(b"--") {
+        let part = rest.strip_prefix(b"\r\n").unwrap_or(rest);
+
+        let headers_end = match find(part, b"\r\n\r\n") {
+            Some(i) => i,
+            None => break,
+        };
+        let headers = String::from_utf8_lossy(&part[..headers_end]);
+        let content = &part[headers_end + 4..];
+
+        let content_end = match find(content, delimiter_with_crlf.as_bytes()) {
+            Some(i) => i,
+            None => break,
+        };
+
+        let name = headers
+            .split(';')
+            .map(|x| x.trim())
+            .find_map(|x| x.strip_prefix("name="))
+            .map(|x| x.trim_matches('"').to_owned())
+            .unwrap_or_default();
+        parts.push((name, content[..content_end].to_owned()));
+
+        rest = &content[content_end + delimiter_with_crlf.len()..];
+    }
+
+    parts
+}
+
+fn find(haystack: &[u8], needle: &[u8]) -> Option<usize> {
+    haystack
+        .windows(needle.len())
+        .position(|window| window == needle)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse() {
+        let body = b"--abc\r\nContent-Disposition: form-data; name=\"media\"\r\n\r\n\x00\x01\r\n--x\r\n--abc\r\nContent-Disposition: form-data; name=\"segment_index\"\r\n\r\n1\r\n--abc--\r\n";
+        let parts = parse("multipart/form-data; boundary=abc", body);
+        assert_eq!(parts.len(), 2);
+        assert_eq!(parts[0].0, "media");
+        assert_eq!(parts[0].1, b"\x00\x01\r\n--x");
+        assert_eq!(parts[1], ("segment_index".to_owned(), b"1".to_vec()));
+    }
+}
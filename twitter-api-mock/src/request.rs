@@ -0,0 +1,81 @@
+use hyper::{header::CONTENT_TYPE, HeaderMap, Method};
+use serde_json::Value;
+
+use crate::{multipart, route::Route};
+
+//
+#[derive(Debug, Clone)]
+pub struct ReceivedRequest {
+    pub route: Option<Route>,
+    pub method: Method,
+    pub host: String,
+    pub path: String,
+    pub query: Vec<(String, String)>,
+    pub headers: HeaderMap,
+    pub body: Vec<u8>,
+}
+
+impl ReceivedRequest {
+    pub fn query_param(&self, name: &str) -> Option<&str> {
+        self.query
+            .iter()
+            .find(|(k, _)| k == name)
+            .map(|(_, v)| v.as_str())
+    }
+
+    pub fn content_type(&self) -> Option<&str> {
+        self.headers.get(CONTENT_TYPE).and_then(|x| x.to_str().ok())
+    }
+
+    pub fn authorization(&self) -> Option<&str> {
+        self.headers
+            .get("authorization")
+            .and_then(|x| x.to_str().ok())
+    }
+
+    pub fn is_oauth1_signed(&self) -> bool {
+        self.authorization()
+            .map(|x| x.starts_with("OAuth ") && x.contains("oauth_signature="))
+            == Some(true)
+    }
+
+    pub fn form(&self) -> Vec<(String, String)> {
+        match self.content_type() {
+            Some(x) if x.starts_with("application/x-www-form-urlencoded") => {
+                serde_urlencoded::from_bytes(&self.body).unwrap_or_default()
+            }
+            _ => vec![],
+        }
+    }
+
+    pub fn form_param(&self, name: &str) -> Option<String> {
+        self.form()
+            .into_iter()
+            .find(|(k, _)| k == name)
+            .map(|(_, v)| v)
+    }
+
+    pub fn json(&self) -> Option<Value> {
+        match self.content_type() {
+            Some(x) if x.starts_with("application/json") => serde_json::from_slice(&self.body).ok(),
+            _ => None,
+        }
+    }
+
+    pub fn multipart_part(&self, name: &str) -> Option<Vec<u8>> {
+        match self.content_type() {
+            Some(x) if x.starts_with("multipart/form-data") => multipart::parse(x, &self.body)
+                .into_iter()
+                .find(|(k, _)| k == name)
+                .map(|(_, v)| v),
+            _ => None,
+        }
+    }
+
+    // The media upload endpoint takes `command` either in the query or in the form.
+    pub fn param(&self, name: &str) -> Option<String> {
+        self.query_param(name)
+            .map(|x| x.to_owned())
+            .or_else(|| self.form_param(name))
+    }
+}
@@ -0,0 +1,113 @@
+use hyper::Method;
+
+//
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[non_exhaustive]
+pub enum Route {
+    OauthRequestToken,
+    OauthAccessToken,
+    OauthInvalidateToken,
+    //
+    MediaUpload,
+    MediaUploadInit,
+    MediaUploadAppend,
+    MediaUploadFinalize,
+    MediaUploadStatus,
+    MediaMetadataCreate,
+    MediaSubtitlesCreate,
+    MediaSubtitlesDelete,
+    V2MediaUploadInitialize,
+    V2MediaUploadAppend,
+    V2MediaUploadFinalize,
+    V2MediaUploadStatus,
+    V2MediaMetadataCreate,
+    //
+    TweetsCreate,
+    TweetsDelete,
+    TweetsLookup,
+    TweetsLookupById,
+    UsersLookupById,
+    UsersLookupByUsername,
+}
+
+impl Route {
+    pub(crate) fn recognize(
+        method: &Method,
+        host: &str,
+        path: &str,
+        command: Option<&str>,
+    ) -> Option<Self> {
+        let segments = path.trim_matches('/').split('/').collect::<Vec<_>>();
+
+        match (host, method, segments.as_slice()) {
+            ("api.twitter.com", &Method::POST, ["oauth", "request_token"]) => {
+                Some(Self::OauthRequestToken)
+            }
+            ("api.twitter.com", &Method::POST, ["oauth", "access_token"]) => {
+                Some(Self::OauthAccessToken)
+            }
+            ("api.twitter.com", &Method::POST, ["1.1", "oauth", "invalidate_token"]) => {
+                Some(Self::OauthInvalidateToken)
+            }
+            //
+            ("upload.twitter.com", method, ["1.1", "media", "upload.json"]) => {
+                match (method, command) {
+                    (&Method::POST, None) => Some(Self::MediaUpload),
+                    (&Method::POST, Some("INIT")) => Some(Self::MediaUploadInit),
+                    (&Method::POST, Some("APPEND")) => Some(Self::MediaUploadAppend),
+                    (&Method::POST, Some("FINALIZE")) => Some(Self::MediaUploadFinalize),
+                    (&Method::GET, Some("STATUS")) => Some(Self::MediaUploadStatus),
+                    _ => None,
+                }
+            }
+            ("upload.twitter.com", &Method::POST, ["1.1", "media", "metadata", "create.json"]) => {
+                Some(Self::MediaMetadataCreate)
+            }
+            ("upload.twitter.com", &Method::POST, ["1.1", "media", "subtitles", "create.json"]) => {
+                Some(Self::MediaSubtitlesCreate)
+            }
+            ("upload.twitter.com", &Method::POST, ["1.1", "media", "subtitles", "delete.json"]) => {
+                Some(Self::MediaSubtitlesDelete)
+            }
+            ("api.twitter.com", &Method::POST, ["2", "media", "upload", "initialize"]) => {
+                Some(Self::V2MediaUploadInitialize)
+            }
+            ("api.twitter.com", &Method::POST, ["2", "media", "upload", id, "append"])
+                if id.parse::<u64>().is_ok() =>
+            {
+                Some(Self::V2MediaUploadAppend)
+            }
+            ("api.twitter.com", &Method::POST, ["2", "media", "upload", id, "finalize"])
+                if id.parse::<u64>().is_ok() =>
+            {
+                Some(Self::V2MediaUploadFinalize)
+            }
+            ("api.twitter.com", &Method::GET, ["2", "media", "upload"])
+                if command == Some("STATUS") =>
+            {
+                Some(Self::V2MediaUploadStatus)
+            }
+            ("api.twitter.com", &Method::POST, ["2", "media", "metadata"]) => {
+                Some(Self::V2MediaMetadataCreate)
+            }
+            //
+            ("api.twitter.com", &Method::POST, ["2", "tweets"]) => Some(Self::TweetsCreate),
+            ("api.twitter.com", &Method::GET, ["2", "tweets"]) => Some(Self::TweetsLookup),
+            ("api.twitter.com", &Method::GET, ["2", "tweets", id]) if id.parse::<u64>().is_ok() => {
+                Some(Self::TweetsLookupById)
+            }
+            ("api.twitter.com", &Method::DELETE, ["2", "tweets", id])
+                if id.parse::<u64>().is_ok() =>
+            {
+                Some(Self::TweetsDelete)
+            }
+            ("api.twitter.com", &Method::GET, ["2", "users", "by", "username", _]) => {
+                Some(Self::UsersLookupByUsername)
+            }
+            ("api.twitter.com", &Method::GET, ["2", "users", id]) if id.parse::<u64>().is_ok() => {
+                Some(Self::UsersLookupById)
+            }
+            _ => None,
+        }
+    }
+}
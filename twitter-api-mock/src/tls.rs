@@ -0,0 +1,93 @@
+use std::{
+    collections::HashMap,
+    sync::{Arc, Mutex},
+};
+
+use rcgen::{
+    BasicConstraints, Certificate as RcgenCertificate, CertificateParams, DnType, IsCa,
+    KeyUsagePurpose,
+};
+use tokio_rustls::{
+    rustls::{
+        server::{ClientHello, ResolvesServerCert},
+        sign::{any_supported_type, CertifiedKey},
+        Certificate, PrivateKey, ServerConfig,
+    },
+    TlsAcceptor,
+};
+
+use crate::MockServerError;
+
+//
+// Used when the client sends no SNI, e.g. when connecting to an IP.
+const DEFAULT_HOST: &str = "localhost";
+
+// A CA made for this server only, leaf certificates are signed per SNI host on demand so assets
+// can be served from any host while clients trust nothing but this CA.
+pub(crate) fn acceptor() -> Result<(TlsAcceptor, String), MockServerError> {
+    let mut params = CertificateParams::new(vec![]);
+    params.is_ca = IsCa::Ca(BasicConstraints::Unconstrained);
+    params
+        .distinguished_name
+        .push(DnType::CommonName, "twitter-api-mock CA");
+    params.key_usages = vec![
+        KeyUsagePurpose::KeyCertSign,
+        KeyUsagePurpose::CrlSign,
+        KeyUsagePurpose::DigitalSignature,
+    ];
+    let ca = RcgenCertificate::from_params(params).map_err(make_certificate_failed)?;
+    let ca_pem = ca.serialize_pem().map_err(make_certificate_failed)?;
+
+    let resolver = HostCertResolver {
+        ca,
+        certified_keys: Mutex::new(HashMap::new()),
+    };
+    // Fail at start rather than on the first handshake.
+    resolver.certified_key(DEFAULT_HOST)?;
+
+    let mut config = ServerConfig::builder()
+        .with_safe_defaults()
+        .with_no_client_auth()
+        .with_cert_resolver(Arc::new(resolver));
+    config.alpn_protocols = vec![b"http/1.1".to_vec()];
+
+    Ok((TlsAcceptor::from(Arc::new(config)), ca_pem))
+}
+
+struct HostCertResolver {
+    ca: RcgenCertificate,
+    certified_keys: Mutex<HashMap<String, Arc<CertifiedKey>>>,
+}
+
+impl HostCertResolver {
+    fn certified_key(&self, host: &str) -> Result<Arc<CertifiedKey>, MockServerError> {
+        let mut certified_keys = self.certified_keys.lock().expect("certified keys poisoned");
+        if let Some(certified_key) = certified_keys.get(host) {
+            return Ok(certified_key.clone());
+        }
+
+        let mut params = CertificateParams::new(vec![host.to_owned()]);
+        params.distinguished_name.push(DnType::CommonName, host);
+        let cert = RcgenCertificate::from_params(params).map_err(make_certificate_failed)?;
+        let cert_der = cert
+            .serialize_der_with_signer(&self.ca)
+            .map_err(make_certificate_failed)?;
+        let key = any_supported_type(&PrivateKey(cert.serialize_private_key_der()))
+            .map_err(make_certificate_failed)?;
+
+        let certified_key = Arc::new(CertifiedKey::new(vec![Certificate(cert_der)], key));
+        certified_keys.insert(host.to_owned(), certified_key.clone());
+        Ok(certified_key)
+    }
+}
+
+impl ResolvesServerCert for HostCertResolver {
+    fn resolve(&self, client_hello: ClientHello) -> Option<Arc<CertifiedKey>> {
+        self.certified_key(client_hello.server_name().unwrap_or(DEFAULT_HOST))
+            .ok()
+    }
+}
+
+fn make_certificate_failed(err: impl ToString) -> MockServerError {
+    MockServerError::MakeCertificateFailed(err.to_string())
+}
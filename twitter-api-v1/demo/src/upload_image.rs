@@ -1,12 +1,13 @@
 /*
-RUST_BACKTRACE=1 RUST_LOG=trace cargo run -p twitter-api-v1-demo --bin twitter_api_v1_demo_upload_image -- 'YOUR_CONSUMER_KEY' 'YOUR_CONSUMER_SECRET' 'YOUR_ACCESS_TOKEN' 'YOUR_ACCESS_TOKEN_SECRET' '/path/x.jpg' 'Hello'
+RUST_BACKTRACE=1 RUST_LOG=trace cargo run -p twitter-api-v1-demo --bin twitter_api_v1_demo_upload_image -- 'YOUR_CONSUMER_KEY' 'YOUR_CONSUMER_SECRET' 'YOUR_ACCESS_TOKEN' 'YOUR_ACCESS_TOKEN_SECRET' '/path/x.jpg' 'Hello' 'A cat on a sofa'
 */
 
 use std::env;
 
 use twitter_api_v1::{
     endpoints::{
-        media::upload_media::upload_image_from_file, tweets::manage_tweets::create_tweet,
+        media::upload_media::{upload_image_from_file, upload_image_from_file_with_alt_text},
+        tweets::manage_tweets::create_tweet,
         EndpointRet,
     },
     objects::MediaCategory,
@@ -35,6 +36,7 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
         .ok_or_else(|| "arg file_path missing".to_string())?
         .parse()?;
     let tweet_text = env::args().nth(6);
+    let alt_text = env::args().nth(7);
 
     //
     let token_secrets = TokenSecrets::new(
@@ -47,13 +49,29 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
     //
     // upload
     //
-    let ret = upload_image_from_file(
-        &token_secrets,
-        reqwest::Client::new(),
-        MediaCategory::TweetImage,
-        &file_path,
-    )
-    .await?;
+    let ret = match alt_text {
+        Some(alt_text) => upload_image_from_file_with_alt_text(
+            &token_secrets,
+            reqwest::Client::new(),
+            MediaCategory::TweetImage,
+            &file_path,
+            &alt_text,
+        )
+        .await?
+        .map(|x| {
+            println!("create_media_metadata:{:?}", x.metadata);
+            x.upload
+        }),
+        None => {
+            upload_image_from_file(
+                &token_secrets,
+                reqwest::Client::new(),
+                MediaCategory::TweetImage,
+                &file_path,
+            )
+            .await?
+        }
+    };
     let media_id = match ret {
         EndpointRet::Ok(ok_json) => {
             println!("upload_image:{ok_json:?}");
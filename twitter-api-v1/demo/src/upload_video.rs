@@ -6,14 +6,14 @@ use std::env;
 
 use twitter_api_v1::{
     endpoints::{
-        media::upload_media::{
-            get_upload_status, upload_append_all_from_file, upload_finalize, upload_init,
+        media::{
+            upload::{upload_media, UploadOptions},
+            upload_session::AppendOptions,
         },
         tweets::manage_tweets::create_tweet,
-        EndpointRet,
     },
-    objects::{MediaCategory, MediaProcessingInfoState},
-    TokenSecrets,
+    objects::MediaCategory,
+    IntoResult as _, TokenSecrets,
 };
 
 #[tokio::main]
@@ -47,99 +47,27 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
         oauth_token_secret,
     );
 
-    let twitter_api_v1::tokio_fs_util::Info {
-        file_size,
-        file_name: _,
-    } = twitter_api_v1::tokio_fs_util::info(&file_path).await?;
-
     //
-    // upload INIT
+    // upload INIT -> APPEND -> FINALIZE -> STATUS
     //
-    let ret = upload_init(
+    let ok_json = upload_media(
         &token_secrets,
         reqwest::Client::builder()
             .connection_verbose(true)
             .build()?,
-        file_size as usize,
+        &file_path,
         "video/mp4",
         MediaCategory::TweetVideo,
+        &UploadOptions::default().append(AppendOptions::default().concurrency(2).max_retries(2)),
     )
     .await?;
-    let media_id = match ret {
-        EndpointRet::Ok(ok_json) => {
-            println!("upload_init:{ok_json:?}");
-            ok_json.media_id
-        }
-        x => panic!("{x:?}"),
-    };
-
-    //
-    // upload APPEND
-    //
-    match upload_append_all_from_file(&token_secrets, reqwest::Client::new(), media_id, &file_path)
-        .await
-    {
-        Ok(Ok(_)) => {}
-        Ok(Err(ret)) => panic!("{ret:?}"),
-        Err(err) => panic!("{err:?}"),
-    }
-
-    //
-    // upload FINALIZE
-    //
-    let ret = upload_finalize(
-        &token_secrets,
-        reqwest::Client::builder()
-            .connection_verbose(true)
-            .build()?,
-        media_id,
-    )
-    .await?;
-    let processing_info = match ret {
-        EndpointRet::Ok(ok_json) => {
-            println!("upload_finalize:{ok_json:?}");
-            ok_json.processing_info
-        }
-        x => panic!("{x:?}"),
-    };
-
-    if processing_info.is_some() {
-        //
-        // upload STATUS
-        //
-        loop {
-            tokio::time::sleep(tokio::time::Duration::from_secs(5)).await;
-
-            let ret = get_upload_status(
-                &token_secrets,
-                reqwest::Client::builder()
-                    .connection_verbose(true)
-                    .build()?,
-                media_id,
-            )
-            .await?;
-            let processing_info = match ret {
-                EndpointRet::Ok(ok_json) => {
-                    println!("get_upload_status:{ok_json:?}");
-                    ok_json.processing_info
-                }
-                x => panic!("{x:?}"),
-            };
-
-            if let Some(processing_info) = processing_info {
-                match processing_info.state {
-                    MediaProcessingInfoState::Succeeded => break,
-                    MediaProcessingInfoState::Failed => panic!("{processing_info:?}"),
-                    _ => {}
-                }
-            }
-        }
-    }
+    println!("upload_media:{ok_json:?}");
+    let media_id = ok_json.media_id;
 
     //
     // tweets
     //
-    let ret = create_tweet(
+    let ok_json = create_tweet(
         &token_secrets,
         reqwest::Client::builder()
             .connection_verbose(true)
@@ -148,13 +76,9 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
         Some(vec![media_id]),
         None,
     )
-    .await?;
-    match ret {
-        EndpointRet::Ok(ok_json) => {
-            println!("create_tweet:{ok_json:?}");
-        }
-        x => panic!("{x:?}"),
-    };
+    .await
+    .into_result()?;
+    println!("create_tweet:{ok_json:?}");
 
     Ok(())
 }
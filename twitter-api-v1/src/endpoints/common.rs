@@ -1,6 +1,13 @@
-use reqwest::{Error as ReqwestError, StatusCode};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use reqwest::{header::HeaderMap, Error as ReqwestError, StatusCode};
 use reqwest_oauth1::Error as ReqwestOauth1Error;
+use serde::de::DeserializeOwned;
 use serde_json::Error as SerdeJsonError;
+use twitter_api_v2::{
+    endpoints::common::{ResponseBody as V2ResponseBody, ResponseBodyIncludes, ResponseBodyMeta},
+    objects::PartialError,
+};
 
 use crate::objects::ResponseBodyErrJson;
 
@@ -10,6 +17,108 @@ use crate::objects::ResponseBodyErrJson;
 pub enum EndpointRet<T> {
     Ok(T),
     Other((StatusCode, Result<ResponseBodyErrJson, Vec<u8>>)),
+    RateLimited((Option<RateLimit>, Result<ResponseBodyErrJson, Vec<u8>>)),
+    // A v2 200 response with `errors` and no `data`, e.g. the requested resource was not found.
+    PartialErrors(ResponseBodyErrJson),
+}
+
+impl<T> EndpointRet<T> {
+    pub fn map<U>(self, f: impl FnOnce(T) -> U) -> EndpointRet<U> {
+        match self {
+            Self::Ok(x) => EndpointRet::Ok(f(x)),
+            Self::Other(x) => EndpointRet::Other(x),
+            Self::RateLimited(x) => EndpointRet::RateLimited(x),
+            Self::PartialErrors(x) => EndpointRet::PartialErrors(x),
+        }
+    }
+
+    pub fn try_map<U, E>(self, f: impl FnOnce(T) -> Result<U, E>) -> Result<EndpointRet<U>, E> {
+        Ok(match self {
+            Self::Ok(x) => EndpointRet::Ok(f(x)?),
+            Self::Other(x) => EndpointRet::Other(x),
+            Self::RateLimited(x) => EndpointRet::RateLimited(x),
+            Self::PartialErrors(x) => EndpointRet::PartialErrors(x),
+        })
+    }
+
+    pub(crate) fn from_err_response<E>(status: StatusCode, headers: &HeaderMap, body: &[u8]) -> Self
+    where
+        E: DeserializeOwned + Into<ResponseBodyErrJson>,
+    {
+        let body = match serde_json::from_slice::<E>(body) {
+            Ok(err_json) => Ok(err_json.into()),
+            Err(_) => Err(body.to_owned()),
+        };
+
+        match status {
+            StatusCode::TOO_MANY_REQUESTS => {
+                Self::RateLimited((RateLimit::from_headers(headers), body))
+            }
+            status => Self::Other((status, body)),
+        }
+    }
+}
+
+//
+// The data of a v2 response with what came along, e.g. the objects requested via expansions.
+#[derive(Debug, Clone)]
+pub struct V2Data<T> {
+    pub data: T,
+    pub includes: ResponseBodyIncludes,
+    pub meta: Option<ResponseBodyMeta>,
+    // Partial errors next to data, e.g. an expanded object that was not found.
+    pub errors: Vec<PartialError>,
+}
+
+impl<T> EndpointRet<V2Data<T>> {
+    pub(crate) fn from_v2_response_body(response_body: V2ResponseBody<T>) -> Self {
+        match response_body.data {
+            Some(data) => Self::Ok(V2Data {
+                data,
+                includes: response_body.includes.unwrap_or_default(),
+                meta: response_body.meta,
+                errors: response_body.errors,
+            }),
+            None => Self::PartialErrors(response_body.errors.into()),
+        }
+    }
+}
+
+//
+// https://developer.twitter.com/en/docs/twitter-api/rate-limits
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RateLimit {
+    pub limit: Option<u64>,
+    pub remaining: Option<u64>,
+    // unix timestamp in seconds
+    pub reset: u64,
+}
+
+impl RateLimit {
+    pub fn from_headers(headers: &HeaderMap) -> Option<Self> {
+        let get = |name: &str| {
+            headers
+                .get(name)
+                .and_then(|x| x.to_str().ok())
+                .and_then(|x| x.trim().parse::<u64>().ok())
+        };
+
+        Some(Self {
+            limit: get("x-rate-limit-limit"),
+            remaining: get("x-rate-limit-remaining"),
+            reset: get("x-rate-limit-reset")?,
+        })
+    }
+
+    pub fn reset_at(&self) -> SystemTime {
+        UNIX_EPOCH + Duration::from_secs(self.reset)
+    }
+
+    pub fn reset_after(&self) -> Duration {
+        self.reset_at()
+            .duration_since(SystemTime::now())
+            .unwrap_or_default()
+    }
 }
 
 //
@@ -23,6 +132,10 @@ pub enum EndpointError {
     DeV2ResponseBodyOkJsonFailed(SerdeJsonError),
     ConvertV2ResponseBodyOkJsonFailed(String),
     //
+    #[cfg(feature = "with_tokio")]
+    ReadSourceFailed(std::io::Error),
+    #[cfg(feature = "with_tokio")]
+    WriteTargetFailed(std::io::Error),
     #[cfg(feature = "with_tokio_fs")]
     GetFileInfoFailed(std::io::Error),
     #[cfg(feature = "with_tokio_fs")]
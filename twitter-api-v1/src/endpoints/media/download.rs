@@ -0,0 +1,239 @@
+use std::sync::Arc;
+
+use reqwest::{
+    header::{CONTENT_RANGE, CONTENT_TYPE, RANGE},
+    Client, StatusCode,
+};
+use tokio::io::{AsyncWrite, AsyncWriteExt as _};
+use twitter_api_v2::objects::{Media, MediaType};
+
+use crate::{endpoints::common::EndpointError, error::TwitterError};
+
+//
+#[derive(Debug, Clone)]
+#[non_exhaustive]
+pub enum DownloadProgress {
+    Started {
+        url: String,
+        // Bytes the target already had, 0 unless the server honored the Range request.
+        resumed_from: u64,
+        total_bytes: Option<u64>,
+    },
+    BytesWritten {
+        bytes_completed: u64,
+        total_bytes: Option<u64>,
+    },
+}
+
+pub type DownloadProgressCallback = Arc<dyn Fn(DownloadProgress) + Send + Sync>;
+
+#[derive(Clone, Default)]
+pub struct DownloadOptions {
+    pub progress: Option<DownloadProgressCallback>,
+}
+
+impl core::fmt::Debug for DownloadOptions {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.debug_struct("DownloadOptions")
+            .field("progress", &self.progress.is_some())
+            .finish()
+    }
+}
+
+impl DownloadOptions {
+    pub fn progress(mut self, progress: DownloadProgressCallback) -> Self {
+        self.progress = Some(progress);
+        self
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct DownloadedMedia {
+    pub url: String,
+    // None when the target was already complete.
+    pub content_type: Option<String>,
+    pub total_bytes: Option<u64>,
+    pub bytes_written: u64,
+    pub resumed: bool,
+}
+
+//
+// Photos are fetched with name=orig, videos and GIFs as their highest bit rate MP4 variant.
+// `offset` is what `writer` already holds, the rest is requested with a Range header.
+// When the server ignores the range the first `offset` bytes of the body are skipped.
+pub async fn download_media_to_writer(
+    client: Client,
+    media: &Media,
+    writer: &mut (dyn AsyncWrite + Unpin + Send),
+    offset: u64,
+    options: &DownloadOptions,
+) -> Result<DownloadedMedia, TwitterError> {
+    use futures_util::StreamExt as _;
+
+    let url = media
+        .original_url()
+        .ok_or_else(|| TwitterError::Validation("media has no downloadable url".into()))?;
+    let content_type_prefix = match media.r#type {
+        Some(MediaType::Photo) => "image/",
+        _ => "video/mp4",
+    };
+    let progress = options.progress.as_ref();
+
+    //
+    let mut request = client.get(&url);
+    if offset > 0 {
+        request = request.header(RANGE, format!("bytes={offset}-"));
+    }
+    let response = request.send().await.map_err(source_err)?;
+
+    let response_status = response.status();
+    if response_status == StatusCode::RANGE_NOT_SATISFIABLE && offset > 0 {
+        let total_bytes = content_range_total(response.headers());
+        if total_bytes == Some(offset) {
+            return Ok(DownloadedMedia {
+                url,
+                content_type: None,
+                total_bytes,
+                bytes_written: 0,
+                resumed: true,
+            });
+        }
+    }
+    if !response_status.is_success() {
+        return Err(source_err(format!(
+            "source responded with {response_status}"
+        )));
+    }
+
+    let content_type = response
+        .headers()
+        .get(CONTENT_TYPE)
+        .and_then(|x| x.to_str().ok())
+        .unwrap_or_default()
+        .to_owned();
+    if !content_type.starts_with(content_type_prefix) {
+        return Err(TwitterError::Validation(format!(
+            "content_type {content_type:?} does not match {}",
+            media
+                .r#type
+                .as_ref()
+                .map(|x| x.to_string())
+                .unwrap_or_default()
+        )));
+    }
+
+    let resumed = response_status == StatusCode::PARTIAL_CONTENT;
+    let total_bytes = if resumed {
+        content_range_total(response.headers())
+            .or_else(|| response.content_length().map(|x| x + offset))
+    } else {
+        response.content_length()
+    };
+    if let Some(progress) = progress {
+        progress(DownloadProgress::Started {
+            url: url.to_owned(),
+            resumed_from: if resumed { offset } else { 0 },
+            total_bytes,
+        });
+    }
+
+    //
+    let mut position = if resumed { offset } else { 0 };
+    let mut bytes_written = 0;
+    let mut stream = response.bytes_stream();
+    while let Some(chunk) = stream.next().await {
+        let chunk = chunk.map_err(source_err)?;
+        let skip = core::cmp::min(offset.saturating_sub(position), chunk.len() as u64);
+        position += skip;
+        let chunk = &chunk[skip as usize..];
+        if chunk.is_empty() {
+            continue;
+        }
+
+        writer
+            .write_all(chunk)
+            .await
+            .map_err(EndpointError::WriteTargetFailed)?;
+        position += chunk.len() as u64;
+        bytes_written += chunk.len() as u64;
+        if let Some(progress) = progress {
+            progress(DownloadProgress::BytesWritten {
+                bytes_completed: position,
+                total_bytes,
+            });
+        }
+    }
+    writer
+        .flush()
+        .await
+        .map_err(EndpointError::WriteTargetFailed)?;
+
+    match total_bytes {
+        Some(total_bytes) if position != total_bytes => Err(source_err(format!(
+            "source ended at {position} of {total_bytes} bytes"
+        ))),
+        _ => Ok(DownloadedMedia {
+            url,
+            content_type: Some(content_type),
+            total_bytes,
+            bytes_written,
+            resumed,
+        }),
+    }
+}
+
+// Appends to `file_path`, so a partial file left by an interrupted download is resumed.
+#[cfg(feature = "with_tokio_fs")]
+pub async fn download_media_to_file(
+    client: Client,
+    media: &Media,
+    file_path: &std::path::PathBuf,
+    options: &DownloadOptions,
+) -> Result<DownloadedMedia, TwitterError> {
+    let mut file = tokio::fs::OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(file_path)
+        .await
+        .map_err(EndpointError::OpenFileFailed)?;
+    let offset = file
+        .metadata()
+        .await
+        .map_err(EndpointError::GetFileInfoFailed)?
+        .len();
+
+    download_media_to_writer(client, media, &mut file, offset, options).await
+}
+
+fn source_err(err: impl Into<Box<dyn std::error::Error + Send + Sync>>) -> TwitterError {
+    EndpointError::ReadSourceFailed(std::io::Error::other(err)).into()
+}
+
+// bytes 0-99/100 or bytes */100
+fn content_range_total(headers: &reqwest::header::HeaderMap) -> Option<u64> {
+    headers
+        .get(CONTENT_RANGE)?
+        .to_str()
+        .ok()?
+        .rsplit_once('/')?
+        .1
+        .parse()
+        .ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_content_range_total() {
+        let mut headers = reqwest::header::HeaderMap::new();
+        assert_eq!(content_range_total(&headers), None);
+        headers.insert(CONTENT_RANGE, "bytes 10-99/100".parse().unwrap());
+        assert_eq!(content_range_total(&headers), Some(100));
+        headers.insert(CONTENT_RANGE, "bytes */100".parse().unwrap());
+        assert_eq!(content_range_total(&headers), Some(100));
+        headers.insert(CONTENT_RANGE, "bytes 0-9/*".parse().unwrap());
+        assert_eq!(content_range_total(&headers), None);
+    }
+}
@@ -0,0 +1,108 @@
+use reqwest::{Client, StatusCode};
+use reqwest_oauth1::OAuthClientProvider as _;
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    endpoints::common::{EndpointError, EndpointRet},
+    objects::ResponseBodyErrJson,
+    secrets::TokenSecrets,
+};
+
+//
+pub const METADATA_CREATE_URL: &str = "https://upload.twitter.com/1.1/media/metadata/create.json";
+
+pub const ALT_TEXT_LEN_MAX: usize = 1000;
+
+//
+//
+//
+pub async fn create_media_metadata(
+    secrets: &TokenSecrets,
+    client: Client,
+    media_id: u64,
+    alt_text: &str,
+) -> Result<EndpointRet<()>, EndpointError> {
+    validate_alt_text(alt_text).map_err(EndpointError::ValidateFailed)?;
+
+    //
+    let body = CreateMediaMetadataRequestBody {
+        media_id: media_id.to_string(),
+        alt_text: Some(AltText {
+            text: alt_text.into(),
+        }),
+    };
+
+    //
+    let response = client
+        .oauth1(secrets.secrets())
+        .post(METADATA_CREATE_URL)
+        .json(&body)
+        .send()
+        .await
+        .map_err(EndpointError::RespondFailed)?;
+
+    //
+    let response_status = response.status();
+    let response_headers = response.headers().to_owned();
+    let response_body = response
+        .bytes()
+        .await
+        .map_err(EndpointError::ReadResponseBodyFailed)?;
+    let response_body = response_body.as_ref();
+
+    match response_status {
+        StatusCode::OK => Ok(EndpointRet::Ok(())),
+        status => Ok(EndpointRet::from_err_response::<ResponseBodyErrJson>(
+            status,
+            &response_headers,
+            response_body,
+        )),
+    }
+}
+
+pub fn validate_alt_text(alt_text: &str) -> Result<(), String> {
+    if alt_text.trim().is_empty() {
+        return Err("alt_text is empty".into());
+    }
+    if alt_text.chars().count() > ALT_TEXT_LEN_MAX {
+        return Err(format!(
+            "alt_text too long, max {ALT_TEXT_LEN_MAX} characters"
+        ));
+    }
+    Ok(())
+}
+
+//
+#[derive(Deserialize, Serialize, Debug, Clone)]
+pub struct CreateMediaMetadataRequestBody {
+    pub media_id: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub alt_text: Option<AltText>,
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone)]
+pub struct AltText {
+    pub text: String,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_validate_alt_text() {
+        assert!(validate_alt_text("A cat").is_ok());
+        assert!(validate_alt_text(&"猫".repeat(ALT_TEXT_LEN_MAX)).is_ok());
+        assert!(validate_alt_text(&"a".repeat(ALT_TEXT_LEN_MAX + 1)).is_err());
+        assert!(validate_alt_text(" ").is_err());
+
+        assert_eq!(
+            serde_json::to_string(&CreateMediaMetadataRequestBody {
+                media_id: "1".into(),
+                alt_text: Some(AltText { text: "x".into() })
+            })
+            .unwrap(),
+            r#"{"media_id":"1","alt_text":{"text":"x"}}"#
+        );
+    }
+}
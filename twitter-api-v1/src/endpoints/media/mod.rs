@@ -1,2 +1,13 @@
 //
+#[cfg(feature = "with_tokio")]
+pub mod download;
+pub mod metadata;
+pub mod subtitles;
+#[cfg(feature = "with_tokio")]
+pub mod upload;
+#[cfg(feature = "with_tokio")]
+pub mod upload_cache;
 pub mod upload_media;
+pub mod upload_progress;
+pub mod upload_session;
+pub mod upload_v2;
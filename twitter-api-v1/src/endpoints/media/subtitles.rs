@@ -0,0 +1,224 @@
+// https://developer.twitter.com/en/docs/twitter-api/v1/media/upload-media/api-reference/post-media-subtitles-create
+
+use reqwest::{Client, StatusCode};
+use reqwest_oauth1::OAuthClientProvider as _;
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    endpoints::common::{EndpointError, EndpointRet},
+    objects::ResponseBodyErrJson,
+    secrets::TokenSecrets,
+};
+
+//
+pub const SUBTITLES_CREATE_URL: &str = "https://upload.twitter.com/1.1/media/subtitles/create.json";
+pub const SUBTITLES_DELETE_URL: &str = "https://upload.twitter.com/1.1/media/subtitles/delete.json";
+
+pub const SUBTITLES_MEDIA_CATEGORY: &str = "TweetVideo";
+
+//
+//
+//
+// The subtitle media ids must be uploaded with MediaCategory::Subtitles first.
+pub async fn create_subtitles(
+    secrets: &TokenSecrets,
+    client: Client,
+    video_media_id: u64,
+    subtitles: &[Subtitle],
+) -> Result<EndpointRet<()>, EndpointError> {
+    if subtitles.is_empty() {
+        return Err(EndpointError::ValidateFailed("subtitles is empty".into()));
+    }
+    for subtitle in subtitles {
+        subtitle.validate().map_err(EndpointError::ValidateFailed)?;
+    }
+
+    //
+    let body = SubtitlesRequestBody {
+        media_id: video_media_id.to_string(),
+        media_category: SUBTITLES_MEDIA_CATEGORY.into(),
+        subtitle_info: SubtitleInfo {
+            subtitles: subtitles.to_vec(),
+        },
+    };
+
+    send(secrets, client, SUBTITLES_CREATE_URL, &body).await
+}
+
+pub async fn delete_subtitles(
+    secrets: &TokenSecrets,
+    client: Client,
+    video_media_id: u64,
+    language_codes: &[&str],
+) -> Result<EndpointRet<()>, EndpointError> {
+    if language_codes.is_empty() {
+        return Err(EndpointError::ValidateFailed(
+            "language_codes is empty".into(),
+        ));
+    }
+    for language_code in language_codes {
+        validate_language_code(language_code).map_err(EndpointError::ValidateFailed)?;
+    }
+
+    //
+    let body = SubtitlesRequestBody {
+        media_id: video_media_id.to_string(),
+        media_category: SUBTITLES_MEDIA_CATEGORY.into(),
+        subtitle_info: SubtitleInfo {
+            subtitles: language_codes
+                .iter()
+                .map(|x| Subtitle {
+                    media_id: None,
+                    language_code: x.to_string(),
+                    display_name: None,
+                })
+                .collect(),
+        },
+    };
+
+    send(secrets, client, SUBTITLES_DELETE_URL, &body).await
+}
+
+async fn send(
+    secrets: &TokenSecrets,
+    client: Client,
+    url: &str,
+    body: &SubtitlesRequestBody,
+) -> Result<EndpointRet<()>, EndpointError> {
+    let response = client
+        .oauth1(secrets.secrets())
+        .post(url)
+        .json(body)
+        .send()
+        .await
+        .map_err(EndpointError::RespondFailed)?;
+
+    //
+    let response_status = response.status();
+    let response_headers = response.headers().to_owned();
+    let response_body = response
+        .bytes()
+        .await
+        .map_err(EndpointError::ReadResponseBodyFailed)?;
+    let response_body = response_body.as_ref();
+
+    match response_status {
+        StatusCode::OK | StatusCode::NO_CONTENT => Ok(EndpointRet::Ok(())),
+        status => Ok(EndpointRet::from_err_response::<ResponseBodyErrJson>(
+            status,
+            &response_headers,
+            response_body,
+        )),
+    }
+}
+
+// BCP47 code, a 2-3 letter primary language subtag plus optional subtags, e.g. EN, pt-BR, zh-Hant.
+pub fn validate_language_code(language_code: &str) -> Result<(), String> {
+    let mut subtags = language_code.split('-');
+    let primary_valid = subtags
+        .next()
+        .map(|x| (2..=3).contains(&x.len()) && x.chars().all(|c| c.is_ascii_alphabetic()))
+        .unwrap_or(false);
+    if primary_valid
+        && subtags
+            .all(|x| (1..=8).contains(&x.len()) && x.chars().all(|c| c.is_ascii_alphanumeric()))
+    {
+        Ok(())
+    } else {
+        Err(format!("language_code {language_code:?} invalid"))
+    }
+}
+
+//
+#[derive(Deserialize, Serialize, Debug, Clone)]
+pub struct SubtitlesRequestBody {
+    pub media_id: String,
+    pub media_category: String,
+    pub subtitle_info: SubtitleInfo,
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone)]
+pub struct SubtitleInfo {
+    pub subtitles: Vec<Subtitle>,
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone)]
+pub struct Subtitle {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub media_id: Option<String>,
+    pub language_code: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub display_name: Option<String>,
+}
+
+impl Subtitle {
+    pub fn new(
+        media_id: u64,
+        language_code: impl Into<String>,
+        display_name: impl Into<String>,
+    ) -> Self {
+        Self {
+            media_id: Some(media_id.to_string()),
+            language_code: language_code.into(),
+            display_name: Some(display_name.into()),
+        }
+    }
+
+    fn validate(&self) -> Result<(), String> {
+        validate_language_code(&self.language_code)?;
+        if self.media_id.is_none() {
+            return Err("subtitle media_id missing".into());
+        }
+        match &self.display_name {
+            Some(x) if !x.trim().is_empty() => Ok(()),
+            _ => Err("subtitle display_name missing".into()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_subtitles_request_body() {
+        let subtitle = Subtitle::new(105, "EN", "English");
+        assert!(subtitle.validate().is_ok());
+        assert!(Subtitle::new(105, "E", "English").validate().is_err());
+        assert!(Subtitle::new(105, "EN", " ").validate().is_err());
+
+        for language_code in ["en", "fil", "pt-BR", "zh-Hant", "es-419", "sr-Latn-RS"] {
+            assert!(
+                validate_language_code(language_code).is_ok(),
+                "{language_code}"
+            );
+        }
+        for language_code in [
+            "",
+            "e",
+            "engl",
+            "1a",
+            "pt-",
+            "pt_BR",
+            "-BR",
+            "zh-Hant-toolongsubtag",
+        ] {
+            assert!(
+                validate_language_code(language_code).is_err(),
+                "{language_code}"
+            );
+        }
+
+        assert_eq!(
+            serde_json::to_string(&SubtitlesRequestBody {
+                media_id: "1".into(),
+                media_category: SUBTITLES_MEDIA_CATEGORY.into(),
+                subtitle_info: SubtitleInfo {
+                    subtitles: vec![subtitle]
+                },
+            })
+            .unwrap(),
+            r#"{"media_id":"1","media_category":"TweetVideo","subtitle_info":{"subtitles":[{"media_id":"105","language_code":"EN","display_name":"English"}]}}"#
+        );
+    }
+}
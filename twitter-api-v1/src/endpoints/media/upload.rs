@@ -0,0 +1,453 @@
+use std::time::Duration;
+
+use reqwest::Client;
+use tokio::time::{sleep_until, timeout_at, Instant};
+
+use crate::{
+    endpoints::{
+        common::EndpointError,
+        media::{
+            metadata::validate_alt_text,
+            upload_media::{UploadInitOptions, UploadResponseBodyOkJson},
+            upload_progress::UploadProgress,
+            upload_session::{
+                append_pending_segments, AppendOptions, SegmentSource, UploadSession,
+            },
+            upload_v2::MediaAuth,
+        },
+    },
+    error::{IntoResult as _, TwitterError},
+    media_util,
+    objects::media::{MediaCategory, MediaProcessingInfoState},
+};
+
+//
+#[derive(Debug, Clone)]
+pub struct UploadOptions {
+    pub append: AppendOptions,
+    // Covers the whole INIT -> APPEND -> FINALIZE -> STATUS sequence.
+    pub timeout: Duration,
+    // Used when STATUS does not return check_after_secs.
+    pub default_check_after: Duration,
+    // Set through media/metadata/create once processing succeeded.
+    pub alt_text: Option<String>,
+    // additional_owners and shared, sent with INIT.
+    pub init: UploadInitOptions,
+}
+
+impl Default for UploadOptions {
+    fn default() -> Self {
+        Self {
+            append: AppendOptions::default(),
+            timeout: Duration::from_secs(60 * 10),
+            default_check_after: Duration::from_secs(1),
+            alt_text: None,
+            init: UploadInitOptions::default(),
+        }
+    }
+}
+
+impl UploadOptions {
+    pub fn append(mut self, append: AppendOptions) -> Self {
+        self.append = append;
+        self
+    }
+
+    pub fn timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = timeout;
+        self
+    }
+
+    pub fn default_check_after(mut self, default_check_after: Duration) -> Self {
+        self.default_check_after = default_check_after;
+        self
+    }
+
+    pub fn alt_text(mut self, alt_text: impl Into<String>) -> Self {
+        self.alt_text = Some(alt_text.into());
+        self
+    }
+
+    pub fn init(mut self, init: UploadInitOptions) -> Self {
+        self.init = init;
+        self
+    }
+}
+
+//
+// Like `upload_media`, with media_type and media_category sniffed from the file content.
+#[cfg(feature = "with_tokio_fs")]
+pub async fn upload_media_from_file(
+    auth: impl Into<MediaAuth<'_>>,
+    client: Client,
+    file_path: &std::path::PathBuf,
+    target: media_util::MediaTarget,
+    options: &UploadOptions,
+) -> Result<UploadResponseBodyOkJson, TwitterError> {
+    let kind = media_util::sniff_file(file_path)
+        .await
+        .map_err(EndpointError::OpenFileFailed)?
+        .ok_or_else(|| TwitterError::Validation("media type unknown".into()))?;
+
+    upload_media(
+        auth,
+        client,
+        file_path,
+        kind.media_type(),
+        kind.media_category(target),
+        options,
+    )
+    .await
+}
+
+//
+// INIT -> APPEND -> FINALIZE -> STATUS until processing succeeded, the returned media_id is ready to attach.
+#[cfg(feature = "with_tokio_fs")]
+pub async fn upload_media(
+    auth: impl Into<MediaAuth<'_>>,
+    client: Client,
+    file_path: &std::path::PathBuf,
+    media_type: impl AsRef<str>,
+    media_category: MediaCategory,
+    options: &UploadOptions,
+) -> Result<UploadResponseBodyOkJson, TwitterError> {
+    let crate::tokio_fs_util::Info {
+        file_size,
+        file_name: _,
+    } = crate::tokio_fs_util::info(file_path)
+        .await
+        .map_err(EndpointError::GetFileInfoFailed)?;
+    if media_category == MediaCategory::Subtitles {
+        if options.init.validate_media {
+            media_util::validate_size(&media_category, file_size)
+                .map_err(TwitterError::Validation)?;
+        }
+        let bytes = tokio::fs::read(file_path)
+            .await
+            .map_err(EndpointError::ReadSourceFailed)?;
+        media_util::validate_srt(&bytes).map_err(TwitterError::Validation)?;
+    }
+    if is_video_category(&media_category)
+        && matches!(
+            media_util::sniff_file(file_path)
+                .await
+                .map_err(EndpointError::OpenFileFailed)?,
+            Some(media_util::MediaKind::Mp4 | media_util::MediaKind::Mov)
+        )
+    {
+        media_util::inspect_mp4_file(file_path)
+            .await
+            .map_err(|err| TwitterError::Validation(err.to_string()))?
+            .validate(&media_category)
+            .map_err(TwitterError::Validation)?;
+    }
+
+    upload_media_from_source(
+        auth.into(),
+        client,
+        file_size,
+        SegmentSource::File(file_path),
+        media_type,
+        media_category,
+        options,
+    )
+    .await
+}
+
+pub async fn upload_media_from_bytes(
+    auth: impl Into<MediaAuth<'_>>,
+    client: Client,
+    bytes: impl Into<bytes::Bytes>,
+    media_type: impl AsRef<str>,
+    media_category: MediaCategory,
+    options: &UploadOptions,
+) -> Result<UploadResponseBodyOkJson, TwitterError> {
+    let bytes = bytes.into();
+    if media_category == MediaCategory::Subtitles {
+        media_util::validate_srt(&bytes).map_err(TwitterError::Validation)?;
+    }
+    if is_video_category(&media_category)
+        && matches!(
+            media_util::sniff(&bytes),
+            Some(media_util::MediaKind::Mp4 | media_util::MediaKind::Mov)
+        )
+    {
+        media_util::inspect_mp4(&bytes)
+            .and_then(|info| info.validate(&media_category))
+            .map_err(TwitterError::Validation)?;
+    }
+
+    upload_media_from_source(
+        auth.into(),
+        client,
+        bytes.len() as u64,
+        SegmentSource::Bytes(bytes),
+        media_type,
+        media_category,
+        options,
+    )
+    .await
+}
+
+// Without `total_bytes` the reader is spooled into memory first, up to the size limit of media_category
+// when validate_media is set.
+// Subtitles are always spooled, so the SRT can be validated before INIT.
+pub async fn upload_media_from_reader(
+    auth: impl Into<MediaAuth<'_>>,
+    client: Client,
+    reader: &mut (dyn tokio::io::AsyncRead + Unpin + Send),
+    total_bytes: Option<u64>,
+    media_type: impl AsRef<str>,
+    media_category: MediaCategory,
+    options: &UploadOptions,
+) -> Result<UploadResponseBodyOkJson, TwitterError> {
+    use tokio::io::AsyncReadExt as _;
+
+    match total_bytes.filter(|_| media_category != MediaCategory::Subtitles) {
+        Some(total_bytes) => {
+            upload_media_from_source(
+                auth.into(),
+                client,
+                total_bytes,
+                SegmentSource::reader(reader),
+                media_type,
+                media_category,
+                options,
+            )
+            .await
+        }
+        None => {
+            let validate_media = options.init.validate_media;
+            let limit = if validate_media {
+                media_util::limits::size_max(&media_category) + 1
+            } else {
+                u64::MAX
+            };
+            let mut buf = vec![];
+            reader
+                .take(limit)
+                .read_to_end(&mut buf)
+                .await
+                .map_err(EndpointError::ReadSourceFailed)?;
+            if validate_media {
+                media_util::validate_size(&media_category, buf.len() as u64)
+                    .map_err(TwitterError::Validation)?;
+            }
+
+            upload_media_from_bytes(auth, client, buf, media_type, media_category, options).await
+        }
+    }
+}
+
+// Streams an http(s) source without touching disk, total_bytes and media_type come from Content-Length and Content-Type.
+// media_type is sniffed when Content-Type is missing or application/octet-stream.
+// Without Content-Length the body is spooled into memory first, see `upload_media_from_reader`.
+pub async fn upload_media_from_url(
+    auth: impl Into<MediaAuth<'_>>,
+    client: Client,
+    url: impl reqwest::IntoUrl,
+    media_category: MediaCategory,
+    options: &UploadOptions,
+) -> Result<UploadResponseBodyOkJson, TwitterError> {
+    use futures_util::TryStreamExt as _;
+    use tokio::io::AsyncReadExt as _;
+
+    let response = client
+        .get(url)
+        .send()
+        .await
+        .map_err(|err| EndpointError::ReadSourceFailed(std::io::Error::other(err)))?;
+    if !response.status().is_success() {
+        return Err(
+            EndpointError::ReadSourceFailed(std::io::Error::other(format!(
+                "source responded with {}",
+                response.status()
+            )))
+            .into(),
+        );
+    }
+
+    let total_bytes = response.content_length();
+    if let (true, Some(total_bytes)) = (options.init.validate_media, total_bytes) {
+        media_util::validate_size(&media_category, total_bytes)
+            .map_err(TwitterError::Validation)?;
+    }
+    let media_type = response
+        .headers()
+        .get(reqwest::header::CONTENT_TYPE)
+        .and_then(|x| x.to_str().ok())
+        .and_then(|x| x.split(';').next())
+        .map(|x| x.trim().to_ascii_lowercase())
+        .filter(|x| !x.is_empty() && x != "application/octet-stream");
+
+    let mut stream =
+        tokio_util::io::StreamReader::new(response.bytes_stream().map_err(std::io::Error::other));
+    let mut head = Vec::with_capacity(media_util::sniff::SNIFF_LEN);
+    if media_type.is_none() {
+        (&mut stream)
+            .take(media_util::sniff::SNIFF_LEN as u64)
+            .read_to_end(&mut head)
+            .await
+            .map_err(EndpointError::ReadSourceFailed)?;
+    }
+    let media_type = match media_type {
+        Some(x) => x,
+        None => media_util::sniff(&head)
+            .ok_or_else(|| TwitterError::Validation("media type unknown".into()))?
+            .media_type()
+            .to_owned(),
+    };
+
+    // The sniffed head is put back in front of the rest of the body.
+    let mut reader = Box::pin(std::io::Cursor::new(head).chain(stream));
+    upload_media_from_reader(
+        auth,
+        client,
+        &mut reader,
+        total_bytes,
+        media_type,
+        media_category,
+        options,
+    )
+    .await
+}
+
+// MP4/MOV content is inspected before INIT, anything else is left to the server.
+fn is_video_category(media_category: &MediaCategory) -> bool {
+    matches!(
+        media_category,
+        MediaCategory::TweetVideo | MediaCategory::DmVideo
+    )
+}
+
+async fn upload_media_from_source(
+    auth: MediaAuth<'_>,
+    client: Client,
+    total_bytes: u64,
+    source: SegmentSource<'_>,
+    media_type: impl AsRef<str>,
+    media_category: MediaCategory,
+    options: &UploadOptions,
+) -> Result<UploadResponseBodyOkJson, TwitterError> {
+    if let Some(alt_text) = &options.alt_text {
+        validate_alt_text(alt_text).map_err(TwitterError::Validation)?;
+    }
+    if options.init.validate_media {
+        media_util::validate(&media_category, media_type.as_ref(), total_bytes)
+            .map_err(TwitterError::Validation)?;
+    }
+    options
+        .init
+        .validate(&media_category)
+        .map_err(TwitterError::Validation)?;
+
+    let deadline = Instant::now() + options.timeout;
+    let progress = options.append.progress.as_ref();
+
+    //
+    let ok_json = timeout_at(
+        deadline,
+        auth.upload_init(
+            client.to_owned(),
+            total_bytes as usize,
+            media_type,
+            media_category,
+            &options.init,
+        ),
+    )
+    .await
+    .map_err(|_| TwitterError::Timeout { media_id: None })?
+    .into_result()?;
+    let media_id = ok_json.media_id;
+    let timeout_err = |_| TwitterError::Timeout {
+        media_id: Some(media_id),
+    };
+
+    //
+    let mut session = UploadSession::from_init_response(&ok_json, total_bytes, "");
+    session.validate().map_err(TwitterError::Validation)?;
+    timeout_at(
+        deadline,
+        append_pending_segments(
+            auth,
+            client.to_owned(),
+            &mut session,
+            source,
+            None,
+            &options.append,
+        ),
+    )
+    .await
+    .map_err(timeout_err)?
+    .into_result()?;
+
+    //
+    let mut ok_json = timeout_at(deadline, auth.upload_finalize(client.to_owned(), media_id))
+        .await
+        .map_err(timeout_err)?
+        .into_result()?;
+    if let Some(progress) = progress {
+        progress(UploadProgress::Finalized {
+            media_id,
+            processing_info: ok_json.processing_info.to_owned(),
+        });
+    }
+
+    //
+    let ok_json = loop {
+        let processing_info = match &ok_json.processing_info {
+            Some(x) => x,
+            None => break ok_json,
+        };
+        match processing_info.state {
+            MediaProcessingInfoState::Succeeded => break ok_json,
+            MediaProcessingInfoState::Failed => {
+                return Err(TwitterError::ProcessingFailed {
+                    media_id,
+                    error: processing_info.error.to_owned(),
+                })
+            }
+            MediaProcessingInfoState::Pending | MediaProcessingInfoState::InProgress => {}
+        }
+
+        let check_after = processing_info
+            .check_after_secs
+            .map(|x| Duration::from_secs(x as u64))
+            .unwrap_or(options.default_check_after);
+        let check_at = Instant::now() + check_after;
+        if check_at > deadline {
+            return Err(TwitterError::Timeout {
+                media_id: Some(media_id),
+            });
+        }
+        sleep_until(check_at).await;
+
+        ok_json = timeout_at(
+            deadline,
+            auth.get_upload_status(client.to_owned(), media_id),
+        )
+        .await
+        .map_err(timeout_err)?
+        .into_result()?;
+        if let (Some(progress), Some(processing_info)) = (progress, &ok_json.processing_info) {
+            progress(UploadProgress::Processing {
+                media_id,
+                state: processing_info.state,
+                progress_percent: processing_info.progress_percent,
+            });
+        }
+    };
+
+    //
+    if let Some(alt_text) = &options.alt_text {
+        timeout_at(
+            deadline,
+            auth.create_media_metadata(client.to_owned(), media_id, alt_text),
+        )
+        .await
+        .map_err(timeout_err)?
+        .into_result()?;
+    }
+
+    Ok(ok_json)
+}
@@ -0,0 +1,376 @@
+use std::{
+    collections::HashMap,
+    sync::{Arc, Mutex},
+    time::{Duration, SystemTime, UNIX_EPOCH},
+};
+
+use reqwest::Client;
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+use sha2::{Digest as _, Sha256};
+use twitter_api_v2::endpoints::tweets::manage_tweets::CreateTweetRequest;
+
+use crate::{
+    endpoints::{
+        common::{EndpointError, EndpointRet},
+        media::{
+            upload::{upload_media_from_bytes, UploadOptions},
+            upload_media::UploadResponseBodyOkJson,
+            upload_v2::MediaAuth,
+        },
+        tweets::manage_tweets::{
+            create_tweet, create_tweet_with_request, CreateTweetResponseBodyOkJson,
+        },
+    },
+    error::TwitterError,
+    objects::media::MediaCategory,
+    secrets::TokenSecrets,
+};
+
+//
+// Implement for a shared store (e.g. Redis) to reuse media ids across processes.
+pub trait MediaCacheStore: Send + Sync {
+    fn get(&self, key: &str) -> Option<CachedMedia>;
+    fn put(&self, key: &str, media: CachedMedia);
+    fn remove(&self, key: &str);
+}
+
+#[derive(Debug, Default)]
+pub struct MemoryMediaCacheStore {
+    inner: Mutex<HashMap<String, CachedMedia>>,
+}
+
+// A poisoned lock still holds a consistent map, every access is a single call.
+impl MediaCacheStore for MemoryMediaCacheStore {
+    fn get(&self, key: &str) -> Option<CachedMedia> {
+        self.inner
+            .lock()
+            .unwrap_or_else(|err| err.into_inner())
+            .get(key)
+            .cloned()
+    }
+
+    fn put(&self, key: &str, media: CachedMedia) {
+        self.inner
+            .lock()
+            .unwrap_or_else(|err| err.into_inner())
+            .insert(key.to_owned(), media);
+    }
+
+    fn remove(&self, key: &str) {
+        self.inner
+            .lock()
+            .unwrap_or_else(|err| err.into_inner())
+            .remove(key);
+    }
+}
+
+//
+#[derive(Deserialize, Serialize, Debug, Clone, Copy, PartialEq, Eq)]
+pub struct CachedMedia {
+    pub media_id: u64,
+    // unix timestamp in seconds
+    pub expires_at: u64,
+}
+
+impl CachedMedia {
+    pub fn expires_at(&self) -> SystemTime {
+        UNIX_EPOCH + Duration::from_secs(self.expires_at)
+    }
+}
+
+#[derive(Debug, Clone)]
+pub enum CachedUpload {
+    Hit(CachedMedia),
+    Uploaded(Box<UploadResponseBodyOkJson>),
+}
+
+impl CachedUpload {
+    pub fn media_id(&self) -> u64 {
+        match self {
+            Self::Hit(x) => x.media_id,
+            Self::Uploaded(x) => x.media_id,
+        }
+    }
+
+    pub fn is_hit(&self) -> bool {
+        matches!(self, Self::Hit(_))
+    }
+}
+
+//
+#[derive(Clone)]
+pub struct MediaCache {
+    store: Arc<dyn MediaCacheStore>,
+    // Ids expiring within this margin are treated as expired, so they survive until the tweet is created.
+    safety_margin: Duration,
+}
+
+impl core::fmt::Debug for MediaCache {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.debug_struct("MediaCache")
+            .field("safety_margin", &self.safety_margin)
+            .finish_non_exhaustive()
+    }
+}
+
+impl Default for MediaCache {
+    fn default() -> Self {
+        Self::new(Arc::new(MemoryMediaCacheStore::default()))
+    }
+}
+
+impl MediaCache {
+    pub fn new(store: Arc<dyn MediaCacheStore>) -> Self {
+        Self {
+            store,
+            safety_margin: Duration::from_secs(60),
+        }
+    }
+
+    pub fn safety_margin(mut self, safety_margin: Duration) -> Self {
+        self.safety_margin = safety_margin;
+        self
+    }
+
+    // Same content under another category or owner is another media, media ids are only usable by
+    // the user who uploaded them.
+    pub fn key(owner: &str, media_category: &MediaCategory, content_sha256: &str) -> String {
+        format!("content:{owner}:{media_category}:{content_sha256}")
+    }
+
+    // v1 access tokens are prefixed with the user id, others are hashed so no secret ends up in the
+    // store. A refreshed OAuth 2.0 access token is another owner.
+    pub fn owner(auth: &MediaAuth<'_>) -> String {
+        let token = match auth {
+            MediaAuth::V1(secrets) => {
+                if let Some((user_id, _)) = secrets.oauth_token.split_once('-') {
+                    if !user_id.is_empty() && user_id.chars().all(|c| c.is_ascii_digit()) {
+                        return format!("user:{user_id}");
+                    }
+                }
+                &secrets.oauth_token
+            }
+            MediaAuth::V2(token) => &token.access_token,
+        };
+        format!("token:{}", sha256_hex(token.as_bytes()))
+    }
+
+    pub fn get(&self, key: &str) -> Option<CachedMedia> {
+        let media = self.store.get(key)?;
+        if self.is_usable(&media) {
+            Some(media)
+        } else {
+            self.store.remove(key);
+            None
+        }
+    }
+
+    // Responses without expires_after_secs are not cached.
+    pub fn insert(&self, key: &str, ok_json: &UploadResponseBodyOkJson) -> Option<CachedMedia> {
+        let media = CachedMedia {
+            media_id: ok_json.media_id,
+            expires_at: now_secs() + ok_json.expires_after_secs? as u64,
+        };
+        self.store.put(key, media);
+        self.store.put(&media_id_key(media.media_id), media);
+        Some(media)
+    }
+
+    // Ids unknown to the cache pass, the ones it knows must not be expired.
+    pub fn check_media_ids(&self, media_ids: &[u64]) -> Result<(), String> {
+        for media_id in media_ids {
+            if let Some(media) = self.store.get(&media_id_key(*media_id)) {
+                if !self.is_usable(&media) {
+                    return Err(format!("media_id {media_id} expired"));
+                }
+            }
+        }
+        Ok(())
+    }
+
+    fn is_usable(&self, media: &CachedMedia) -> bool {
+        now_secs() + self.safety_margin.as_secs() < media.expires_at
+    }
+}
+
+fn media_id_key(media_id: u64) -> String {
+    format!("media_id:{media_id}")
+}
+
+fn now_secs() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_secs()
+}
+
+pub fn sha256_hex(bytes: &[u8]) -> String {
+    Sha256::digest(bytes)
+        .iter()
+        .map(|x| format!("{x:02x}"))
+        .collect()
+}
+
+//
+//
+//
+pub async fn upload_media_from_bytes_cached(
+    auth: impl Into<MediaAuth<'_>>,
+    client: Client,
+    cache: &MediaCache,
+    bytes: impl Into<bytes::Bytes>,
+    media_type: impl AsRef<str>,
+    media_category: MediaCategory,
+    options: &UploadOptions,
+) -> Result<CachedUpload, TwitterError> {
+    let auth = auth.into();
+    let bytes = bytes.into();
+    let key = MediaCache::key(
+        &MediaCache::owner(&auth),
+        &media_category,
+        &sha256_hex(&bytes),
+    );
+    if let Some(media) = cache.get(&key) {
+        return Ok(CachedUpload::Hit(media));
+    }
+
+    let ok_json =
+        upload_media_from_bytes(auth, client, bytes, media_type, media_category, options).await?;
+    cache.insert(&key, &ok_json);
+    Ok(CachedUpload::Uploaded(Box::new(ok_json)))
+}
+
+#[cfg(feature = "with_tokio_fs")]
+pub async fn upload_media_cached(
+    auth: impl Into<MediaAuth<'_>>,
+    client: Client,
+    cache: &MediaCache,
+    file_path: &std::path::PathBuf,
+    media_type: impl AsRef<str>,
+    media_category: MediaCategory,
+    options: &UploadOptions,
+) -> Result<CachedUpload, TwitterError> {
+    let auth = auth.into();
+    let sha256 = crate::tokio_fs_util::sha256(file_path)
+        .await
+        .map_err(EndpointError::OpenFileFailed)?;
+    let key = MediaCache::key(&MediaCache::owner(&auth), &media_category, &sha256);
+    if let Some(media) = cache.get(&key) {
+        return Ok(CachedUpload::Hit(media));
+    }
+
+    let ok_json = crate::endpoints::media::upload::upload_media(
+        auth,
+        client,
+        file_path,
+        media_type,
+        media_category,
+        options,
+    )
+    .await?;
+    cache.insert(&key, &ok_json);
+    Ok(CachedUpload::Uploaded(Box::new(ok_json)))
+}
+
+// Like `create_tweet`, rejecting media ids the cache knows to be expired.
+pub async fn create_tweet_with_media_cache(
+    secrets: &TokenSecrets,
+    client: Client,
+    cache: &MediaCache,
+    status: Option<&str>,
+    media_ids: Option<Vec<u64>>,
+    other_parameters: Option<Map<String, Value>>,
+) -> Result<EndpointRet<CreateTweetResponseBodyOkJson>, EndpointError> {
+    if let Some(media_ids) = &media_ids {
+        cache
+            .check_media_ids(media_ids)
+            .map_err(EndpointError::ValidateFailed)?;
+    }
+
+    create_tweet(secrets, client, status, media_ids, other_parameters).await
+}
+
+// Like `create_tweet_with_request`, rejecting media ids the cache knows to be expired.
+pub async fn create_tweet_with_request_and_media_cache(
+    secrets: &TokenSecrets,
+    client: Client,
+    cache: &MediaCache,
+    request: &CreateTweetRequest,
+) -> Result<EndpointRet<CreateTweetResponseBodyOkJson>, EndpointError> {
+    if let Some(media) = &request.media {
+        let media_ids = media
+            .media_ids
+            .iter()
+            .filter_map(|x| x.parse().ok())
+            .collect::<Vec<u64>>();
+        cache
+            .check_media_ids(&media_ids)
+            .map_err(EndpointError::ValidateFailed)?;
+    }
+
+    create_tweet_with_request(secrets, client, request).await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::secrets::UserAccessToken;
+
+    fn ok_json(media_id: u64, expires_after_secs: Option<usize>) -> UploadResponseBodyOkJson {
+        UploadResponseBodyOkJson {
+            media_id,
+            media_id_string: media_id.to_string(),
+            media_key: "".into(),
+            size: None,
+            expires_after_secs,
+            image: None,
+            video: None,
+            processing_info: None,
+        }
+    }
+
+    #[test]
+    fn test_media_cache() {
+        let cache = MediaCache::default();
+        let owner = MediaCache::owner(&MediaAuth::V1(&TokenSecrets::new("", "", "783214-x", "")));
+        assert_eq!(owner, "user:783214");
+        let key = MediaCache::key(&owner, &MediaCategory::TweetImage, &sha256_hex(b"logo"));
+        assert_eq!(
+            sha256_hex(b""),
+            "e3b0c44298fc1c149afbf4c8996fb92427ae41e4649b934ca495991b7852b855"
+        );
+        assert!(key.starts_with("content:user:783214:tweet_image:"));
+        assert_ne!(
+            key,
+            MediaCache::key(&owner, &MediaCategory::DmImage, &sha256_hex(b"logo"))
+        );
+        assert_ne!(
+            key,
+            MediaCache::key("user:1", &MediaCategory::TweetImage, &sha256_hex(b"logo"))
+        );
+        let owner = MediaCache::owner(&MediaAuth::V2(&UserAccessToken::new("secret")));
+        assert!(owner.starts_with("token:"));
+        assert!(!owner.contains("secret"));
+
+        assert!(cache.get(&key).is_none());
+        assert!(cache.insert(&key, &ok_json(1, None)).is_none());
+        assert!(cache.get(&key).is_none());
+
+        let media = cache.insert(&key, &ok_json(2, Some(3600))).unwrap();
+        assert_eq!(cache.get(&key), Some(media));
+        assert!(cache.check_media_ids(&[2, 3]).is_ok());
+
+        // Inside the safety margin.
+        cache.insert(&key, &ok_json(4, Some(30)));
+        assert!(cache.get(&key).is_none());
+        assert_eq!(
+            cache.check_media_ids(&[2, 4]).unwrap_err(),
+            "media_id 4 expired"
+        );
+
+        let cache = cache.safety_margin(Duration::ZERO);
+        assert!(cache.check_media_ids(&[4]).is_ok());
+    }
+}
@@ -4,10 +4,20 @@ use reqwest::{
 };
 use reqwest_oauth1::OAuthClientProvider as _;
 use serde::{Deserialize, Serialize};
-use serde_json::Map;
+use serde_json::{Map, Value};
 
+use crate::endpoints::media::metadata::{create_media_metadata, validate_alt_text};
+#[cfg(feature = "with_tokio")]
+use crate::endpoints::media::upload_session::{
+    append_pending_segments, AppendOptions, SegmentSource, UploadSession,
+    DEFAULT_EXPIRES_AFTER_SECS,
+};
 use crate::{
-    endpoints::common::{EndpointError, EndpointRet},
+    endpoints::{
+        common::{EndpointError, EndpointRet},
+        media::upload_progress::{ProgressCallback, UploadProgress},
+    },
+    media_util,
     objects::{
         media::{MediaCategory, MediaImage, MediaProcessingInfo, MediaVideo},
         ResponseBodyErrJson,
@@ -21,6 +31,75 @@ pub const UPLOAD_URL: &str = "https://upload.twitter.com/1.1/media/upload.json";
 pub const SEGMENT_SIZE: usize = 1024 * 1024 * 5;
 pub const SEGMENT_INDEX_MIN: usize = 0;
 pub const SEGMENT_INDEX_MAX: usize = 999;
+pub const ADDITIONAL_OWNERS_MAX: usize = 100;
+
+//
+#[derive(Debug, Clone, Default)]
+pub struct UploadInitOptions {
+    // User ids allowed to use the media besides the uploader.
+    pub additional_owners: Vec<u64>,
+    // Lets DM media be reused across conversations.
+    pub shared: bool,
+    // Check media_type and size limits of media_category before sending, off by default.
+    pub validate_media: bool,
+}
+
+impl UploadInitOptions {
+    pub fn additional_owners(mut self, additional_owners: impl IntoIterator<Item = u64>) -> Self {
+        self.additional_owners = additional_owners.into_iter().collect();
+        self
+    }
+
+    pub fn shared(mut self, shared: bool) -> Self {
+        self.shared = shared;
+        self
+    }
+
+    pub fn validate_media(mut self, validate_media: bool) -> Self {
+        self.validate_media = validate_media;
+        self
+    }
+
+    pub fn validate(&self, media_category: &MediaCategory) -> Result<(), String> {
+        if self.additional_owners.len() > ADDITIONAL_OWNERS_MAX {
+            return Err(format!(
+                "additional_owners too many, count: {}, max: {ADDITIONAL_OWNERS_MAX}",
+                self.additional_owners.len()
+            ));
+        }
+        if self.shared
+            && !matches!(
+                media_category,
+                MediaCategory::DmImage | MediaCategory::DmGif | MediaCategory::DmVideo
+            )
+        {
+            return Err(format!("shared is only for dm media, not {media_category}"));
+        }
+        Ok(())
+    }
+
+    pub(crate) fn additional_owners_param(&self) -> Option<String> {
+        if self.additional_owners.is_empty() {
+            return None;
+        }
+        Some(
+            self.additional_owners
+                .iter()
+                .map(|x| x.to_string())
+                .collect::<Vec<_>>()
+                .join(","),
+        )
+    }
+
+    fn insert_into(&self, map: &mut Map<String, Value>) {
+        if let Some(additional_owners) = self.additional_owners_param() {
+            map.insert("additional_owners".into(), additional_owners.into());
+        }
+        if self.shared {
+            map.insert("shared".into(), true.into());
+        }
+    }
+}
 
 //
 //
@@ -33,6 +112,30 @@ pub async fn upload_image<T>(
     stream_length: Option<u64>,
     file_name: Option<String>,
 ) -> Result<EndpointRet<UploadResponseBodyOkJson>, EndpointError>
+where
+    T: Into<Body>,
+{
+    upload_image_with_options(
+        secrets,
+        client,
+        media_category,
+        stream,
+        stream_length,
+        file_name,
+        &UploadInitOptions::default(),
+    )
+    .await
+}
+
+pub async fn upload_image_with_options<T>(
+    secrets: &TokenSecrets,
+    client: Client,
+    media_category: MediaCategory,
+    stream: T,
+    stream_length: Option<u64>,
+    file_name: Option<String>,
+    options: &UploadInitOptions,
+) -> Result<EndpointRet<UploadResponseBodyOkJson>, EndpointError>
 where
     T: Into<Body>,
 {
@@ -45,8 +148,18 @@ where
         }
     }
 
+    if let (true, Some(stream_length)) = (options.validate_media, stream_length) {
+        media_util::validate_size(&media_category, stream_length)
+            .map_err(EndpointError::ValidateFailed)?;
+    }
+
+    options
+        .validate(&media_category)
+        .map_err(EndpointError::ValidateFailed)?;
+
     let mut query = Map::new();
     query.insert("media_category".into(), media_category.to_string().into());
+    options.insert_into(&mut query);
 
     //
     let part = if let Some(stream_length) = stream_length {
@@ -75,6 +188,7 @@ where
 
     //
     let response_status = response.status();
+    let response_headers = response.headers().to_owned();
     let response_body = response
         .bytes()
         .await
@@ -86,13 +200,50 @@ where
             serde_json::from_slice(response_body)
                 .map_err(EndpointError::DeResponseBodyOkJsonFailed)?,
         )),
-        status => match serde_json::from_slice::<ResponseBodyErrJson>(response_body) {
-            Ok(err_json) => Ok(EndpointRet::Other((status, Ok(err_json)))),
-            Err(_) => Ok(EndpointRet::Other((status, Err(response_body.to_owned())))),
-        },
+        status => Ok(EndpointRet::from_err_response::<ResponseBodyErrJson>(
+            status,
+            &response_headers,
+            response_body,
+        )),
     }
 }
 
+// The upload together with the media/metadata/create outcome, metadata is None without alt text.
+// The media stays usable when setting the alt text failed, retry with create_media_metadata.
+#[derive(Debug)]
+pub struct UploadWithMetadata {
+    pub upload: UploadResponseBodyOkJson,
+    pub metadata: Option<Result<EndpointRet<()>, EndpointError>>,
+}
+
+// The alt text is set via media/metadata/create once the upload succeeded.
+pub async fn upload_image_with_alt_text<T>(
+    secrets: &TokenSecrets,
+    client: Client,
+    media_category: MediaCategory,
+    stream: T,
+    stream_length: Option<u64>,
+    file_name: Option<String>,
+    alt_text: &str,
+) -> Result<EndpointRet<UploadWithMetadata>, EndpointError>
+where
+    T: Into<Body>,
+{
+    validate_alt_text(alt_text).map_err(EndpointError::ValidateFailed)?;
+
+    let ret = upload_image(
+        secrets,
+        client.to_owned(),
+        media_category,
+        stream,
+        stream_length,
+        file_name,
+    )
+    .await?;
+
+    Ok(apply_alt_text(secrets, client, ret, Some(alt_text)).await)
+}
+
 #[cfg(feature = "with_tokio")]
 pub async fn upload_image_from_reader_stream<S>(
     secrets: &TokenSecrets,
@@ -149,6 +300,128 @@ pub async fn upload_image_from_file(
     .await
 }
 
+#[cfg(feature = "with_tokio_fs")]
+pub async fn upload_image_from_file_with_alt_text(
+    secrets: &TokenSecrets,
+    client: Client,
+    media_category: MediaCategory,
+    file_path: &std::path::PathBuf,
+    alt_text: &str,
+) -> Result<EndpointRet<UploadWithMetadata>, EndpointError> {
+    validate_alt_text(alt_text).map_err(EndpointError::ValidateFailed)?;
+
+    let ret = upload_image_from_file(secrets, client.to_owned(), media_category, file_path).await?;
+
+    Ok(apply_alt_text(secrets, client, ret, Some(alt_text)).await)
+}
+
+// Strips metadata, applies the EXIF orientation and downsizes/re-encodes when over the limits.
+#[cfg(feature = "with_image")]
+pub async fn upload_image_normalized(
+    secrets: &TokenSecrets,
+    client: Client,
+    media_category: MediaCategory,
+    bytes: &[u8],
+    file_name: Option<String>,
+    options: &media_util::NormalizeOptions,
+) -> Result<
+    (
+        EndpointRet<UploadResponseBodyOkJson>,
+        Vec<media_util::ImageTransform>,
+    ),
+    EndpointError,
+> {
+    let media_util::NormalizedImage {
+        bytes,
+        media_type,
+        transforms,
+    } = media_util::normalize_image(bytes, options).map_err(EndpointError::ValidateFailed)?;
+
+    let file_name = file_name.map(|x| {
+        let extension = match media_type {
+            "image/jpeg" => "jpg",
+            "image/png" => "png",
+            _ => return x,
+        };
+        std::path::Path::new(&x)
+            .with_extension(extension)
+            .to_string_lossy()
+            .into_owned()
+    });
+    let stream_length = bytes.len() as u64;
+
+    let ret = upload_image(
+        secrets,
+        client,
+        media_category,
+        bytes,
+        Some(stream_length),
+        file_name,
+    )
+    .await?;
+
+    Ok((ret, transforms))
+}
+
+#[cfg(all(feature = "with_tokio_fs", feature = "with_image"))]
+pub async fn upload_image_from_file_normalized(
+    secrets: &TokenSecrets,
+    client: Client,
+    media_category: MediaCategory,
+    file_path: &std::path::PathBuf,
+    alt_text: Option<&str>,
+    options: &media_util::NormalizeOptions,
+) -> Result<
+    (
+        EndpointRet<UploadWithMetadata>,
+        Vec<media_util::ImageTransform>,
+    ),
+    EndpointError,
+> {
+    if let Some(alt_text) = alt_text {
+        validate_alt_text(alt_text).map_err(EndpointError::ValidateFailed)?;
+    }
+
+    let bytes = tokio::fs::read(file_path)
+        .await
+        .map_err(EndpointError::OpenFileFailed)?;
+    let file_name = file_path
+        .file_name()
+        .and_then(|x| x.to_str())
+        .map(|x| x.to_owned());
+
+    let (ret, transforms) = upload_image_normalized(
+        secrets,
+        client.to_owned(),
+        media_category,
+        &bytes,
+        file_name,
+        options,
+    )
+    .await?;
+
+    Ok((
+        apply_alt_text(secrets, client, ret, alt_text).await,
+        transforms,
+    ))
+}
+
+async fn apply_alt_text(
+    secrets: &TokenSecrets,
+    client: Client,
+    ret: EndpointRet<UploadResponseBodyOkJson>,
+    alt_text: Option<&str>,
+) -> EndpointRet<UploadWithMetadata> {
+    let metadata = match (&ret, alt_text) {
+        (EndpointRet::Ok(ok_json), Some(alt_text)) => {
+            Some(create_media_metadata(secrets, client, ok_json.media_id, alt_text).await)
+        }
+        _ => None,
+    };
+
+    ret.map(|upload| UploadWithMetadata { upload, metadata })
+}
+
 //
 //
 //
@@ -159,12 +432,40 @@ pub async fn upload_init(
     media_type: impl AsRef<str>,
     media_category: MediaCategory,
 ) -> Result<EndpointRet<UploadResponseBodyOkJson>, EndpointError> {
+    upload_init_with_options(
+        secrets,
+        client,
+        total_bytes,
+        media_type,
+        media_category,
+        &UploadInitOptions::default(),
+    )
+    .await
+}
+
+pub async fn upload_init_with_options(
+    secrets: &TokenSecrets,
+    client: Client,
+    total_bytes: usize,
+    media_type: impl AsRef<str>,
+    media_category: MediaCategory,
+    options: &UploadInitOptions,
+) -> Result<EndpointRet<UploadResponseBodyOkJson>, EndpointError> {
+    if options.validate_media {
+        media_util::validate(&media_category, media_type.as_ref(), total_bytes as u64)
+            .map_err(EndpointError::ValidateFailed)?;
+    }
+    options
+        .validate(&media_category)
+        .map_err(EndpointError::ValidateFailed)?;
+
     //
     let mut form = Map::new();
     form.insert("command".into(), "INIT".into());
     form.insert("total_bytes".into(), total_bytes.into());
     form.insert("media_type".into(), media_type.as_ref().into());
     form.insert("media_category".into(), media_category.to_string().into());
+    options.insert_into(&mut form);
 
     //
     let response = client
@@ -177,6 +478,7 @@ pub async fn upload_init(
 
     //
     let response_status = response.status();
+    let response_headers = response.headers().to_owned();
     let response_body = response
         .bytes()
         .await
@@ -189,10 +491,11 @@ pub async fn upload_init(
             serde_json::from_slice(response_body)
                 .map_err(EndpointError::DeResponseBodyOkJsonFailed)?,
         )),
-        status => match serde_json::from_slice::<ResponseBodyErrJson>(response_body) {
-            Ok(err_json) => Ok(EndpointRet::Other((status, Ok(err_json)))),
-            Err(_) => Ok(EndpointRet::Other((status, Err(response_body.to_owned())))),
-        },
+        status => Ok(EndpointRet::from_err_response::<ResponseBodyErrJson>(
+            status,
+            &response_headers,
+            response_body,
+        )),
     }
 }
 
@@ -243,6 +546,7 @@ where
 
     //
     let response_status = response.status();
+    let response_headers = response.headers().to_owned();
 
     match response_status {
         StatusCode::NO_CONTENT => Ok(EndpointRet::Ok(())),
@@ -253,10 +557,11 @@ where
                 .map_err(EndpointError::ReadResponseBodyFailed)?;
             let response_body = response_body.as_ref();
 
-            match serde_json::from_slice::<ResponseBodyErrJson>(response_body) {
-                Ok(err_json) => Ok(EndpointRet::Other((status, Ok(err_json)))),
-                Err(_) => Ok(EndpointRet::Other((status, Err(response_body.to_owned())))),
-            }
+            Ok(EndpointRet::from_err_response::<ResponseBodyErrJson>(
+                status,
+                &response_headers,
+                response_body,
+            ))
         }
     }
 }
@@ -324,12 +629,79 @@ pub async fn upload_append_from_file(
     .await
 }
 
+#[cfg(feature = "with_tokio_fs")]
+pub async fn upload_append_from_file_with_progress(
+    secrets: &TokenSecrets,
+    client: Client,
+    media_id: u64,
+    segment_index: usize,
+    file_path: &std::path::PathBuf,
+    file_index: core::ops::Range<usize>,
+    progress: ProgressCallback,
+) -> Result<EndpointRet<()>, EndpointError> {
+    use futures_util::StreamExt as _;
+    use tokio::{
+        fs::File,
+        io::{AsyncReadExt as _, AsyncSeekExt as _, SeekFrom},
+    };
+    use tokio_util::io::ReaderStream;
+
+    let file_take_size = (file_index.end - file_index.start) as u64;
+
+    let mut file = File::open(&file_path)
+        .await
+        .map_err(EndpointError::OpenFileFailed)?;
+    file.seek(SeekFrom::Start(file_index.start as u64))
+        .await
+        .map_err(EndpointError::OpenFileFailed)?;
+
+    let mut bytes_sent = 0;
+    let stream = ReaderStream::new(file.take(file_take_size)).inspect(move |chunk| {
+        if let Ok(chunk) = chunk {
+            bytes_sent += chunk.len() as u64;
+            progress(UploadProgress::SegmentBytesSent {
+                segment_index,
+                bytes_sent,
+                segment_bytes: file_take_size,
+            });
+        }
+    });
+
+    upload_append(
+        secrets,
+        client,
+        media_id,
+        segment_index,
+        Body::wrap_stream(stream),
+        Some(file_take_size),
+    )
+    .await
+}
+
 #[cfg(feature = "with_tokio_fs")]
 pub async fn upload_append_all_from_file(
     secrets: &TokenSecrets,
     client: Client,
     media_id: u64,
     file_path: &std::path::PathBuf,
+) -> Result<Result<(), EndpointRet<()>>, EndpointError> {
+    upload_append_all_from_file_with_options(
+        secrets,
+        client,
+        media_id,
+        file_path,
+        &AppendOptions::default(),
+    )
+    .await
+}
+
+#[cfg(feature = "with_tokio_fs")]
+pub async fn upload_append_all_from_file_with_options(
+    secrets: &TokenSecrets,
+    client: Client,
+    media_id: u64,
+    file_path: &std::path::PathBuf,
+    options: &AppendOptions,
 ) -> Result<Result<(), EndpointRet<()>>, EndpointError> {
     let crate::tokio_fs_util::Info {
         file_size,
@@ -338,30 +710,73 @@ pub async fn upload_append_all_from_file(
         .await
         .map_err(EndpointError::GetFileInfoFailed)?;
 
-    for segment_index in SEGMENT_INDEX_MIN..=SEGMENT_INDEX_MAX {
-        let file_index_start = segment_index * SEGMENT_SIZE;
-        let file_index_end = core::cmp::min(file_index_start + SEGMENT_SIZE, file_size as usize);
+    let mut session = UploadSession::new(media_id, file_size, "", DEFAULT_EXPIRES_AFTER_SECS);
+    if session.segment_count() > SEGMENT_INDEX_MAX + 1 {
+        return Err(EndpointError::ValidateFailed("too many segments".into()));
+    }
 
-        let ret = upload_append_from_file(
-            secrets,
-            client.to_owned(),
-            media_id,
-            segment_index,
-            file_path,
-            file_index_start..file_index_end,
-        )
-        .await?;
-        match ret {
-            EndpointRet::Ok(_) => {}
-            x => return Ok(Err(x)),
-        };
+    append_pending_segments(
+        secrets.into(),
+        client,
+        &mut session,
+        SegmentSource::File(file_path),
+        None,
+        options,
+    )
+    .await
+}
 
-        if file_index_start + SEGMENT_SIZE >= file_size as usize {
-            break;
-        }
+#[cfg(feature = "with_tokio")]
+pub async fn upload_append_all_from_bytes(
+    secrets: &TokenSecrets,
+    client: Client,
+    media_id: u64,
+    bytes: impl Into<bytes::Bytes>,
+    options: &AppendOptions,
+) -> Result<Result<(), EndpointRet<()>>, EndpointError> {
+    let bytes = bytes.into();
+
+    let mut session =
+        UploadSession::new(media_id, bytes.len() as u64, "", DEFAULT_EXPIRES_AFTER_SECS);
+    if session.segment_count() > SEGMENT_INDEX_MAX + 1 {
+        return Err(EndpointError::ValidateFailed("too many segments".into()));
     }
 
-    Ok(Ok(()))
+    append_pending_segments(
+        secrets.into(),
+        client,
+        &mut session,
+        SegmentSource::Bytes(bytes),
+        None,
+        options,
+    )
+    .await
+}
+
+// Reads exactly `total_bytes` (the value passed to INIT), one segment at a time.
+#[cfg(feature = "with_tokio")]
+pub async fn upload_append_all_from_reader(
+    secrets: &TokenSecrets,
+    client: Client,
+    media_id: u64,
+    reader: &mut (dyn tokio::io::AsyncRead + Unpin + Send),
+    total_bytes: u64,
+    options: &AppendOptions,
+) -> Result<Result<(), EndpointRet<()>>, EndpointError> {
+    let mut session = UploadSession::new(media_id, total_bytes, "", DEFAULT_EXPIRES_AFTER_SECS);
+    if session.segment_count() > SEGMENT_INDEX_MAX + 1 {
+        return Err(EndpointError::ValidateFailed("too many segments".into()));
+    }
+
+    append_pending_segments(
+        secrets.into(),
+        client,
+        &mut session,
+        SegmentSource::reader(reader),
+        None,
+        options,
+    )
+    .await
 }
 
 //
@@ -388,6 +803,7 @@ pub async fn upload_finalize(
 
     //
     let response_status = response.status();
+    let response_headers = response.headers().to_owned();
     let response_body = response
         .bytes()
         .await
@@ -400,13 +816,32 @@ pub async fn upload_finalize(
             serde_json::from_slice(response_body)
                 .map_err(EndpointError::DeResponseBodyOkJsonFailed)?,
         )),
-        status => match serde_json::from_slice::<ResponseBodyErrJson>(response_body) {
-            Ok(err_json) => Ok(EndpointRet::Other((status, Ok(err_json)))),
-            Err(_) => Ok(EndpointRet::Other((status, Err(response_body.to_owned())))),
-        },
+        status => Ok(EndpointRet::from_err_response::<ResponseBodyErrJson>(
+            status,
+            &response_headers,
+            response_body,
+        )),
     }
 }
 
+pub async fn upload_finalize_with_progress(
+    secrets: &TokenSecrets,
+    client: Client,
+    media_id: u64,
+    progress: &ProgressCallback,
+) -> Result<EndpointRet<UploadResponseBodyOkJson>, EndpointError> {
+    let ret = upload_finalize(secrets, client, media_id).await?;
+
+    if let EndpointRet::Ok(ok_json) = &ret {
+        progress(UploadProgress::Finalized {
+            media_id,
+            processing_info: ok_json.processing_info.to_owned(),
+        });
+    }
+
+    Ok(ret)
+}
+
 //
 //
 //
@@ -431,6 +866,7 @@ pub async fn get_upload_status(
 
     //
     let response_status = response.status();
+    let response_headers = response.headers().to_owned();
     let response_body = response
         .bytes()
         .await
@@ -443,13 +879,37 @@ pub async fn get_upload_status(
             serde_json::from_slice(response_body)
                 .map_err(EndpointError::DeResponseBodyOkJsonFailed)?,
         )),
-        status => match serde_json::from_slice::<ResponseBodyErrJson>(response_body) {
-            Ok(err_json) => Ok(EndpointRet::Other((status, Ok(err_json)))),
-            Err(_) => Ok(EndpointRet::Other((status, Err(response_body.to_owned())))),
-        },
+        status => Ok(EndpointRet::from_err_response::<ResponseBodyErrJson>(
+            status,
+            &response_headers,
+            response_body,
+        )),
     }
 }
 
+pub async fn get_upload_status_with_progress(
+    secrets: &TokenSecrets,
+    client: Client,
+    media_id: u64,
+    progress: &ProgressCallback,
+) -> Result<EndpointRet<UploadResponseBodyOkJson>, EndpointError> {
+    let ret = get_upload_status(secrets, client, media_id).await?;
+
+    if let EndpointRet::Ok(UploadResponseBodyOkJson {
+        processing_info: Some(processing_info),
+        ..
+    }) = &ret
+    {
+        progress(UploadProgress::Processing {
+            media_id,
+            state: processing_info.state,
+            progress_percent: processing_info.progress_percent,
+        });
+    }
+
+    Ok(ret)
+}
+
 //
 //
 //
@@ -567,4 +1027,24 @@ mod tests {
             Err(err) => panic!("{err}"),
         }
     }
+
+    #[test]
+    fn test_upload_init_options() {
+        let options = UploadInitOptions::default().additional_owners([1, 2]);
+        assert!(options.validate(&MediaCategory::TweetImage).is_ok());
+        assert_eq!(options.additional_owners_param().unwrap(), "1,2");
+        assert!(UploadInitOptions::default()
+            .additional_owners_param()
+            .is_none());
+
+        let options = UploadInitOptions::default().additional_owners(0..101);
+        assert_eq!(
+            options.validate(&MediaCategory::TweetImage).unwrap_err(),
+            "additional_owners too many, count: 101, max: 100"
+        );
+
+        let options = UploadInitOptions::default().shared(true);
+        assert!(options.validate(&MediaCategory::DmVideo).is_ok());
+        assert!(options.validate(&MediaCategory::TweetVideo).is_err());
+    }
 }
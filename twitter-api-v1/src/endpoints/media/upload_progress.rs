@@ -0,0 +1,33 @@
+use std::sync::Arc;
+
+use crate::objects::media::{MediaProcessingInfo, MediaProcessingInfoState};
+
+//
+#[derive(Debug, Clone)]
+#[non_exhaustive]
+pub enum UploadProgress {
+    // Bytes of the segment handed to the connection so far, emitted per chunk and again on retries.
+    SegmentBytesSent {
+        segment_index: usize,
+        bytes_sent: u64,
+        segment_bytes: u64,
+    },
+    SegmentCompleted {
+        segment_index: usize,
+        completed_segments: usize,
+        total_segments: usize,
+        bytes_completed: u64,
+        total_bytes: u64,
+    },
+    Finalized {
+        media_id: u64,
+        processing_info: Option<MediaProcessingInfo>,
+    },
+    Processing {
+        media_id: u64,
+        state: MediaProcessingInfoState,
+        progress_percent: Option<usize>,
+    },
+}
+
+pub type ProgressCallback = Arc<dyn Fn(UploadProgress) + Send + Sync>;
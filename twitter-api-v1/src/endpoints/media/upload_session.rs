@@ -0,0 +1,667 @@
+use std::{
+    collections::BTreeSet,
+    time::{Duration, SystemTime, UNIX_EPOCH},
+};
+
+use serde::{Deserialize, Serialize};
+
+use crate::endpoints::media::{
+    upload_media::{UploadResponseBodyOkJson, SEGMENT_INDEX_MAX, SEGMENT_SIZE},
+    upload_progress::ProgressCallback,
+};
+#[cfg(feature = "with_tokio")]
+use crate::endpoints::{
+    common::{EndpointError, EndpointRet},
+    media::{
+        upload_media::upload_append,
+        upload_progress::UploadProgress,
+        upload_v2::{upload_append_v2, MediaAuth},
+    },
+};
+#[cfg(feature = "with_tokio")]
+use crate::secrets::TokenSecrets;
+#[cfg(feature = "with_tokio_fs")]
+use crate::{
+    endpoints::media::upload_media::{
+        upload_append_from_file, upload_append_from_file_with_progress, upload_init,
+    },
+    objects::media::MediaCategory,
+};
+
+// Twitter keeps an uninitialized media_id for 24 hours.
+pub const DEFAULT_EXPIRES_AFTER_SECS: usize = 60 * 60 * 24;
+
+//
+#[derive(Clone)]
+pub struct AppendOptions {
+    // Segments in flight at the same time.
+    pub concurrency: usize,
+    // Retries per segment on transport failures and 5xx responses.
+    pub max_retries: usize,
+    pub retry_delay: Duration,
+    pub progress: Option<ProgressCallback>,
+}
+
+impl Default for AppendOptions {
+    fn default() -> Self {
+        Self {
+            concurrency: 1,
+            max_retries: 0,
+            retry_delay: Duration::from_secs(1),
+            progress: None,
+        }
+    }
+}
+
+impl core::fmt::Debug for AppendOptions {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.debug_struct("AppendOptions")
+            .field("concurrency", &self.concurrency)
+            .field("max_retries", &self.max_retries)
+            .field("retry_delay", &self.retry_delay)
+            .field("progress", &self.progress.is_some())
+            .finish()
+    }
+}
+
+impl AppendOptions {
+    pub fn concurrency(mut self, concurrency: usize) -> Self {
+        self.concurrency = concurrency;
+        self
+    }
+
+    pub fn max_retries(mut self, max_retries: usize) -> Self {
+        self.max_retries = max_retries;
+        self
+    }
+
+    pub fn retry_delay(mut self, retry_delay: Duration) -> Self {
+        self.retry_delay = retry_delay;
+        self
+    }
+
+    pub fn progress(mut self, progress: ProgressCallback) -> Self {
+        self.progress = Some(progress);
+        self
+    }
+}
+
+//
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq, Eq)]
+pub struct UploadSession {
+    pub media_id: u64,
+    pub file_size: u64,
+    pub file_sha256: String,
+    pub segment_size: usize,
+    pub completed_segments: BTreeSet<usize>,
+    // unix timestamp in seconds
+    pub expires_at: u64,
+}
+
+impl UploadSession {
+    pub fn new(
+        media_id: u64,
+        file_size: u64,
+        file_sha256: impl Into<String>,
+        expires_after_secs: usize,
+    ) -> Self {
+        let now = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .unwrap_or_default()
+            .as_secs();
+
+        Self {
+            media_id,
+            file_size,
+            file_sha256: file_sha256.into(),
+            segment_size: SEGMENT_SIZE,
+            completed_segments: BTreeSet::new(),
+            expires_at: now + expires_after_secs as u64,
+        }
+    }
+
+    pub fn from_init_response(
+        ok_json: &UploadResponseBodyOkJson,
+        file_size: u64,
+        file_sha256: impl Into<String>,
+    ) -> Self {
+        Self::new(
+            ok_json.media_id,
+            file_size,
+            file_sha256,
+            ok_json
+                .expires_after_secs
+                .unwrap_or(DEFAULT_EXPIRES_AFTER_SECS),
+        )
+    }
+
+    pub fn with_segment_size(mut self, segment_size: usize) -> Self {
+        self.segment_size = segment_size;
+        self
+    }
+
+    //
+    pub fn segment_count(&self) -> usize {
+        core::cmp::max(
+            1,
+            (self.file_size as usize).div_ceil(core::cmp::max(self.segment_size, 1)),
+        )
+    }
+
+    pub fn segment_range(&self, segment_index: usize) -> core::ops::Range<usize> {
+        let start = segment_index * self.segment_size;
+        let end = core::cmp::min(start + self.segment_size, self.file_size as usize);
+        start..end
+    }
+
+    pub fn pending_segments(&self) -> Vec<usize> {
+        (0..self.segment_count())
+            .filter(|x| !self.completed_segments.contains(x))
+            .collect()
+    }
+
+    pub fn mark_completed(&mut self, segment_index: usize) {
+        self.completed_segments.insert(segment_index);
+    }
+
+    pub fn is_complete(&self) -> bool {
+        self.pending_segments().is_empty()
+    }
+
+    pub fn bytes_completed(&self) -> u64 {
+        self.completed_segments
+            .iter()
+            .map(|x| self.segment_range(*x).len() as u64)
+            .sum()
+    }
+
+    pub fn expires_at(&self) -> SystemTime {
+        UNIX_EPOCH + Duration::from_secs(self.expires_at)
+    }
+
+    pub fn is_expired(&self) -> bool {
+        SystemTime::now() >= self.expires_at()
+    }
+
+    pub fn validate(&self) -> Result<(), String> {
+        if self.segment_size == 0 || self.segment_size > SEGMENT_SIZE {
+            return Err("segment_size invalid".into());
+        }
+        if self.segment_count() > SEGMENT_INDEX_MAX + 1 {
+            return Err("too many segments".into());
+        }
+        if self.is_expired() {
+            return Err("upload session expired".into());
+        }
+        Ok(())
+    }
+}
+
+#[cfg(feature = "with_tokio_fs")]
+impl UploadSession {
+    pub async fn load(path: &std::path::PathBuf) -> Result<Self, std::io::Error> {
+        let bytes = tokio::fs::read(path).await?;
+        serde_json::from_slice(&bytes).map_err(std::io::Error::other)
+    }
+
+    // Writes to a sibling file first so a crash never leaves a truncated session behind.
+    pub async fn save(&self, path: &std::path::PathBuf) -> Result<(), std::io::Error> {
+        let bytes = serde_json::to_vec(self).map_err(std::io::Error::other)?;
+        let mut tmp_path = path.as_os_str().to_owned();
+        tmp_path.push(".tmp");
+        tokio::fs::write(&tmp_path, bytes).await?;
+        tokio::fs::rename(&tmp_path, path).await
+    }
+}
+
+//
+//
+//
+#[cfg(feature = "with_tokio_fs")]
+pub async fn upload_session_init_from_file(
+    secrets: &TokenSecrets,
+    client: reqwest::Client,
+    file_path: &std::path::PathBuf,
+    media_type: impl AsRef<str>,
+    media_category: MediaCategory,
+) -> Result<EndpointRet<UploadSession>, EndpointError> {
+    let crate::tokio_fs_util::Info {
+        file_size,
+        file_name: _,
+    } = crate::tokio_fs_util::info(file_path)
+        .await
+        .map_err(EndpointError::GetFileInfoFailed)?;
+    let file_sha256 = crate::tokio_fs_util::sha256(file_path)
+        .await
+        .map_err(EndpointError::OpenFileFailed)?;
+
+    let ret = upload_init(
+        secrets,
+        client,
+        file_size as usize,
+        media_type,
+        media_category,
+    )
+    .await?;
+
+    Ok(ret.map(|ok_json| UploadSession::from_init_response(&ok_json, file_size, file_sha256)))
+}
+
+// Appends the segments not yet completed, the session is saved to `session_path` after every segment.
+#[cfg(feature = "with_tokio_fs")]
+pub async fn upload_append_all_from_file_resumable(
+    secrets: &TokenSecrets,
+    client: reqwest::Client,
+    session: &mut UploadSession,
+    file_path: &std::path::PathBuf,
+    session_path: Option<&std::path::PathBuf>,
+    options: &AppendOptions,
+) -> Result<Result<(), EndpointRet<()>>, EndpointError> {
+    session.validate().map_err(EndpointError::ValidateFailed)?;
+
+    let crate::tokio_fs_util::Info {
+        file_size,
+        file_name: _,
+    } = crate::tokio_fs_util::info(file_path)
+        .await
+        .map_err(EndpointError::GetFileInfoFailed)?;
+    if file_size != session.file_size {
+        return Err(EndpointError::ValidateFailed(
+            "file does not match upload session".into(),
+        ));
+    }
+    let file_sha256 = crate::tokio_fs_util::sha256(file_path)
+        .await
+        .map_err(EndpointError::OpenFileFailed)?;
+    if file_sha256 != session.file_sha256 {
+        return Err(EndpointError::ValidateFailed(
+            "file does not match upload session".into(),
+        ));
+    }
+
+    append_pending_segments(
+        secrets.into(),
+        client,
+        session,
+        SegmentSource::File(file_path),
+        session_path,
+        options,
+    )
+    .await
+}
+
+// `reader` yields the media from its first byte, the bytes of completed segments are read and
+// dropped. Unlike the file variant it cannot be checked against the session's sha256.
+#[cfg(feature = "with_tokio")]
+pub async fn upload_append_all_from_reader_resumable(
+    secrets: &TokenSecrets,
+    client: reqwest::Client,
+    session: &mut UploadSession,
+    reader: &mut (dyn tokio::io::AsyncRead + Unpin + Send),
+    options: &AppendOptions,
+) -> Result<Result<(), EndpointRet<()>>, EndpointError> {
+    session.validate().map_err(EndpointError::ValidateFailed)?;
+
+    append_pending_segments(
+        secrets.into(),
+        client,
+        session,
+        SegmentSource::reader(reader),
+        None,
+        options,
+    )
+    .await
+}
+
+//
+#[cfg(feature = "with_tokio")]
+pub(crate) enum SegmentSource<'a> {
+    #[cfg(feature = "with_tokio_fs")]
+    File(&'a std::path::PathBuf),
+    Bytes(bytes::Bytes),
+    // Read front to back, the bytes of completed segments are skipped.
+    Reader {
+        reader: &'a mut (dyn tokio::io::AsyncRead + Unpin + Send),
+        position: usize,
+    },
+}
+
+#[cfg(feature = "with_tokio")]
+enum SegmentData {
+    #[cfg(feature = "with_tokio_fs")]
+    File(std::path::PathBuf, core::ops::Range<usize>),
+    Bytes(bytes::Bytes),
+}
+
+#[cfg(feature = "with_tokio")]
+impl<'a> SegmentSource<'a> {
+    pub(crate) fn reader(reader: &'a mut (dyn tokio::io::AsyncRead + Unpin + Send)) -> Self {
+        Self::Reader {
+            reader,
+            position: 0,
+        }
+    }
+
+    async fn segment(
+        &mut self,
+        range: core::ops::Range<usize>,
+    ) -> Result<SegmentData, EndpointError> {
+        use tokio::io::AsyncReadExt as _;
+
+        match self {
+            #[cfg(feature = "with_tokio_fs")]
+            Self::File(file_path) => Ok(SegmentData::File(file_path.to_path_buf(), range)),
+            Self::Bytes(bytes) => Ok(SegmentData::Bytes(bytes.slice(range))),
+            Self::Reader { reader, position } => {
+                if range.start < *position {
+                    return Err(EndpointError::ValidateFailed(
+                        "reader segments must be read in order".into(),
+                    ));
+                }
+                let skip = (range.start - *position) as u64;
+                let skipped =
+                    tokio::io::copy(&mut (&mut **reader).take(skip), &mut tokio::io::sink())
+                        .await
+                        .map_err(EndpointError::ReadSourceFailed)?;
+                if skipped != skip {
+                    return Err(EndpointError::ReadSourceFailed(
+                        std::io::ErrorKind::UnexpectedEof.into(),
+                    ));
+                }
+
+                let mut buf = vec![0; range.len()];
+                reader
+                    .read_exact(&mut buf)
+                    .await
+                    .map_err(EndpointError::ReadSourceFailed)?;
+                *position = range.end;
+                Ok(SegmentData::Bytes(buf.into()))
+            }
+        }
+    }
+}
+
+// On the first failure no new segment is started, the ones in flight are still awaited and recorded.
+#[cfg(feature = "with_tokio")]
+pub(crate) async fn append_pending_segments(
+    auth: MediaAuth<'_>,
+    client: reqwest::Client,
+    session: &mut UploadSession,
+    mut source: SegmentSource<'_>,
+    session_path: Option<&std::path::PathBuf>,
+    options: &AppendOptions,
+) -> Result<Result<(), EndpointRet<()>>, EndpointError> {
+    use futures_util::{stream::FuturesUnordered, StreamExt as _};
+
+    #[cfg(not(feature = "with_tokio_fs"))]
+    debug_assert!(session_path.is_none());
+
+    let concurrency = core::cmp::max(options.concurrency, 1);
+    let mut pending = session.pending_segments().into_iter();
+    let mut in_flight = FuturesUnordered::new();
+    let mut first_failure: Option<Result<Result<(), EndpointRet<()>>, EndpointError>> = None;
+
+    loop {
+        while first_failure.is_none() && in_flight.len() < concurrency {
+            let segment_index = match pending.next() {
+                Some(x) => x,
+                None => break,
+            };
+            if session.is_expired() {
+                first_failure = Some(Err(EndpointError::ValidateFailed(
+                    "upload session expired".into(),
+                )));
+                break;
+            }
+
+            let data = match source.segment(session.segment_range(segment_index)).await {
+                Ok(x) => x,
+                Err(err) => {
+                    first_failure = Some(Err(err));
+                    break;
+                }
+            };
+            let client = client.to_owned();
+            let media_id = session.media_id;
+            in_flight.push(async move {
+                let ret = upload_append_segment_with_retry(
+                    auth,
+                    client,
+                    media_id,
+                    segment_index,
+                    data,
+                    options,
+                )
+                .await;
+                (segment_index, ret)
+            });
+        }
+
+        let (segment_index, ret) = match in_flight.next().await {
+            Some(x) => x,
+            None => break,
+        };
+        match ret {
+            Ok(EndpointRet::Ok(_)) => {
+                session.mark_completed(segment_index);
+                if let Some(progress) = &options.progress {
+                    progress(UploadProgress::SegmentCompleted {
+                        segment_index,
+                        completed_segments: session.completed_segments.len(),
+                        total_segments: session.segment_count(),
+                        bytes_completed: session.bytes_completed(),
+                        total_bytes: session.file_size,
+                    });
+                }
+                #[cfg(feature = "with_tokio_fs")]
+                if let Some(session_path) = session_path {
+                    session
+                        .save(session_path)
+                        .await
+                        .map_err(EndpointError::OpenFileFailed)?;
+                }
+            }
+            Ok(x) => {
+                first_failure.get_or_insert(Ok(Err(x)));
+            }
+            Err(err) => {
+                first_failure.get_or_insert(Err(err));
+            }
+        }
+    }
+
+    first_failure.unwrap_or(Ok(Ok(())))
+}
+
+#[cfg(feature = "with_tokio")]
+async fn upload_append_segment_with_retry(
+    auth: MediaAuth<'_>,
+    client: reqwest::Client,
+    media_id: u64,
+    segment_index: usize,
+    data: SegmentData,
+    options: &AppendOptions,
+) -> Result<EndpointRet<()>, EndpointError> {
+    let mut n_retries = 0;
+    loop {
+        let ret = match (auth, &data, &options.progress) {
+            #[cfg(feature = "with_tokio_fs")]
+            (MediaAuth::V1(secrets), SegmentData::File(file_path, range), Some(progress)) => {
+                upload_append_from_file_with_progress(
+                    secrets,
+                    client.to_owned(),
+                    media_id,
+                    segment_index,
+                    file_path,
+                    range.to_owned(),
+                    progress.to_owned(),
+                )
+                .await
+            }
+            #[cfg(feature = "with_tokio_fs")]
+            (MediaAuth::V1(secrets), SegmentData::File(file_path, range), None) => {
+                upload_append_from_file(
+                    secrets,
+                    client.to_owned(),
+                    media_id,
+                    segment_index,
+                    file_path,
+                    range.to_owned(),
+                )
+                .await
+            }
+            (MediaAuth::V1(secrets), SegmentData::Bytes(bytes), progress) => {
+                upload_append(
+                    secrets,
+                    client.to_owned(),
+                    media_id,
+                    segment_index,
+                    bytes_body(bytes.to_owned(), segment_index, progress),
+                    Some(bytes.len() as u64),
+                )
+                .await
+            }
+            // v2 takes the segment from memory, files are read one segment at a time.
+            (MediaAuth::V2(token), data, progress) => {
+                let bytes = data.to_bytes().await?;
+                upload_append_v2(
+                    token,
+                    client.to_owned(),
+                    media_id,
+                    segment_index,
+                    bytes_body(bytes.to_owned(), segment_index, progress),
+                    Some(bytes.len() as u64),
+                )
+                .await
+            }
+        };
+
+        let retryable = match &ret {
+            Ok(EndpointRet::Other((status, _))) => status.is_server_error(),
+            Err(EndpointError::RespondFailed(_) | EndpointError::ReadResponseBodyFailed(_)) => true,
+            _ => false,
+        };
+        if !retryable || n_retries >= options.max_retries {
+            return ret;
+        }
+
+        n_retries += 1;
+        tokio::time::sleep(options.retry_delay).await;
+    }
+}
+
+#[cfg(feature = "with_tokio")]
+impl SegmentData {
+    async fn to_bytes(&self) -> Result<bytes::Bytes, EndpointError> {
+        match self {
+            #[cfg(feature = "with_tokio_fs")]
+            Self::File(file_path, range) => {
+                use tokio::{
+                    fs::File,
+                    io::{AsyncReadExt as _, AsyncSeekExt as _, SeekFrom},
+                };
+
+                let mut file = File::open(file_path)
+                    .await
+                    .map_err(EndpointError::OpenFileFailed)?;
+                file.seek(SeekFrom::Start(range.start as u64))
+                    .await
+                    .map_err(EndpointError::OpenFileFailed)?;
+                let mut buf = vec![0; range.len()];
+                file.read_exact(&mut buf)
+                    .await
+                    .map_err(EndpointError::ReadSourceFailed)?;
+                Ok(buf.into())
+            }
+            Self::Bytes(bytes) => Ok(bytes.to_owned()),
+        }
+    }
+}
+
+#[cfg(feature = "with_tokio")]
+fn bytes_body(
+    bytes: bytes::Bytes,
+    segment_index: usize,
+    progress: &Option<ProgressCallback>,
+) -> reqwest::Body {
+    match progress {
+        Some(progress) => bytes_body_with_progress(bytes, segment_index, progress.to_owned()),
+        None => bytes.into(),
+    }
+}
+#[cfg(feature = "with_tokio")]
+fn bytes_body_with_progress(
+    bytes: bytes::Bytes,
+    segment_index: usize,
+    progress: ProgressCallback,
+) -> reqwest::Body {
+    use futures_util::{stream, StreamExt as _};
+
+    const CHUNK_SIZE: usize = 64 * 1024;
+
+    let segment_bytes = bytes.len() as u64;
+    let chunks = (0..bytes.len())
+        .step_by(CHUNK_SIZE)
+        .map(move |i| {
+            Ok::<_, std::io::Error>(bytes.slice(i..core::cmp::min(i + CHUNK_SIZE, bytes.len())))
+        })
+        .collect::<Vec<_>>();
+
+    let mut bytes_sent = 0;
+    reqwest::Body::wrap_stream(stream::iter(chunks).inspect(move |chunk| {
+        if let Ok(chunk) = chunk {
+            bytes_sent += chunk.len() as u64;
+            progress(UploadProgress::SegmentBytesSent {
+                segment_index,
+                bytes_sent,
+                segment_bytes,
+            });
+        }
+    }))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_upload_session() {
+        let mut session = UploadSession::new(1, 10, "x", 60).with_segment_size(4);
+        assert_eq!(session.segment_count(), 3);
+        assert_eq!(session.segment_range(0), 0..4);
+        assert_eq!(session.segment_range(2), 8..10);
+        assert_eq!(session.pending_segments(), vec![0, 1, 2]);
+        assert!(session.validate().is_ok());
+
+        session.mark_completed(1);
+        assert_eq!(session.pending_segments(), vec![0, 2]);
+        assert!(!session.is_complete());
+
+        let json = serde_json::to_string(&session).unwrap();
+        assert_eq!(
+            serde_json::from_str::<UploadSession>(&json).unwrap(),
+            session
+        );
+
+        session.mark_completed(0);
+        assert_eq!(session.bytes_completed(), 8);
+        session.mark_completed(2);
+        assert!(session.is_complete());
+        assert_eq!(session.bytes_completed(), 10);
+
+        //
+        assert_eq!(UploadSession::new(1, 0, "x", 60).segment_count(), 1);
+        assert_eq!(
+            UploadSession::new(1, SEGMENT_SIZE as u64 * 2, "x", 60).segment_count(),
+            2
+        );
+
+        //
+        let session = UploadSession::new(1, 10, "x", 0);
+        assert!(session.is_expired());
+        assert_eq!(session.validate().unwrap_err(), "upload session expired");
+
+        let session = UploadSession::new(1, SEGMENT_SIZE as u64 * 1001, "x", 60);
+        assert_eq!(session.validate().unwrap_err(), "too many segments");
+    }
+}
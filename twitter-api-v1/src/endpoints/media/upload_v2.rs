@@ -0,0 +1,392 @@
+use reqwest::{
+    multipart::{Form, Part},
+    Body, Client, Response, StatusCode,
+};
+use serde_json::Map;
+use twitter_api_v2::{
+    endpoints::media::{
+        metadata::{
+            MediaMetadata, MediaMetadataAltText, MediaMetadataCreateRequestBody,
+            URL_FOR_MEDIA_METADATA_CREATE,
+        },
+        upload::{
+            url_for_media_upload_append, url_for_media_upload_finalize,
+            MediaUploadInitializeRequestBody, MediaUploadResponseBody,
+            URL_FOR_MEDIA_UPLOAD_INITIALIZE, URL_FOR_MEDIA_UPLOAD_STATUS,
+        },
+    },
+    objects::ResponseBodyErrJson as V2ResponseBodyErrJson,
+};
+
+use crate::{
+    endpoints::{
+        common::{EndpointError, EndpointRet},
+        media::{
+            metadata::{create_media_metadata, validate_alt_text},
+            upload_media::{
+                get_upload_status, upload_finalize, upload_init_with_options, UploadInitOptions,
+                UploadResponseBodyOkJson, SEGMENT_INDEX_MAX,
+            },
+        },
+    },
+    media_util,
+    objects::media::MediaCategory,
+    secrets::{TokenSecrets, UserAccessToken},
+};
+
+//
+// Selects the upload API, v1.1 on upload.twitter.com with OAuth 1.0a or v2 on api.twitter.com with OAuth 2.0.
+#[derive(Debug, Clone, Copy)]
+pub enum MediaAuth<'a> {
+    V1(&'a TokenSecrets),
+    V2(&'a UserAccessToken),
+}
+
+impl<'a> From<&'a TokenSecrets> for MediaAuth<'a> {
+    fn from(secrets: &'a TokenSecrets) -> Self {
+        Self::V1(secrets)
+    }
+}
+
+impl<'a> From<&'a UserAccessToken> for MediaAuth<'a> {
+    fn from(token: &'a UserAccessToken) -> Self {
+        Self::V2(token)
+    }
+}
+
+impl MediaAuth<'_> {
+    pub async fn upload_init(
+        &self,
+        client: Client,
+        total_bytes: usize,
+        media_type: impl AsRef<str>,
+        media_category: MediaCategory,
+        options: &UploadInitOptions,
+    ) -> Result<EndpointRet<UploadResponseBodyOkJson>, EndpointError> {
+        match self {
+            Self::V1(secrets) => {
+                upload_init_with_options(
+                    secrets,
+                    client,
+                    total_bytes,
+                    media_type,
+                    media_category,
+                    options,
+                )
+                .await
+            }
+            Self::V2(token) => {
+                upload_init_v2(
+                    token,
+                    client,
+                    total_bytes,
+                    media_type,
+                    media_category,
+                    options,
+                )
+                .await
+            }
+        }
+    }
+
+    pub async fn upload_finalize(
+        &self,
+        client: Client,
+        media_id: u64,
+    ) -> Result<EndpointRet<UploadResponseBodyOkJson>, EndpointError> {
+        match self {
+            Self::V1(secrets) => upload_finalize(secrets, client, media_id).await,
+            Self::V2(token) => upload_finalize_v2(token, client, media_id).await,
+        }
+    }
+
+    pub async fn get_upload_status(
+        &self,
+        client: Client,
+        media_id: u64,
+    ) -> Result<EndpointRet<UploadResponseBodyOkJson>, EndpointError> {
+        match self {
+            Self::V1(secrets) => get_upload_status(secrets, client, media_id).await,
+            Self::V2(token) => get_upload_status_v2(token, client, media_id).await,
+        }
+    }
+
+    pub async fn create_media_metadata(
+        &self,
+        client: Client,
+        media_id: u64,
+        alt_text: &str,
+    ) -> Result<EndpointRet<()>, EndpointError> {
+        match self {
+            Self::V1(secrets) => create_media_metadata(secrets, client, media_id, alt_text).await,
+            Self::V2(token) => create_media_metadata_v2(token, client, media_id, alt_text).await,
+        }
+    }
+}
+
+//
+//
+//
+pub async fn upload_init_v2(
+    token: &UserAccessToken,
+    client: Client,
+    total_bytes: usize,
+    media_type: impl AsRef<str>,
+    media_category: MediaCategory,
+    options: &UploadInitOptions,
+) -> Result<EndpointRet<UploadResponseBodyOkJson>, EndpointError> {
+    if options.validate_media {
+        media_util::validate(&media_category, media_type.as_ref(), total_bytes as u64)
+            .map_err(EndpointError::ValidateFailed)?;
+    }
+    options
+        .validate(&media_category)
+        .map_err(EndpointError::ValidateFailed)?;
+
+    //
+    let body = MediaUploadInitializeRequestBody {
+        media_type: media_type.as_ref().into(),
+        total_bytes,
+        media_category: media_category.to_string(),
+        additional_owners: (!options.additional_owners.is_empty()).then(|| {
+            options
+                .additional_owners
+                .iter()
+                .map(|x| x.to_string())
+                .collect()
+        }),
+        shared: options.shared.then_some(true),
+    };
+
+    //
+    let response = client
+        .post(URL_FOR_MEDIA_UPLOAD_INITIALIZE)
+        .bearer_auth(&token.access_token)
+        .json(&body)
+        .send()
+        .await
+        .map_err(|err| EndpointError::RespondFailed(err.into()))?;
+
+    media_upload_ret(response).await
+}
+
+pub async fn upload_append_v2<T>(
+    token: &UserAccessToken,
+    client: Client,
+    media_id: u64,
+    segment_index: usize,
+    stream: T,
+    stream_length: Option<u64>,
+) -> Result<EndpointRet<()>, EndpointError>
+where
+    T: Into<Body>,
+{
+    if segment_index > SEGMENT_INDEX_MAX {
+        return Err(EndpointError::ValidateFailed(
+            "segment_index invalid".into(),
+        ));
+    }
+
+    //
+    let part = if let Some(stream_length) = stream_length {
+        Part::stream_with_length(stream, stream_length)
+    } else {
+        Part::stream(stream)
+    };
+
+    let form = Form::new()
+        .text("segment_index", segment_index.to_string())
+        .part("media", part)
+        .percent_encode_noop();
+
+    //
+    let response = client
+        .post(url_for_media_upload_append(media_id))
+        .bearer_auth(&token.access_token)
+        .multipart(form)
+        .send()
+        .await
+        .map_err(|err| EndpointError::RespondFailed(err.into()))?;
+
+    //
+    let response_status = response.status();
+    let response_headers = response.headers().to_owned();
+    let response_body = response
+        .bytes()
+        .await
+        .map_err(EndpointError::ReadResponseBodyFailed)?;
+    let response_body = response_body.as_ref();
+
+    match response_status {
+        StatusCode::OK | StatusCode::NO_CONTENT => Ok(EndpointRet::Ok(())),
+        status => Ok(EndpointRet::from_err_response::<V2ResponseBodyErrJson>(
+            status,
+            &response_headers,
+            response_body,
+        )),
+    }
+}
+
+pub async fn upload_finalize_v2(
+    token: &UserAccessToken,
+    client: Client,
+    media_id: u64,
+) -> Result<EndpointRet<UploadResponseBodyOkJson>, EndpointError> {
+    let response = client
+        .post(url_for_media_upload_finalize(media_id))
+        .bearer_auth(&token.access_token)
+        .send()
+        .await
+        .map_err(|err| EndpointError::RespondFailed(err.into()))?;
+
+    media_upload_ret(response).await
+}
+
+pub async fn get_upload_status_v2(
+    token: &UserAccessToken,
+    client: Client,
+    media_id: u64,
+) -> Result<EndpointRet<UploadResponseBodyOkJson>, EndpointError> {
+    //
+    let mut query = Map::new();
+    query.insert("command".into(), "STATUS".into());
+    query.insert("media_id".into(), media_id.to_string().into());
+
+    //
+    let response = client
+        .get(URL_FOR_MEDIA_UPLOAD_STATUS)
+        .bearer_auth(&token.access_token)
+        .query(&query)
+        .send()
+        .await
+        .map_err(|err| EndpointError::RespondFailed(err.into()))?;
+
+    media_upload_ret(response).await
+}
+
+pub async fn create_media_metadata_v2(
+    token: &UserAccessToken,
+    client: Client,
+    media_id: u64,
+    alt_text: &str,
+) -> Result<EndpointRet<()>, EndpointError> {
+    validate_alt_text(alt_text).map_err(EndpointError::ValidateFailed)?;
+
+    //
+    let body = MediaMetadataCreateRequestBody {
+        id: media_id.to_string(),
+        metadata: MediaMetadata {
+            alt_text: Some(MediaMetadataAltText {
+                text: alt_text.into(),
+            }),
+        },
+    };
+
+    //
+    let response = client
+        .post(URL_FOR_MEDIA_METADATA_CREATE)
+        .bearer_auth(&token.access_token)
+        .json(&body)
+        .send()
+        .await
+        .map_err(|err| EndpointError::RespondFailed(err.into()))?;
+
+    //
+    let response_status = response.status();
+    let response_headers = response.headers().to_owned();
+    let response_body = response
+        .bytes()
+        .await
+        .map_err(EndpointError::ReadResponseBodyFailed)?;
+    let response_body = response_body.as_ref();
+
+    match response_status {
+        StatusCode::OK => Ok(EndpointRet::Ok(())),
+        status => Ok(EndpointRet::from_err_response::<V2ResponseBodyErrJson>(
+            status,
+            &response_headers,
+            response_body,
+        )),
+    }
+}
+
+//
+async fn media_upload_ret(
+    response: Response,
+) -> Result<EndpointRet<UploadResponseBodyOkJson>, EndpointError> {
+    let response_status = response.status();
+    let response_headers = response.headers().to_owned();
+    let response_body = response
+        .bytes()
+        .await
+        .map_err(EndpointError::ReadResponseBodyFailed)?;
+    let response_body = response_body.as_ref();
+
+    match response_status {
+        StatusCode::OK | StatusCode::CREATED | StatusCode::ACCEPTED => {
+            let response_body = serde_json::from_slice::<MediaUploadResponseBody>(response_body)
+                .map_err(EndpointError::DeV2ResponseBodyOkJsonFailed)?;
+            let ok_json = UploadResponseBodyOkJson::try_from(response_body)
+                .map_err(EndpointError::ConvertV2ResponseBodyOkJsonFailed)?;
+            Ok(EndpointRet::Ok(ok_json))
+        }
+        status => Ok(EndpointRet::from_err_response::<V2ResponseBodyErrJson>(
+            status,
+            &response_headers,
+            response_body,
+        )),
+    }
+}
+
+impl TryFrom<MediaUploadResponseBody> for UploadResponseBodyOkJson {
+    type Error = String;
+    fn try_from(value: MediaUploadResponseBody) -> Result<Self, Self::Error> {
+        let data = value.data.ok_or("data missing")?;
+        let processing_info = data
+            .processing_info
+            .map(|x| serde_json::from_value(x.into()))
+            .transpose()
+            .map_err(|err| format!("processing_info invalid, {err}"))?;
+
+        Ok(Self {
+            media_id: data.id,
+            media_id_string: data.id.to_string(),
+            media_key: data.media_key.unwrap_or_default(),
+            size: data.size,
+            expires_after_secs: data.expires_after_secs,
+            image: None,
+            video: None,
+            processing_info,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::objects::media::MediaProcessingInfoState;
+
+    #[test]
+    fn test_try_from_v2_response_body() {
+        let body = serde_json::from_str::<MediaUploadResponseBody>(
+            r#"{"data":{"id":"1880028106020515840","media_key":"7_1880028106020515840","expires_after_secs":86400,"processing_info":{"state":"in_progress","check_after_secs":5,"progress_percent":42}}}"#,
+        )
+        .unwrap();
+        let ok_json = UploadResponseBodyOkJson::try_from(body).unwrap();
+        assert_eq!(ok_json.media_id, 1880028106020515840);
+        assert_eq!(ok_json.media_key, "7_1880028106020515840");
+        let processing_info = ok_json.processing_info.unwrap();
+        assert_eq!(processing_info.state, MediaProcessingInfoState::InProgress);
+        assert_eq!(processing_info.check_after_secs, Some(5));
+
+        let body = serde_json::from_str::<MediaUploadResponseBody>(
+            r#"{"errors":[{"title":"Not Found Error","detail":"x","type":"https://api.twitter.com/2/problems/resource-not-found"}]}"#,
+        )
+        .unwrap();
+        assert_eq!(
+            UploadResponseBodyOkJson::try_from(body).unwrap_err(),
+            "data missing"
+        );
+    }
+}
@@ -5,4 +5,4 @@ pub mod users;
 
 //
 pub mod common;
-pub use common::{EndpointError, EndpointRet};
+pub use common::{EndpointError, EndpointRet, RateLimit, V2Data};
@@ -0,0 +1,275 @@
+use std::collections::HashSet;
+
+use reqwest::{Client, StatusCode};
+use reqwest_oauth1::OAuthClientProvider as _;
+use serde_json::Map;
+use twitter_api_v2::{
+    endpoints::{
+        common::ResponseBodyIncludes,
+        fields::TweetField,
+        tweets::lookup::{
+            url_for_tweet_by_id, MultipleTweetsResponseBody, SingleTweetResponseBody,
+            TWEETS_LOOKUP_IDS_MAX, URL_FOR_TWEETS_LOOKUP,
+        },
+        FieldsQuery,
+    },
+    objects::{PartialError, ResponseBodyErrJson as V2ResponseBodyErrJson, Tweet},
+};
+
+use crate::{
+    endpoints::common::{EndpointError, EndpointRet, V2Data},
+    secrets::TokenSecrets,
+};
+
+// All public tweet.fields, non_public_metrics and organic_metrics need the author's context.
+pub fn tweet_fields_default() -> FieldsQuery {
+    FieldsQuery::new().tweet_fields([
+        TweetField::Attachments,
+        TweetField::AuthorId,
+        TweetField::ContextAnnotations,
+        TweetField::ConversationId,
+        TweetField::CreatedAt,
+        TweetField::EditControls,
+        TweetField::EditHistoryTweetIds,
+        TweetField::Entities,
+        TweetField::InReplyToUserId,
+        TweetField::Lang,
+        TweetField::NoteTweet,
+        TweetField::PossiblySensitive,
+        TweetField::PublicMetrics,
+        TweetField::ReferencedTweets,
+        TweetField::ReplySettings,
+        TweetField::Withheld,
+    ])
+}
+
+//
+//
+//
+pub async fn show_tweet_by_id(
+    secrets: &TokenSecrets,
+    client: Client,
+    tweet_id: u64,
+) -> Result<EndpointRet<Tweet>, EndpointError> {
+    Ok(
+        show_tweet_by_id_with_fields(secrets, client, tweet_id, &tweet_fields_default())
+            .await?
+            .map(|x| x.data),
+    )
+}
+
+pub async fn show_tweet_by_id_with_fields(
+    secrets: &TokenSecrets,
+    client: Client,
+    tweet_id: u64,
+    fields: &FieldsQuery,
+) -> Result<EndpointRet<V2Data<Tweet>>, EndpointError> {
+    //
+    let url = url_for_tweet_by_id(tweet_id);
+
+    let mut query = Map::new();
+    for (name, value) in fields.query_pairs() {
+        query.insert(name.into(), value.into());
+    }
+
+    //
+    let response = client
+        .oauth1(secrets.secrets())
+        .get(url)
+        .query(&query)
+        .send()
+        .await
+        .map_err(EndpointError::RespondFailed)?;
+
+    //
+    let response_status = response.status();
+    let response_headers = response.headers().to_owned();
+    let response_body = response
+        .bytes()
+        .await
+        .map_err(EndpointError::ReadResponseBodyFailed)?;
+    let response_body = response_body.as_ref();
+
+    match response_status {
+        StatusCode::OK => {
+            let response_body = serde_json::from_slice::<SingleTweetResponseBody>(response_body)
+                .map_err(EndpointError::DeV2ResponseBodyOkJsonFailed)?;
+            Ok(EndpointRet::from_v2_response_body(response_body))
+        }
+        status => Ok(EndpointRet::from_err_response::<V2ResponseBodyErrJson>(
+            status,
+            &response_headers,
+            response_body,
+        )),
+    }
+}
+
+//
+//
+//
+#[derive(Debug, Clone, Default)]
+pub struct TweetsLookup {
+    pub tweets: Vec<Tweet>,
+    pub includes: ResponseBodyIncludes,
+    pub not_found_ids: Vec<u64>,
+    // Partial errors other than not found, e.g. tweets of protected users.
+    pub errors: Vec<PartialError>,
+    // Set when a chunk after the first did not respond with 200, the fields above only cover the
+    // chunks before it.
+    pub failure: Option<TweetsLookupFailure>,
+}
+
+#[derive(Debug, Clone)]
+pub struct TweetsLookupFailure {
+    // The ids of the failed chunk and of all chunks after it, none of them were looked up.
+    pub ids: Vec<u64>,
+    pub ret: EndpointRet<()>,
+}
+
+impl TweetsLookup {
+    fn merge(&mut self, response_body: MultipleTweetsResponseBody) {
+        if let Some(includes) = response_body.includes {
+            self.includes.extend(includes);
+        }
+        self.tweets.extend(response_body.data.unwrap_or_default());
+        for err in response_body.errors {
+            match err
+                .resource_id
+                .as_deref()
+                .and_then(|x| x.parse::<u64>().ok())
+            {
+                Some(id) if err.is_resource_not_found() => self.not_found_ids.push(id),
+                _ => self.errors.push(err),
+            }
+        }
+    }
+}
+
+// More than TWEETS_LOOKUP_IDS_MAX ids are split into several requests, duplicates are dropped.
+// When the first chunk does not respond with 200 it is returned as is, when a later one fails the
+// lookup stops and the results so far are returned with `failure` set.
+pub async fn lookup_tweets(
+    secrets: &TokenSecrets,
+    client: Client,
+    tweet_ids: &[u64],
+) -> Result<EndpointRet<TweetsLookup>, EndpointError> {
+    lookup_tweets_with_fields(secrets, client, tweet_ids, &tweet_fields_default()).await
+}
+
+pub async fn lookup_tweets_with_fields(
+    secrets: &TokenSecrets,
+    client: Client,
+    tweet_ids: &[u64],
+    fields: &FieldsQuery,
+) -> Result<EndpointRet<TweetsLookup>, EndpointError> {
+    let mut seen = HashSet::with_capacity(tweet_ids.len());
+    let ids = tweet_ids
+        .iter()
+        .copied()
+        .filter(|x| seen.insert(*x))
+        .collect::<Vec<_>>();
+    if ids.is_empty() {
+        return Err(EndpointError::ValidateFailed("tweet_ids is empty".into()));
+    }
+
+    let mut lookup = TweetsLookup::default();
+    for (i, chunk) in ids.chunks(TWEETS_LOOKUP_IDS_MAX).enumerate() {
+        let mut query = Map::new();
+        query.insert(
+            "ids".into(),
+            chunk
+                .iter()
+                .map(|x| x.to_string())
+                .collect::<Vec<_>>()
+                .join(",")
+                .into(),
+        );
+        for (name, value) in fields.query_pairs() {
+            query.insert(name.into(), value.into());
+        }
+
+        //
+        let response = client
+            .clone()
+            .oauth1(secrets.secrets())
+            .get(URL_FOR_TWEETS_LOOKUP)
+            .query(&query)
+            .send()
+            .await
+            .map_err(EndpointError::RespondFailed)?;
+
+        //
+        let response_status = response.status();
+        let response_headers = response.headers().to_owned();
+        let response_body = response
+            .bytes()
+            .await
+            .map_err(EndpointError::ReadResponseBodyFailed)?;
+        let response_body = response_body.as_ref();
+
+        match response_status {
+            StatusCode::OK => {
+                let response_body =
+                    serde_json::from_slice::<MultipleTweetsResponseBody>(response_body)
+                        .map_err(EndpointError::DeV2ResponseBodyOkJsonFailed)?;
+                lookup.merge(response_body);
+            }
+            status => {
+                let ret = EndpointRet::<()>::from_err_response::<V2ResponseBodyErrJson>(
+                    status,
+                    &response_headers,
+                    response_body,
+                );
+                if i == 0 {
+                    return Ok(ret.map(|_| TweetsLookup::default()));
+                }
+                lookup.failure = Some(TweetsLookupFailure {
+                    ids: ids[i * TWEETS_LOOKUP_IDS_MAX..].to_vec(),
+                    ret,
+                });
+                break;
+            }
+        }
+    }
+
+    Ok(EndpointRet::Ok(lookup))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_merge() {
+        let mut lookup = TweetsLookup::default();
+        lookup.merge(
+            serde_json::from_str(
+                r#"{
+                    "data": [{"id": "2", "text": "a", "edit_history_tweet_ids": ["2"]}],
+                    "errors": [
+                        {"value": "1", "detail": "Could not find tweet with ids: [1].", "title": "Not Found Error", "resource_type": "tweet", "parameter": "ids", "resource_id": "1", "type": "https://api.twitter.com/2/problems/resource-not-found"},
+                        {"value": "3", "detail": "Sorry, you are not authorized to see the Tweet with ids: [3].", "title": "Authorization Error", "resource_type": "tweet", "parameter": "ids", "resource_id": "3", "type": "https://api.twitter.com/2/problems/not-authorized-for-resource"}
+                    ],
+                    "includes": {"users": [{"id": "10", "name": "a", "username": "a"}]}
+                }"#,
+            )
+            .unwrap(),
+        );
+        lookup.merge(
+            serde_json::from_str(
+                r#"{
+                    "errors": [
+                        {"value": "4", "detail": "Could not find tweet with ids: [4].", "title": "Not Found Error", "resource_type": "tweet", "parameter": "ids", "resource_id": "4", "type": "https://api.twitter.com/2/problems/resource-not-found"}
+                    ]
+                }"#,
+            )
+            .unwrap(),
+        );
+
+        assert_eq!(lookup.tweets.len(), 1);
+        assert_eq!(lookup.not_found_ids, vec![1, 4]);
+        assert_eq!(lookup.errors.len(), 1);
+        assert_eq!(lookup.errors[0].resource_id.as_deref(), Some("3"));
+        assert_eq!(lookup.includes.users.len(), 1);
+    }
+}
@@ -4,7 +4,9 @@ use serde::{Deserialize, Serialize};
 use serde_json::{Map, Value};
 use twitter_api_v2::{
     endpoints::tweets::manage_tweets::{
+        url_for_tweets_delete as v2_url_for_tweets_delete, CreateTweetRequest,
         TweetsCreateResponseBody as V2TweetsCreateResponseBody,
+        TweetsDeleteResponseBody as V2TweetsDeleteResponseBody,
         URL_FOR_TWEETS_CREATE as V2_URL_FOR_TWEETS_CREATE,
     },
     objects::ResponseBodyErrJson as V2ResponseBodyErrJson,
@@ -53,17 +55,36 @@ pub async fn create_tweet(
         form.append(&mut other_parameters);
     }
 
-    //
+    send_create_tweet(secrets, client, &form).await
+}
+
+// Validated before sending, e.g. poll and media are mutually exclusive.
+pub async fn create_tweet_with_request(
+    secrets: &TokenSecrets,
+    client: Client,
+    request: &CreateTweetRequest,
+) -> Result<EndpointRet<CreateTweetResponseBodyOkJson>, EndpointError> {
+    request.validate().map_err(EndpointError::ValidateFailed)?;
+
+    send_create_tweet(secrets, client, request).await
+}
+
+async fn send_create_tweet(
+    secrets: &TokenSecrets,
+    client: Client,
+    body: &impl Serialize,
+) -> Result<EndpointRet<CreateTweetResponseBodyOkJson>, EndpointError> {
     let response = client
         .oauth1(secrets.secrets())
         .post(V2_URL_FOR_TWEETS_CREATE)
-        .json(&form)
+        .json(body)
         .send()
         .await
         .map_err(EndpointError::RespondFailed)?;
 
     //
     let response_status = response.status();
+    let response_headers = response.headers().to_owned();
     let response_body = response
         .bytes()
         .await
@@ -74,14 +95,15 @@ pub async fn create_tweet(
         StatusCode::CREATED => {
             let response_body = serde_json::from_slice::<V2TweetsCreateResponseBody>(response_body)
                 .map_err(EndpointError::DeV2ResponseBodyOkJsonFailed)?;
-            Ok(EndpointRet::Ok(CreateTweetResponseBodyOkJson::from(
-                response_body,
-            )))
+            let ok_json = CreateTweetResponseBodyOkJson::try_from(response_body)
+                .map_err(EndpointError::ConvertV2ResponseBodyOkJsonFailed)?;
+            Ok(EndpointRet::Ok(ok_json))
         }
-        status => match serde_json::from_slice::<V2ResponseBodyErrJson>(response_body) {
-            Ok(err_json) => Ok(EndpointRet::Other((status, Ok(err_json.into())))),
-            Err(_) => Ok(EndpointRet::Other((status, Err(response_body.to_owned())))),
-        },
+        status => Ok(EndpointRet::from_err_response::<V2ResponseBodyErrJson>(
+            status,
+            &response_headers,
+            response_body,
+        )),
     }
 }
 
@@ -93,11 +115,69 @@ pub struct CreateTweetResponseBodyOkJson {
 }
 
 //
-impl From<V2TweetsCreateResponseBody> for CreateTweetResponseBodyOkJson {
-    fn from(value: V2TweetsCreateResponseBody) -> Self {
-        Self {
-            id: value.data.id,
-            id_string: value.data.id.to_string(),
+impl TryFrom<V2TweetsCreateResponseBody> for CreateTweetResponseBodyOkJson {
+    type Error = String;
+    fn try_from(value: V2TweetsCreateResponseBody) -> Result<Self, Self::Error> {
+        let data = value.data.ok_or("data missing")?;
+        Ok(Self {
+            id: data.id,
+            id_string: data.id.to_string(),
+        })
+    }
+}
+
+//
+//
+//
+pub async fn delete_tweet(
+    secrets: &TokenSecrets,
+    client: Client,
+    tweet_id: u64,
+) -> Result<EndpointRet<DeleteTweetResponseBodyOkJson>, EndpointError> {
+    let response = client
+        .oauth1(secrets.secrets())
+        .delete(v2_url_for_tweets_delete(tweet_id))
+        .send()
+        .await
+        .map_err(EndpointError::RespondFailed)?;
+
+    //
+    let response_status = response.status();
+    let response_headers = response.headers().to_owned();
+    let response_body = response
+        .bytes()
+        .await
+        .map_err(EndpointError::ReadResponseBodyFailed)?;
+    let response_body = response_body.as_ref();
+
+    match response_status {
+        StatusCode::OK => {
+            let response_body = serde_json::from_slice::<V2TweetsDeleteResponseBody>(response_body)
+                .map_err(EndpointError::DeV2ResponseBodyOkJsonFailed)?;
+            let ok_json = DeleteTweetResponseBodyOkJson::try_from(response_body)
+                .map_err(EndpointError::ConvertV2ResponseBodyOkJsonFailed)?;
+            Ok(EndpointRet::Ok(ok_json))
         }
+        status => Ok(EndpointRet::from_err_response::<V2ResponseBodyErrJson>(
+            status,
+            &response_headers,
+            response_body,
+        )),
+    }
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone)]
+pub struct DeleteTweetResponseBodyOkJson {
+    pub deleted: bool,
+}
+
+//
+impl TryFrom<V2TweetsDeleteResponseBody> for DeleteTweetResponseBodyOkJson {
+    type Error = String;
+    fn try_from(value: V2TweetsDeleteResponseBody) -> Result<Self, Self::Error> {
+        let data = value.data.ok_or("data missing")?;
+        Ok(Self {
+            deleted: data.deleted,
+        })
     }
 }
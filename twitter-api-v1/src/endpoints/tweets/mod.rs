@@ -1,2 +1,3 @@
 //
+pub mod lookup;
 pub mod manage_tweets;
@@ -2,14 +2,16 @@ use reqwest::{Client, StatusCode};
 use reqwest_oauth1::OAuthClientProvider as _;
 use serde_json::Map;
 use twitter_api_v2::{
-    endpoints::users::lookup::{
-        url_for_user_by_id, url_for_user_by_username, SingleUserResponseBody,
+    endpoints::{
+        fields::{Expansion, UserField},
+        users::lookup::{url_for_user_by_id, url_for_user_by_username, SingleUserResponseBody},
+        FieldsQuery,
     },
-    objects::ResponseBodyErrJson as V2ResponseBodyErrJson,
+    objects::{ResponseBodyErrJson as V2ResponseBodyErrJson, User as V2User},
 };
 
 use crate::{
-    endpoints::common::{EndpointError, EndpointRet},
+    endpoints::common::{EndpointError, EndpointRet, V2Data},
     objects::User,
     secrets::TokenSecrets,
 };
@@ -23,48 +25,20 @@ pub async fn show_user_by_id(
     user_id: u64,
     include_entities: Option<bool>,
 ) -> Result<EndpointRet<User>, EndpointError> {
-    //
-    let url = url_for_user_by_id(user_id);
-
-    let mut query = Map::new();
-    query.insert(
-        "user.fields".into(),
-        "id,name,username,profile_image_url".into(),
-    );
-    if include_entities == Some(true) {
-        query.insert("expansions".into(), "pinned_tweet_id".into());
-    }
-
-    //
-    let response = client
-        .oauth1(secrets.secrets())
-        .get(url)
-        .query(&query)
-        .send()
-        .await
-        .map_err(EndpointError::RespondFailed)?;
-
-    //
-    let response_status = response.status();
-    let response_body = response
-        .bytes()
-        .await
-        .map_err(EndpointError::ReadResponseBodyFailed)?;
-    let response_body = response_body.as_ref();
+    show_user_by_id_with_fields(secrets, client, user_id, &user_fields(include_entities))
+        .await?
+        .try_map(|x| {
+            User::try_from(x.data).map_err(EndpointError::ConvertV2ResponseBodyOkJsonFailed)
+        })
+}
 
-    match response_status {
-        StatusCode::OK => {
-            let response_body = serde_json::from_slice::<SingleUserResponseBody>(response_body)
-                .map_err(EndpointError::DeV2ResponseBodyOkJsonFailed)?;
-            let user = User::try_from(response_body.data)
-                .map_err(EndpointError::ConvertV2ResponseBodyOkJsonFailed)?;
-            Ok(EndpointRet::Ok(user))
-        }
-        status => match serde_json::from_slice::<V2ResponseBodyErrJson>(response_body) {
-            Ok(err_json) => Ok(EndpointRet::Other((status, Ok(err_json.into())))),
-            Err(_) => Ok(EndpointRet::Other((status, Err(response_body.to_owned())))),
-        },
-    }
+pub async fn show_user_by_id_with_fields(
+    secrets: &TokenSecrets,
+    client: Client,
+    user_id: u64,
+    fields: &FieldsQuery,
+) -> Result<EndpointRet<V2Data<V2User>>, EndpointError> {
+    show_user(secrets, client, url_for_user_by_id(user_id), fields).await
 }
 
 //
@@ -76,16 +50,55 @@ pub async fn show_user_by_screen_name(
     screen_name: impl AsRef<str>,
     include_entities: Option<bool>,
 ) -> Result<EndpointRet<User>, EndpointError> {
-    //
-    let url = url_for_user_by_username(screen_name);
+    show_user_by_screen_name_with_fields(
+        secrets,
+        client,
+        screen_name,
+        &user_fields(include_entities),
+    )
+    .await?
+    .try_map(|x| User::try_from(x.data).map_err(EndpointError::ConvertV2ResponseBodyOkJsonFailed))
+}
 
-    let mut query = Map::new();
-    query.insert(
-        "user.fields".into(),
-        "id,name,username,profile_image_url".into(),
-    );
+pub async fn show_user_by_screen_name_with_fields(
+    secrets: &TokenSecrets,
+    client: Client,
+    screen_name: impl AsRef<str>,
+    fields: &FieldsQuery,
+) -> Result<EndpointRet<V2Data<V2User>>, EndpointError> {
+    show_user(
+        secrets,
+        client,
+        url_for_user_by_username(screen_name),
+        fields,
+    )
+    .await
+}
+
+// What the v1 User conversion needs.
+fn user_fields(include_entities: Option<bool>) -> FieldsQuery {
+    let fields = FieldsQuery::new().user_fields([
+        UserField::Id,
+        UserField::Name,
+        UserField::Username,
+        UserField::ProfileImageUrl,
+    ]);
     if include_entities == Some(true) {
-        query.insert("expansions".into(), "pinned_tweet_id".into());
+        fields.expansions([Expansion::PinnedTweetId])
+    } else {
+        fields
+    }
+}
+
+async fn show_user(
+    secrets: &TokenSecrets,
+    client: Client,
+    url: String,
+    fields: &FieldsQuery,
+) -> Result<EndpointRet<V2Data<V2User>>, EndpointError> {
+    let mut query = Map::new();
+    for (name, value) in fields.query_pairs() {
+        query.insert(name.into(), value.into());
     }
 
     //
@@ -99,6 +112,7 @@ pub async fn show_user_by_screen_name(
 
     //
     let response_status = response.status();
+    let response_headers = response.headers().to_owned();
     let response_body = response
         .bytes()
         .await
@@ -109,13 +123,12 @@ pub async fn show_user_by_screen_name(
         StatusCode::OK => {
             let response_body = serde_json::from_slice::<SingleUserResponseBody>(response_body)
                 .map_err(EndpointError::DeV2ResponseBodyOkJsonFailed)?;
-            let user = User::try_from(response_body.data)
-                .map_err(EndpointError::ConvertV2ResponseBodyOkJsonFailed)?;
-            Ok(EndpointRet::Ok(user))
+            Ok(EndpointRet::from_v2_response_body(response_body))
         }
-        status => match serde_json::from_slice::<V2ResponseBodyErrJson>(response_body) {
-            Ok(err_json) => Ok(EndpointRet::Other((status, Ok(err_json.into())))),
-            Err(_) => Ok(EndpointRet::Other((status, Err(response_body.to_owned())))),
-        },
+        status => Ok(EndpointRet::from_err_response::<V2ResponseBodyErrJson>(
+            status,
+            &response_headers,
+            response_body,
+        )),
     }
 }
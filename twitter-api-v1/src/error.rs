@@ -0,0 +1,354 @@
+use reqwest::StatusCode;
+use twitter_api_v2::objects::ProblemType;
+
+use crate::{
+    endpoints::common::{EndpointError, EndpointRet, RateLimit},
+    objects::{ErrorCode, MediaProcessingInfoError, ResponseBodyErrJson},
+};
+
+//
+#[derive(Debug)]
+#[non_exhaustive]
+pub enum TwitterError {
+    Transport(EndpointError),
+    Decode(EndpointError),
+    Validation(String),
+    Api {
+        status: StatusCode,
+        code: Option<ErrorCode>,
+        message: Option<String>,
+        body: Result<ResponseBodyErrJson, Vec<u8>>,
+    },
+    RateLimited {
+        rate_limit: Option<RateLimit>,
+        body: Result<ResponseBodyErrJson, Vec<u8>>,
+    },
+    // The v2 request succeeded but returned only partial errors, e.g. not found.
+    PartialErrors {
+        code: Option<ErrorCode>,
+        body: ResponseBodyErrJson,
+    },
+    ProcessingFailed {
+        media_id: u64,
+        error: Option<MediaProcessingInfoError>,
+    },
+    // The upload_media* deadline passed, transport timeouts are Transport.
+    Timeout {
+        media_id: Option<u64>,
+    },
+}
+
+impl TwitterError {
+    pub fn is_retryable(&self) -> bool {
+        match self {
+            Self::Transport(_) => true,
+            Self::Decode(_) | Self::Validation(_) => false,
+            Self::Api { status, code, .. } => {
+                status.is_server_error()
+                    || matches!(
+                        code,
+                        Some(ErrorCode::OverCapacity | ErrorCode::InternalError)
+                    )
+            }
+            Self::RateLimited { .. } => true,
+            Self::PartialErrors { .. } => false,
+            // Retrying would upload the whole media again.
+            Self::ProcessingFailed { .. } | Self::Timeout { .. } => false,
+        }
+    }
+
+    pub fn rate_limit(&self) -> Option<&RateLimit> {
+        match self {
+            Self::RateLimited { rate_limit, .. } => rate_limit.as_ref(),
+            _ => None,
+        }
+    }
+
+    pub fn status(&self) -> Option<StatusCode> {
+        match self {
+            Self::Api { status, .. } => Some(*status),
+            Self::RateLimited { .. } => Some(StatusCode::TOO_MANY_REQUESTS),
+            _ => None,
+        }
+    }
+
+    pub fn code(&self) -> Option<ErrorCode> {
+        match self {
+            Self::Api { code, .. } | Self::PartialErrors { code, .. } => *code,
+            _ => None,
+        }
+    }
+
+    pub fn problem_type(&self) -> Option<&ProblemType> {
+        match self {
+            Self::Api { body: Ok(body), .. } | Self::RateLimited { body: Ok(body), .. } => {
+                body.problem_type()
+            }
+            Self::PartialErrors { body, .. } => body.problem_type(),
+            _ => None,
+        }
+    }
+}
+
+//
+impl core::fmt::Display for TwitterError {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        match self {
+            Self::Transport(err) => write!(f, "transport failed: {err}"),
+            Self::Decode(err) => write!(f, "decode failed: {err}"),
+            Self::Validation(msg) => write!(f, "validation failed: {msg}"),
+            Self::Api {
+                status,
+                code,
+                message,
+                body,
+            } => {
+                write!(f, "api error, status: {status}")?;
+                if let Some(code) = code {
+                    write!(f, ", code: {}", i64::from(*code))?;
+                }
+                if let Some(problem_type) = body.as_ref().ok().and_then(|x| x.problem_type()) {
+                    write!(f, ", type: {problem_type}")?;
+                }
+                match (message, body) {
+                    (Some(message), _) => write!(f, ", message: {message}"),
+                    (None, Err(body)) if !body.is_empty() => {
+                        write!(f, ", body: {}", String::from_utf8_lossy(body))
+                    }
+                    _ => Ok(()),
+                }
+            }
+            Self::RateLimited { rate_limit, .. } => {
+                write!(f, "rate limited")?;
+                if let Some(rate_limit) = rate_limit {
+                    write!(f, ", reset after {}s", rate_limit.reset_after().as_secs())?;
+                }
+                Ok(())
+            }
+            Self::PartialErrors { code, body } => {
+                write!(f, "partial errors")?;
+                if let Some(code) = code {
+                    write!(f, ", code: {}", i64::from(*code))?;
+                }
+                if let Some(problem_type) = body.problem_type() {
+                    write!(f, ", type: {problem_type}")?;
+                }
+                if let Some(first_error) = body.first_error() {
+                    write!(f, ", message: {}", first_error.message)?;
+                }
+                Ok(())
+            }
+            Self::ProcessingFailed { media_id, error } => {
+                write!(f, "media processing failed, media_id: {media_id}")?;
+                if let Some(error) = error {
+                    write!(f, ", name: {}, message: {}", error.name, error.message)?;
+                }
+                Ok(())
+            }
+            Self::Timeout { media_id } => {
+                write!(f, "timed out")?;
+                if let Some(media_id) = media_id {
+                    write!(f, ", media_id: {media_id}")?;
+                }
+                Ok(())
+            }
+        }
+    }
+}
+
+impl std::error::Error for TwitterError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Self::Transport(err) | Self::Decode(err) => Some(err),
+            _ => None,
+        }
+    }
+}
+
+//
+impl From<EndpointError> for TwitterError {
+    fn from(err: EndpointError) -> Self {
+        match err {
+            EndpointError::ValidateFailed(msg) => Self::Validation(msg),
+            EndpointError::RespondFailed(_) | EndpointError::ReadResponseBodyFailed(_) => {
+                Self::Transport(err)
+            }
+            EndpointError::DeResponseBodyOkJsonFailed(_)
+            | EndpointError::DeV2ResponseBodyOkJsonFailed(_)
+            | EndpointError::ConvertV2ResponseBodyOkJsonFailed(_) => Self::Decode(err),
+            #[cfg(feature = "with_tokio")]
+            EndpointError::ReadSourceFailed(_) | EndpointError::WriteTargetFailed(_) => {
+                Self::Transport(err)
+            }
+            #[cfg(feature = "with_tokio_fs")]
+            EndpointError::GetFileInfoFailed(_) | EndpointError::OpenFileFailed(_) => {
+                Self::Transport(err)
+            }
+        }
+    }
+}
+
+//
+pub trait IntoResult<T> {
+    fn into_result(self) -> Result<T, TwitterError>;
+}
+
+impl<T> IntoResult<T> for EndpointRet<T> {
+    fn into_result(self) -> Result<T, TwitterError> {
+        match self {
+            EndpointRet::Ok(x) => Ok(x),
+            EndpointRet::Other((status, body)) => {
+                let first_error = body.as_ref().ok().and_then(|x| x.first_error());
+                Err(TwitterError::Api {
+                    status,
+                    code: first_error.filter(|x| x.code != 0).map(|x| x.error_code()),
+                    message: first_error.map(|x| x.message.to_owned()),
+                    body,
+                })
+            }
+            EndpointRet::RateLimited((rate_limit, body)) => {
+                Err(TwitterError::RateLimited { rate_limit, body })
+            }
+            EndpointRet::PartialErrors(body) => Err(TwitterError::PartialErrors {
+                code: body
+                    .first_error()
+                    .filter(|x| x.code != 0)
+                    .map(|x| x.error_code()),
+                body,
+            }),
+        }
+    }
+}
+
+impl<T> IntoResult<T> for Result<EndpointRet<T>, EndpointError> {
+    fn into_result(self) -> Result<T, TwitterError> {
+        self?.into_result()
+    }
+}
+
+impl IntoResult<()> for Result<Result<(), EndpointRet<()>>, EndpointError> {
+    fn into_result(self) -> Result<(), TwitterError> {
+        match self? {
+            Ok(()) => Ok(()),
+            Err(ret) => ret.into_result(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use reqwest::header::{HeaderMap, HeaderValue};
+    use twitter_api_v2::objects::{
+        PartialError as V2PartialError, ResponseBodyErrJson as V2ResponseBodyErrJson,
+    };
+
+    #[test]
+    fn test_into_result() {
+        //
+        let ret = EndpointRet::Ok(1).into_result();
+        assert_eq!(ret.unwrap(), 1);
+
+        //
+        let ret = EndpointRet::<()>::from_err_response::<ResponseBodyErrJson>(
+            StatusCode::FORBIDDEN,
+            &HeaderMap::new(),
+            br#"{"errors":[{"code":187,"message":"Status is a duplicate."}]}"#,
+        );
+        let err = ret.into_result().unwrap_err();
+        assert_eq!(err.status(), Some(StatusCode::FORBIDDEN));
+        assert_eq!(err.code(), Some(ErrorCode::DuplicateStatus));
+        assert!(!err.is_retryable());
+        assert_eq!(
+            err.to_string(),
+            "api error, status: 403 Forbidden, code: 187, message: Status is a duplicate."
+        );
+
+        //
+        let ret = EndpointRet::<()>::from_err_response::<ResponseBodyErrJson>(
+            StatusCode::SERVICE_UNAVAILABLE,
+            &HeaderMap::new(),
+            b"Over capacity",
+        );
+        let err = ret.into_result().unwrap_err();
+        assert_eq!(err.code(), None);
+        assert!(err.is_retryable());
+
+        //
+        let mut headers = HeaderMap::new();
+        headers.insert("x-rate-limit-limit", HeaderValue::from_static("200"));
+        headers.insert("x-rate-limit-remaining", HeaderValue::from_static("0"));
+        headers.insert("x-rate-limit-reset", HeaderValue::from_static("1666000000"));
+        let ret = EndpointRet::<()>::from_err_response::<ResponseBodyErrJson>(
+            StatusCode::TOO_MANY_REQUESTS,
+            &headers,
+            br#"{"errors":[{"code":88,"message":"Rate limit exceeded"}]}"#,
+        );
+        let err = ret.into_result().unwrap_err();
+        assert!(err.is_retryable());
+        assert_eq!(
+            err.rate_limit(),
+            Some(&RateLimit {
+                limit: Some(200),
+                remaining: Some(0),
+                reset: 1666000000
+            })
+        );
+
+        //
+        let ret = EndpointRet::<()>::from_err_response::<V2ResponseBodyErrJson>(
+            StatusCode::FORBIDDEN,
+            &HeaderMap::new(),
+            br#"{"title":"Client Forbidden","detail":"Not enrolled.","reason":"client-not-enrolled","type":"https://api.twitter.com/2/problems/client-forbidden"}"#,
+        );
+        let err = ret.into_result().unwrap_err();
+        assert_eq!(err.code(), None);
+        assert_eq!(err.problem_type(), Some(&ProblemType::ClientForbidden));
+        assert_eq!(
+            err.to_string(),
+            "api error, status: 403 Forbidden, type: https://api.twitter.com/2/problems/client-forbidden, message: Not enrolled."
+        );
+        match &err {
+            TwitterError::Api { body: Ok(body), .. } => {
+                assert!(body.problem.as_ref().unwrap().is_client_not_enrolled())
+            }
+            x => panic!("{x:?}"),
+        }
+
+        //
+        let partial_errors = serde_json::from_str::<Vec<V2PartialError>>(
+            r#"[{"value":"1","detail":"Could not find tweet with ids: [1].","title":"Not Found Error","resource_type":"tweet","parameter":"ids","resource_id":"1","type":"https://api.twitter.com/2/problems/resource-not-found"}]"#,
+        )
+        .unwrap();
+        let err = EndpointRet::<()>::PartialErrors(partial_errors.into())
+            .into_result()
+            .unwrap_err();
+        assert_eq!(err.status(), None);
+        assert_eq!(err.code(), Some(ErrorCode::NoStatusFound));
+        assert_eq!(err.problem_type(), Some(&ProblemType::ResourceNotFound));
+        assert!(!err.is_retryable());
+        assert_eq!(
+            err.to_string(),
+            "partial errors, code: 144, type: https://api.twitter.com/2/problems/resource-not-found, message: Could not find tweet with ids: [1]."
+        );
+        match &err {
+            TwitterError::PartialErrors { body, .. } => {
+                assert_eq!(body.partial_errors[0].resource_id.as_deref(), Some("1"))
+            }
+            x => panic!("{x:?}"),
+        }
+
+        //
+        let ret: Result<Result<(), EndpointRet<()>>, EndpointError> = Err(
+            EndpointError::ValidateFailed("media_category invalid".into()),
+        );
+        match ret.into_result() {
+            Err(TwitterError::Validation(msg)) => assert_eq!(msg, "media_category invalid"),
+            x => panic!("{x:?}"),
+        }
+
+        //
+        assert!(!TwitterError::Timeout { media_id: Some(1) }.is_retryable());
+    }
+}
@@ -3,9 +3,14 @@ pub mod endpoints;
 
 pub mod objects;
 
+pub mod media_util;
+
 //
+pub mod error;
+pub use error::{IntoResult, TwitterError};
+
 pub mod secrets;
-pub use secrets::TokenSecrets;
+pub use secrets::{TokenSecrets, UserAccessToken};
 
 #[cfg(feature = "with_tokio_fs")]
 pub mod tokio_fs_util;
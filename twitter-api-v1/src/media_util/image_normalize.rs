@@ -0,0 +1,406 @@
+use std::io::Cursor;
+
+use image::{
+    codecs::jpeg::JpegEncoder, imageops::FilterType, metadata::Orientation, DynamicImage,
+    ImageDecoder as _, ImageFormat, ImageReader,
+};
+
+use super::{
+    limits::IMAGE_SIZE_MAX,
+    sniff::{sniff, MediaKind},
+};
+
+//
+#[derive(Debug, Clone)]
+pub struct NormalizeOptions {
+    pub size_max: u64,
+    // Longest side, larger images are downsized.
+    pub dimension_max: u32,
+    // Starting quality, lowered step by step until size_max is met.
+    pub jpeg_quality: u8,
+}
+
+impl Default for NormalizeOptions {
+    fn default() -> Self {
+        Self {
+            size_max: IMAGE_SIZE_MAX,
+            dimension_max: 4096,
+            jpeg_quality: 85,
+        }
+    }
+}
+
+impl NormalizeOptions {
+    pub fn size_max(mut self, size_max: u64) -> Self {
+        self.size_max = size_max;
+        self
+    }
+
+    pub fn dimension_max(mut self, dimension_max: u32) -> Self {
+        self.dimension_max = dimension_max;
+        self
+    }
+
+    pub fn jpeg_quality(mut self, jpeg_quality: u8) -> Self {
+        self.jpeg_quality = jpeg_quality.clamp(1, 100);
+        self
+    }
+}
+
+//
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum ImageTransform {
+    // EXIF (including GPS), XMP and IPTC removed.
+    MetadataStripped,
+    OrientationApplied {
+        exif_orientation: u8,
+    },
+    Resized {
+        from: (u32, u32),
+        to: (u32, u32),
+    },
+    Reencoded {
+        media_type: &'static str,
+        quality: Option<u8>,
+    },
+}
+
+#[derive(Debug, Clone)]
+pub struct NormalizedImage {
+    pub bytes: Vec<u8>,
+    pub media_type: &'static str,
+    // Empty when the original bytes are returned untouched.
+    pub transforms: Vec<ImageTransform>,
+}
+
+//
+// JPEG, PNG and WebP only, GIF is returned untouched.
+// Without orientation or size issues metadata is stripped losslessly, otherwise the image is re-encoded.
+pub fn normalize_image(
+    bytes: &[u8],
+    options: &NormalizeOptions,
+) -> Result<NormalizedImage, String> {
+    let kind = sniff(bytes).ok_or("image type unknown")?;
+    let format = match kind {
+        MediaKind::Jpeg => ImageFormat::Jpeg,
+        MediaKind::Png => ImageFormat::Png,
+        MediaKind::Webp => ImageFormat::WebP,
+        MediaKind::Gif { .. } => {
+            return Ok(NormalizedImage {
+                bytes: bytes.to_owned(),
+                media_type: kind.media_type(),
+                transforms: vec![],
+            })
+        }
+        _ => return Err(format!("{} is not an image", kind.media_type())),
+    };
+
+    let mut decoder = ImageReader::with_format(Cursor::new(bytes), format)
+        .into_decoder()
+        .map_err(|err| format!("decode image failed, {err}"))?;
+    let has_metadata = decoder.exif_metadata().ok().flatten().is_some()
+        || decoder.xmp_metadata().ok().flatten().is_some()
+        || decoder.iptc_metadata().ok().flatten().is_some()
+        // The decoder only reports eXIf, text and tIME chunks are metadata too.
+        || (kind == MediaKind::Png
+            && strip_png_metadata(bytes).is_some_and(|x| x.len() < bytes.len()));
+    let orientation = decoder.orientation().unwrap_or(Orientation::NoTransforms);
+    let (width, height) = decoder.dimensions();
+
+    //
+    let fits = bytes.len() as u64 <= options.size_max
+        && width.max(height) <= options.dimension_max
+        && orientation == Orientation::NoTransforms;
+    if fits {
+        let stripped = match (has_metadata, kind) {
+            (false, _) => Some(bytes.to_owned()),
+            (true, MediaKind::Jpeg) => strip_jpeg_metadata(bytes),
+            (true, MediaKind::Png) => strip_png_metadata(bytes),
+            (true, _) => None,
+        };
+        if let Some(stripped) = stripped {
+            return Ok(NormalizedImage {
+                bytes: stripped,
+                media_type: kind.media_type(),
+                transforms: if has_metadata {
+                    vec![ImageTransform::MetadataStripped]
+                } else {
+                    vec![]
+                },
+            });
+        }
+    }
+
+    //
+    let mut transforms = vec![];
+    if has_metadata {
+        transforms.push(ImageTransform::MetadataStripped);
+    }
+
+    let mut image =
+        DynamicImage::from_decoder(decoder).map_err(|err| format!("decode image failed, {err}"))?;
+    if orientation != Orientation::NoTransforms {
+        image.apply_orientation(orientation);
+        transforms.push(ImageTransform::OrientationApplied {
+            exif_orientation: orientation.to_exif(),
+        });
+    }
+    let from = (image.width(), image.height());
+    if from.0.max(from.1) > options.dimension_max {
+        image = image.resize(
+            options.dimension_max,
+            options.dimension_max,
+            FilterType::Lanczos3,
+        );
+    }
+
+    let has_alpha = image.color().has_alpha();
+    let mut quality = options.jpeg_quality;
+    let (bytes, media_type, quality) = loop {
+        let encoded = if has_alpha {
+            let mut buf = Cursor::new(vec![]);
+            image
+                .write_to(&mut buf, ImageFormat::Png)
+                .map_err(|err| format!("encode image failed, {err}"))?;
+            buf.into_inner()
+        } else {
+            let mut buf = vec![];
+            JpegEncoder::new_with_quality(&mut buf, quality)
+                .encode_image(&image.to_rgb8())
+                .map_err(|err| format!("encode image failed, {err}"))?;
+            buf
+        };
+
+        if encoded.len() as u64 <= options.size_max {
+            break if has_alpha {
+                (encoded, MediaKind::Png.media_type(), None)
+            } else {
+                (encoded, MediaKind::Jpeg.media_type(), Some(quality))
+            };
+        }
+
+        if !has_alpha && quality > 60 {
+            quality = quality.saturating_sub(10).max(60);
+            continue;
+        }
+        let (w, h) = (image.width() * 3 / 4, image.height() * 3 / 4);
+        if w.min(h) < 32 {
+            return Err(format!(
+                "image cannot be reduced to {} bytes",
+                options.size_max
+            ));
+        }
+        image = image.resize_exact(w, h, FilterType::Lanczos3);
+    };
+
+    if (image.width(), image.height()) != from {
+        transforms.push(ImageTransform::Resized {
+            from,
+            to: (image.width(), image.height()),
+        });
+    }
+    transforms.push(ImageTransform::Reencoded {
+        media_type,
+        quality,
+    });
+
+    Ok(NormalizedImage {
+        bytes,
+        media_type,
+        transforms,
+    })
+}
+
+//
+// Drops APP1 (EXIF, XMP) and APP13 (IPTC), everything from SOS on is copied as is.
+fn strip_jpeg_metadata(bytes: &[u8]) -> Option<Vec<u8>> {
+    if !bytes.starts_with(b"\xFF\xD8") {
+        return None;
+    }
+    let mut out = b"\xFF\xD8".to_vec();
+    let mut i = 2;
+    loop {
+        if *bytes.get(i)? != 0xFF {
+            return None;
+        }
+        let marker = *bytes.get(i + 1)?;
+        match marker {
+            // Fill byte
+            0xFF => {
+                i += 1;
+                continue;
+            }
+            // SOS
+            0xDA => {
+                out.extend_from_slice(&bytes[i..]);
+                return Some(out);
+            }
+            0x01 | 0xD0..=0xD7 => {
+                out.extend_from_slice(&bytes[i..i + 2]);
+                i += 2;
+                continue;
+            }
+            _ => {}
+        }
+        let len = u16::from_be_bytes([*bytes.get(i + 2)?, *bytes.get(i + 3)?]) as usize;
+        let segment = bytes.get(i..i + 2 + len)?;
+        if !matches!(marker, 0xE1 | 0xED) {
+            out.extend_from_slice(segment);
+        }
+        i += 2 + len;
+    }
+}
+
+// https://www.w3.org/TR/png/#11Ancillary-chunks
+fn strip_png_metadata(bytes: &[u8]) -> Option<Vec<u8>> {
+    let mut out = bytes.get(..8)?.to_vec();
+    let mut i = 8;
+    while i < bytes.len() {
+        let len = u32::from_be_bytes(bytes.get(i..i + 4)?.try_into().ok()?) as usize;
+        let chunk = bytes.get(i..i + 12 + len)?;
+        if !matches!(
+            &chunk[4..8],
+            b"eXIf" | b"tEXt" | b"zTXt" | b"iTXt" | b"tIME"
+        ) {
+            out.extend_from_slice(chunk);
+        }
+        i += 12 + len;
+    }
+    Some(out)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use image::{GenericImageView as _, Rgb, RgbImage, Rgba, RgbaImage};
+
+    fn jpeg(width: u32, height: u32, exif_orientation: Option<u8>) -> Vec<u8> {
+        let image = RgbImage::from_fn(width, height, |x, y| {
+            Rgb([((x * 7) ^ (y * 13)) as u8, (x * y) as u8, (x + y) as u8])
+        });
+        let mut buf = vec![];
+        JpegEncoder::new_with_quality(&mut buf, 95)
+            .encode_image(&image)
+            .unwrap();
+
+        match exif_orientation {
+            Some(orientation) => {
+                let mut exif =
+                    b"Exif\0\0MM\0\x2a\0\0\0\x08\0\x01\x01\x12\0\x03\0\0\0\x01\0".to_vec();
+                exif.extend_from_slice(&[orientation, 0, 0, 0, 0, 0, 0]);
+                let mut app1 = b"\xFF\xE1".to_vec();
+                app1.extend_from_slice(&((exif.len() + 2) as u16).to_be_bytes());
+                app1.extend_from_slice(&exif);
+                [&buf[..2], &app1, &buf[2..]].concat()
+            }
+            None => buf,
+        }
+    }
+
+    #[test]
+    fn test_normalize_image() {
+        // Orientation 6, rotated 90 degrees clockwise.
+        let ret = normalize_image(&jpeg(64, 32, Some(6)), &NormalizeOptions::default()).unwrap();
+        assert_eq!(ret.media_type, "image/jpeg");
+        assert_eq!(
+            ret.transforms,
+            vec![
+                ImageTransform::MetadataStripped,
+                ImageTransform::OrientationApplied {
+                    exif_orientation: 6
+                },
+                ImageTransform::Reencoded {
+                    media_type: "image/jpeg",
+                    quality: Some(85)
+                }
+            ]
+        );
+        let image = image::load_from_memory(&ret.bytes).unwrap();
+        assert_eq!(image.dimensions(), (32, 64));
+        assert!(strip_jpeg_metadata(&ret.bytes).unwrap() == ret.bytes);
+
+        // Lossless.
+        let original = jpeg(64, 32, Some(1));
+        let ret = normalize_image(&original, &NormalizeOptions::default()).unwrap();
+        assert_eq!(ret.transforms, vec![ImageTransform::MetadataStripped]);
+        assert_eq!(ret.bytes, jpeg(64, 32, None));
+
+        let ret = normalize_image(&ret.bytes, &NormalizeOptions::default()).unwrap();
+        assert!(ret.transforms.is_empty());
+
+        // Too large.
+        let ret = normalize_image(
+            &jpeg(64, 32, None),
+            &NormalizeOptions::default().dimension_max(16),
+        )
+        .unwrap();
+        assert_eq!(
+            ret.transforms[0],
+            ImageTransform::Resized {
+                from: (64, 32),
+                to: (16, 8)
+            }
+        );
+
+        let original = jpeg(512, 512, None);
+        let size_max = original.len() as u64 / 4;
+        let ret =
+            normalize_image(&original, &NormalizeOptions::default().size_max(size_max)).unwrap();
+        assert!(ret.bytes.len() as u64 <= size_max);
+        assert!(matches!(
+            ret.transforms.last(),
+            Some(ImageTransform::Reencoded {
+                media_type: "image/jpeg",
+                ..
+            })
+        ));
+        assert!(normalize_image(&original, &NormalizeOptions::default().size_max(10)).is_err());
+
+        // Alpha stays PNG.
+        let png = {
+            let mut buf = Cursor::new(vec![]);
+            DynamicImage::ImageRgba8(RgbaImage::from_pixel(64, 64, Rgba([1, 2, 3, 4])))
+                .write_to(&mut buf, ImageFormat::Png)
+                .unwrap();
+            buf.into_inner()
+        };
+        let ret = normalize_image(&png, &NormalizeOptions::default().dimension_max(32)).unwrap();
+        assert_eq!(ret.media_type, "image/png");
+        assert_eq!(
+            image::load_from_memory(&ret.bytes).unwrap().dimensions(),
+            (32, 32)
+        );
+
+        //
+        let gif = b"GIF89a\x01\x00\x01\x00\x80\x00\x00";
+        assert!(normalize_image(gif, &NormalizeOptions::default())
+            .unwrap()
+            .transforms
+            .is_empty());
+        assert!(normalize_image(
+            b"1\n00:00:01,000 --> 00:00:02,000\nx\n",
+            &NormalizeOptions::default()
+        )
+        .is_err());
+    }
+
+    #[test]
+    fn test_strip_png_metadata() {
+        let mut png = b"\x89PNG\r\n\x1A\n".to_vec();
+        for (chunk_type, data) in [
+            (&b"IHDR"[..], &[0_u8; 13][..]),
+            (b"tEXt", b"GPS\0here"),
+            (b"IEND", b""),
+        ] {
+            png.extend_from_slice(&(data.len() as u32).to_be_bytes());
+            png.extend_from_slice(chunk_type);
+            png.extend_from_slice(data);
+            png.extend_from_slice(&[0; 4]);
+        }
+        let stripped = strip_png_metadata(&png).unwrap();
+        assert_eq!(stripped.len(), png.len() - 20);
+        assert!(!stripped.windows(4).any(|x| x == b"tEXt"));
+    }
+}
@@ -0,0 +1,88 @@
+// https://developer.twitter.com/en/docs/twitter-api/v1/media/upload-media/uploading-media/media-best-practices
+
+use crate::{
+    endpoints::media::upload_media::{SEGMENT_INDEX_MAX, SEGMENT_SIZE},
+    objects::media::MediaCategory,
+};
+
+//
+pub const IMAGE_SIZE_MAX: u64 = 1024 * 1024 * 5;
+pub const GIF_SIZE_MAX: u64 = 1024 * 1024 * 15;
+pub const VIDEO_SIZE_MAX: u64 = 1024 * 1024 * 512;
+pub const CHUNKED_SIZE_MAX: u64 = (SEGMENT_SIZE * (SEGMENT_INDEX_MAX + 1)) as u64;
+
+// Applies to TweetVideo and DmVideo.
+pub const VIDEO_DURATION_MIN_MILLIS: u64 = 500;
+pub const VIDEO_DURATION_MAX_MILLIS: u64 = 140 * 1000;
+pub const VIDEO_WIDTH_RANGE: core::ops::RangeInclusive<u32> = 32..=1280;
+pub const VIDEO_HEIGHT_RANGE: core::ops::RangeInclusive<u32> = 32..=1024;
+pub const VIDEO_ASPECT_RATIO_MAX: f64 = 3.0;
+pub const VIDEO_FRAME_RATE_MAX: f64 = 60.0;
+
+pub fn size_max(media_category: &MediaCategory) -> u64 {
+    match media_category {
+        MediaCategory::TweetImage | MediaCategory::DmImage => IMAGE_SIZE_MAX,
+        MediaCategory::TweetGif | MediaCategory::DmGif => GIF_SIZE_MAX,
+        MediaCategory::TweetVideo | MediaCategory::DmVideo => VIDEO_SIZE_MAX,
+        MediaCategory::Subtitles | MediaCategory::Other(_) => CHUNKED_SIZE_MAX,
+    }
+}
+
+//
+pub fn validate_size(media_category: &MediaCategory, size: u64) -> Result<(), String> {
+    if size == 0 {
+        return Err("media is empty".into());
+    }
+    let max = size_max(media_category);
+    if size > max {
+        return Err(format!(
+            "media too large for {media_category}, size: {size}, max: {max}"
+        ));
+    }
+    Ok(())
+}
+
+pub fn validate_media_type(media_category: &MediaCategory, media_type: &str) -> Result<(), String> {
+    let ok = match media_category {
+        MediaCategory::TweetImage | MediaCategory::DmImage => media_type.starts_with("image/"),
+        MediaCategory::TweetGif | MediaCategory::DmGif => media_type == "image/gif",
+        MediaCategory::TweetVideo | MediaCategory::DmVideo => media_type.starts_with("video/"),
+        MediaCategory::Subtitles => {
+            media_type == "application/x-subrip" || media_type.starts_with("text/")
+        }
+        MediaCategory::Other(_) => true,
+    };
+    if ok {
+        Ok(())
+    } else {
+        Err(format!(
+            "media_type {media_type} does not match {media_category}"
+        ))
+    }
+}
+
+pub fn validate(media_category: &MediaCategory, media_type: &str, size: u64) -> Result<(), String> {
+    validate_media_type(media_category, media_type)?;
+    validate_size(media_category, size)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_validate() {
+        assert!(validate(&MediaCategory::TweetImage, "image/png", IMAGE_SIZE_MAX).is_ok());
+        assert_eq!(
+            validate(&MediaCategory::TweetImage, "image/png", IMAGE_SIZE_MAX + 1).unwrap_err(),
+            "media too large for tweet_image, size: 5242881, max: 5242880"
+        );
+        assert!(validate(&MediaCategory::TweetGif, "image/gif", GIF_SIZE_MAX).is_ok());
+        assert!(validate(&MediaCategory::TweetVideo, "video/mp4", VIDEO_SIZE_MAX + 1).is_err());
+        assert!(validate(&MediaCategory::TweetVideo, "image/gif", 1).is_err());
+        assert!(validate(&MediaCategory::DmGif, "image/png", 1).is_err());
+        assert!(validate(&MediaCategory::Subtitles, "application/x-subrip", 1).is_ok());
+        assert!(validate_size(&MediaCategory::Subtitles, 0).is_err());
+        assert!(validate_size(&MediaCategory::Other("x".into()), CHUNKED_SIZE_MAX + 1).is_err());
+    }
+}
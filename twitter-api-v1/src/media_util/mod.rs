@@ -0,0 +1,19 @@
+//
+#[cfg(feature = "with_image")]
+pub mod image_normalize;
+pub mod limits;
+pub mod mp4;
+pub mod sniff;
+pub mod srt;
+
+#[cfg(feature = "with_image")]
+pub use image_normalize::{normalize_image, ImageTransform, NormalizeOptions, NormalizedImage};
+pub use limits::{validate, validate_media_type, validate_size};
+pub use mp4::{inspect_mp4, Mp4Info};
+pub use sniff::{sniff, MediaKind, MediaTarget};
+pub use srt::validate_srt;
+
+#[cfg(feature = "with_tokio_fs")]
+pub use mp4::inspect_mp4_file;
+#[cfg(feature = "with_tokio_fs")]
+pub use sniff::sniff_file;
@@ -0,0 +1,397 @@
+// ISO/IEC 14496-12, only the boxes needed for the video best practices are read.
+// https://developer.twitter.com/en/docs/twitter-api/v1/media/upload-media/uploading-media/media-best-practices
+
+use super::limits::{
+    VIDEO_ASPECT_RATIO_MAX, VIDEO_DURATION_MAX_MILLIS, VIDEO_DURATION_MIN_MILLIS,
+    VIDEO_FRAME_RATE_MAX, VIDEO_HEIGHT_RANGE, VIDEO_WIDTH_RANGE,
+};
+use crate::objects::media::MediaCategory;
+
+// The moov box is read into memory, bigger ones are rejected.
+pub const MOOV_SIZE_MAX: u64 = 1024 * 1024 * 64;
+
+//
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Mp4Info {
+    pub duration_millis: u64,
+    pub width: Option<u32>,
+    pub height: Option<u32>,
+    pub frame_rate: Option<f64>,
+    // Sample entry fourcc, e.g. avc1.
+    pub video_codec: Option<String>,
+    // Sample entry fourcc, e.g. mp4a.
+    pub audio_codec: Option<String>,
+}
+
+impl Mp4Info {
+    // Empty for categories other than TweetVideo and DmVideo.
+    pub fn violations(&self, media_category: &MediaCategory) -> Vec<String> {
+        if !matches!(
+            media_category,
+            MediaCategory::TweetVideo | MediaCategory::DmVideo
+        ) {
+            return vec![];
+        }
+
+        let mut violations = vec![];
+        if self.duration_millis < VIDEO_DURATION_MIN_MILLIS
+            || self.duration_millis > VIDEO_DURATION_MAX_MILLIS
+        {
+            violations.push(format!(
+                "duration {:.3}s out of range {:.1}s..={}s",
+                self.duration_millis as f64 / 1000.0,
+                VIDEO_DURATION_MIN_MILLIS as f64 / 1000.0,
+                VIDEO_DURATION_MAX_MILLIS / 1000,
+            ));
+        }
+        match self.video_codec.as_deref() {
+            None => violations.push("video track missing".into()),
+            Some("avc1" | "avc3") => {}
+            Some(codec) => violations.push(format!("video codec {codec} is not H.264")),
+        }
+        if let (Some(width), Some(height)) = (self.width, self.height) {
+            if !VIDEO_WIDTH_RANGE.contains(&width) || !VIDEO_HEIGHT_RANGE.contains(&height) {
+                violations.push(format!(
+                    "dimensions {width}x{height} out of range {}x{}..={}x{}",
+                    VIDEO_WIDTH_RANGE.start(),
+                    VIDEO_HEIGHT_RANGE.start(),
+                    VIDEO_WIDTH_RANGE.end(),
+                    VIDEO_HEIGHT_RANGE.end(),
+                ));
+            }
+            if width > 0 && height > 0 {
+                let ratio = width.max(height) as f64 / width.min(height) as f64;
+                if ratio > VIDEO_ASPECT_RATIO_MAX {
+                    violations.push(format!("aspect ratio {width}:{height} beyond 1:3 or 3:1"));
+                }
+            }
+        }
+        if let Some(frame_rate) = self.frame_rate {
+            if frame_rate > VIDEO_FRAME_RATE_MAX {
+                violations.push(format!(
+                    "frame rate {frame_rate:.2} over {VIDEO_FRAME_RATE_MAX}"
+                ));
+            }
+        }
+        match self.audio_codec.as_deref() {
+            None | Some("mp4a") => {}
+            Some(codec) => violations.push(format!("audio codec {codec} is not AAC")),
+        }
+        violations
+    }
+
+    pub fn validate(&self, media_category: &MediaCategory) -> Result<(), String> {
+        let violations = self.violations(media_category);
+        if violations.is_empty() {
+            Ok(())
+        } else {
+            Err(format!("video invalid: {}", violations.join("; ")))
+        }
+    }
+}
+
+//
+pub fn inspect_mp4(bytes: &[u8]) -> Result<Mp4Info, String> {
+    for x in boxes(bytes) {
+        let (box_type, payload) = x?;
+        if &box_type == b"moov" {
+            return parse_moov(payload);
+        }
+    }
+    Err("moov box not found".into())
+}
+
+// Seeks over the top-level boxes, so only moov is read even when it is at the end of the file.
+#[cfg(feature = "with_tokio_fs")]
+pub async fn inspect_mp4_file(path: &std::path::PathBuf) -> Result<Mp4Info, std::io::Error> {
+    use std::io::{Error as IoError, ErrorKind, SeekFrom};
+    use tokio::{
+        fs::File,
+        io::{AsyncReadExt as _, AsyncSeekExt as _},
+    };
+
+    let invalid = |msg: String| IoError::new(ErrorKind::InvalidData, msg);
+
+    let mut file = File::open(path).await?;
+    let file_size = file.metadata().await?.len();
+    let mut pos = 0_u64;
+    while pos.checked_add(8).is_some_and(|x| x <= file_size) {
+        file.seek(SeekFrom::Start(pos)).await?;
+        let mut header = [0; 8];
+        file.read_exact(&mut header).await?;
+        let mut header_size = 8_u64;
+        let mut size = u32::from_be_bytes([header[0], header[1], header[2], header[3]]) as u64;
+        if size == 1 {
+            size = file.read_u64().await?;
+            header_size = 16;
+        } else if size == 0 {
+            size = file_size - pos;
+        }
+        let end = match pos.checked_add(size) {
+            Some(end) if size >= header_size && end <= file_size => end,
+            _ => return Err(invalid("mp4 box truncated".into())),
+        };
+
+        if &header[4..8] == b"moov" {
+            if size > MOOV_SIZE_MAX {
+                return Err(invalid("moov box too large".into()));
+            }
+            let mut payload = vec![0; (size - header_size) as usize];
+            file.read_exact(&mut payload).await?;
+            return parse_moov(&payload).map_err(invalid);
+        }
+        pos = end;
+    }
+    Err(invalid("moov box not found".into()))
+}
+
+//
+fn boxes(mut data: &[u8]) -> impl Iterator<Item = Result<([u8; 4], &[u8]), String>> {
+    core::iter::from_fn(move || {
+        if data.len() < 8 {
+            return None;
+        }
+        let box_type = [data[4], data[5], data[6], data[7]];
+        let (header_size, size) = match be_u32(data, 0)? as u64 {
+            0 => (8, data.len() as u64),
+            1 => (16, be_u64(data, 8).unwrap_or_default()),
+            x => (8, x),
+        };
+        if size < header_size || size > data.len() as u64 {
+            data = &[];
+            return Some(Err("mp4 box truncated".into()));
+        }
+        let payload = &data[header_size as usize..size as usize];
+        data = &data[size as usize..];
+        Some(Ok((box_type, payload)))
+    })
+}
+
+fn find<'a>(data: &'a [u8], box_type: &[u8; 4]) -> Result<Option<&'a [u8]>, String> {
+    for x in boxes(data) {
+        let (t, payload) = x?;
+        if &t == box_type {
+            return Ok(Some(payload));
+        }
+    }
+    Ok(None)
+}
+
+fn find_path<'a>(data: &'a [u8], path: &[&[u8; 4]]) -> Result<Option<&'a [u8]>, String> {
+    let mut data = data;
+    for box_type in path {
+        data = match find(data, box_type)? {
+            Some(x) => x,
+            None => return Ok(None),
+        };
+    }
+    Ok(Some(data))
+}
+
+fn be_u32(data: &[u8], offset: usize) -> Option<u32> {
+    data.get(offset..offset + 4)
+        .map(|x| u32::from_be_bytes([x[0], x[1], x[2], x[3]]))
+}
+
+fn be_u64(data: &[u8], offset: usize) -> Option<u64> {
+    Some(((be_u32(data, offset)? as u64) << 32) | be_u32(data, offset + 4)? as u64)
+}
+
+// (timescale, duration) of mvhd and mdhd, both share the same layout up to duration.
+fn parse_header_duration(data: &[u8]) -> Option<(u32, u64)> {
+    match data.first()? {
+        0 => Some((be_u32(data, 12)?, be_u32(data, 16)? as u64)),
+        _ => Some((be_u32(data, 20)?, be_u64(data, 24)?)),
+    }
+}
+
+fn parse_moov(moov: &[u8]) -> Result<Mp4Info, String> {
+    let (timescale, duration) = find(moov, b"mvhd")?
+        .and_then(parse_header_duration)
+        .ok_or("mvhd box invalid")?;
+    let mut info = Mp4Info {
+        duration_millis: duration
+            .checked_mul(1000)
+            .ok_or("mvhd duration too large")?
+            / timescale.max(1) as u64,
+        ..Default::default()
+    };
+
+    for x in boxes(moov) {
+        let (box_type, trak) = x?;
+        if &box_type != b"trak" {
+            continue;
+        }
+        let handler_type = find_path(trak, &[b"mdia", b"hdlr"])?.and_then(|x| x.get(8..12));
+        let codec = find_path(trak, &[b"mdia", b"minf", b"stbl", b"stsd"])?
+            .and_then(|x| x.get(12..16))
+            .map(|x| String::from_utf8_lossy(x).into_owned());
+
+        match handler_type {
+            Some(b"vide") if info.video_codec.is_none() => {
+                info.video_codec = codec;
+
+                // Fixed-point 16.16, at the end of tkhd.
+                if let Some(tkhd) = find(trak, b"tkhd")? {
+                    let offset = if tkhd.first() == Some(&0) { 76 } else { 88 };
+                    info.width = be_u32(tkhd, offset).map(|x| x >> 16);
+                    info.height = be_u32(tkhd, offset + 4).map(|x| x >> 16);
+                }
+
+                let mdhd = find_path(trak, &[b"mdia", b"mdhd"])?.and_then(parse_header_duration);
+                let sample_count =
+                    find_path(trak, &[b"mdia", b"minf", b"stbl", b"stts"])?.map(|stts| {
+                        let entry_count = be_u32(stts, 4).unwrap_or_default() as usize;
+                        (0..entry_count)
+                            .map_while(|i| be_u32(stts, 8 + i * 8))
+                            .map(|x| x as u64)
+                            .sum::<u64>()
+                    });
+                if let (Some((timescale, duration)), Some(sample_count)) = (mdhd, sample_count) {
+                    if duration > 0 && sample_count > 0 {
+                        info.frame_rate =
+                            Some(sample_count as f64 * timescale as f64 / duration as f64);
+                    }
+                }
+            }
+            Some(b"soun") if info.audio_codec.is_none() => {
+                info.audio_codec = codec;
+            }
+            _ => {}
+        }
+    }
+
+    Ok(info)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn mp4_box(box_type: &[u8; 4], payload: &[u8]) -> Vec<u8> {
+        let mut bytes = ((payload.len() + 8) as u32).to_be_bytes().to_vec();
+        bytes.extend_from_slice(box_type);
+        bytes.extend_from_slice(payload);
+        bytes
+    }
+
+    fn trak(handler_type: &[u8; 4], codec: &[u8; 4], width: u32, height: u32, fps: u32) -> Vec<u8> {
+        let mut tkhd = vec![0; 84];
+        tkhd[76..80].copy_from_slice(&(width << 16).to_be_bytes());
+        tkhd[80..84].copy_from_slice(&(height << 16).to_be_bytes());
+
+        // timescale 1000, 10s
+        let mut mdhd = vec![0; 24];
+        mdhd[12..16].copy_from_slice(&1000_u32.to_be_bytes());
+        mdhd[16..20].copy_from_slice(&10_000_u32.to_be_bytes());
+
+        let mut hdlr = vec![0; 24];
+        hdlr[8..12].copy_from_slice(handler_type);
+
+        let mut stsd = vec![0, 0, 0, 0, 0, 0, 0, 1];
+        stsd.extend_from_slice(&mp4_box(codec, &[0; 8]));
+
+        let mut stts = vec![0, 0, 0, 0, 0, 0, 0, 1];
+        stts.extend_from_slice(&(fps * 10).to_be_bytes());
+        stts.extend_from_slice(&(1000 / fps.max(1)).to_be_bytes());
+
+        let stbl = [mp4_box(b"stsd", &stsd), mp4_box(b"stts", &stts)].concat();
+        let minf = mp4_box(b"stbl", &stbl);
+        let mdia = [
+            mp4_box(b"mdhd", &mdhd),
+            mp4_box(b"hdlr", &hdlr),
+            mp4_box(b"minf", &minf),
+        ]
+        .concat();
+        mp4_box(
+            b"trak",
+            &[mp4_box(b"tkhd", &tkhd), mp4_box(b"mdia", &mdia)].concat(),
+        )
+    }
+
+    fn mp4(duration_secs: u32, width: u32, height: u32, fps: u32) -> Vec<u8> {
+        let mut mvhd = vec![0; 100];
+        mvhd[12..16].copy_from_slice(&600_u32.to_be_bytes());
+        mvhd[16..20].copy_from_slice(&(duration_secs * 600).to_be_bytes());
+
+        let moov = [
+            mp4_box(b"mvhd", &mvhd),
+            trak(b"vide", b"avc1", width, height, fps),
+            trak(b"soun", b"mp4a", 0, 0, 0),
+        ]
+        .concat();
+        [
+            mp4_box(b"ftyp", b"isom\0\0\0\0isomavc1"),
+            mp4_box(b"mdat", &[0; 16]),
+            mp4_box(b"moov", &moov),
+        ]
+        .concat()
+    }
+
+    #[test]
+    fn test_inspect_mp4() {
+        let info = inspect_mp4(&mp4(10, 1280, 720, 30)).unwrap();
+        assert_eq!(
+            info,
+            Mp4Info {
+                duration_millis: 10_000,
+                width: Some(1280),
+                height: Some(720),
+                frame_rate: Some(30.0),
+                video_codec: Some("avc1".into()),
+                audio_codec: Some("mp4a".into()),
+            }
+        );
+        assert!(info.validate(&MediaCategory::TweetVideo).is_ok());
+
+        let info = inspect_mp4(&mp4(141, 1920, 200, 120)).unwrap();
+        assert_eq!(
+            info.validate(&MediaCategory::DmVideo).unwrap_err(),
+            "video invalid: duration 141.000s out of range 0.5s..=140s; dimensions 1920x200 out of range 32x32..=1280x1024; aspect ratio 1920:200 beyond 1:3 or 3:1; frame rate 120.00 over 60"
+        );
+        assert!(info
+            .validate(&MediaCategory::Other("amplify_video".into()))
+            .is_ok());
+
+        let mut info = Mp4Info {
+            video_codec: Some("hvc1".into()),
+            audio_codec: Some("Opus".into()),
+            duration_millis: 1000,
+            ..Default::default()
+        };
+        assert_eq!(
+            info.violations(&MediaCategory::TweetVideo),
+            vec![
+                "video codec hvc1 is not H.264",
+                "audio codec Opus is not AAC"
+            ]
+        );
+        info.video_codec = None;
+        assert_eq!(
+            info.violations(&MediaCategory::TweetVideo)[0],
+            "video track missing"
+        );
+
+        assert_eq!(
+            inspect_mp4(b"\0\0\0\x20ftypisom").unwrap_err(),
+            "mp4 box truncated"
+        );
+        assert_eq!(
+            inspect_mp4(&mp4_box(b"ftyp", b"isom")).unwrap_err(),
+            "moov box not found"
+        );
+    }
+
+    #[test]
+    fn test_inspect_mp4_duration_overflow() {
+        // mvhd version 1, timescale 1 and a 64-bit duration that overflows in millis.
+        let mut mvhd = vec![0; 112];
+        mvhd[0] = 1;
+        mvhd[20..24].copy_from_slice(&1_u32.to_be_bytes());
+        mvhd[24..32].copy_from_slice(&(u64::MAX / 10).to_be_bytes());
+        assert_eq!(
+            inspect_mp4(&mp4_box(b"moov", &mp4_box(b"mvhd", &mvhd))).unwrap_err(),
+            "mvhd duration too large"
+        );
+    }
+}
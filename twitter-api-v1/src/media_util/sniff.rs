@@ -0,0 +1,271 @@
+use crate::objects::media::MediaCategory;
+
+// Enough for the magic bytes, the GIF frame walk falls back to the NETSCAPE2.0 loop extension when truncated.
+pub const SNIFF_LEN: usize = 64 * 1024;
+
+//
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MediaKind {
+    Jpeg,
+    Png,
+    Gif { animated: bool },
+    Webp,
+    Mp4,
+    Mov,
+    Srt,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum MediaTarget {
+    #[default]
+    Tweet,
+    Dm,
+}
+
+impl MediaKind {
+    pub fn media_type(&self) -> &'static str {
+        match self {
+            Self::Jpeg => "image/jpeg",
+            Self::Png => "image/png",
+            Self::Gif { .. } => "image/gif",
+            Self::Webp => "image/webp",
+            Self::Mp4 => "video/mp4",
+            Self::Mov => "video/quicktime",
+            Self::Srt => "application/x-subrip",
+        }
+    }
+
+    pub fn media_category(&self, target: MediaTarget) -> MediaCategory {
+        match (self, target) {
+            (
+                Self::Jpeg | Self::Png | Self::Webp | Self::Gif { animated: false },
+                MediaTarget::Tweet,
+            ) => MediaCategory::TweetImage,
+            (
+                Self::Jpeg | Self::Png | Self::Webp | Self::Gif { animated: false },
+                MediaTarget::Dm,
+            ) => MediaCategory::DmImage,
+            (Self::Gif { animated: true }, MediaTarget::Tweet) => MediaCategory::TweetGif,
+            (Self::Gif { animated: true }, MediaTarget::Dm) => MediaCategory::DmGif,
+            (Self::Mp4 | Self::Mov, MediaTarget::Tweet) => MediaCategory::TweetVideo,
+            (Self::Mp4 | Self::Mov, MediaTarget::Dm) => MediaCategory::DmVideo,
+            (Self::Srt, _) => MediaCategory::Subtitles,
+        }
+    }
+}
+
+//
+pub fn sniff(bytes: &[u8]) -> Option<MediaKind> {
+    if bytes.starts_with(b"\xFF\xD8\xFF") {
+        return Some(MediaKind::Jpeg);
+    }
+    if bytes.starts_with(b"\x89PNG\r\n\x1A\n") {
+        return Some(MediaKind::Png);
+    }
+    if bytes.starts_with(b"GIF87a") || bytes.starts_with(b"GIF89a") {
+        return Some(MediaKind::Gif {
+            animated: is_animated_gif(bytes),
+        });
+    }
+    if bytes.len() >= 12 && &bytes[..4] == b"RIFF" && &bytes[8..12] == b"WEBP" {
+        return Some(MediaKind::Webp);
+    }
+    if bytes.len() >= 12 && &bytes[4..8] == b"ftyp" {
+        return Some(match &bytes[8..12] {
+            b"qt  " => MediaKind::Mov,
+            _ => MediaKind::Mp4,
+        });
+    }
+    if bytes.len() >= 8 && matches!(&bytes[4..8], b"moov" | b"mdat" | b"wide" | b"free") {
+        return Some(MediaKind::Mov);
+    }
+    if is_srt(bytes) {
+        return Some(MediaKind::Srt);
+    }
+    None
+}
+
+#[cfg(feature = "with_tokio_fs")]
+pub async fn sniff_file(path: &std::path::PathBuf) -> Result<Option<MediaKind>, std::io::Error> {
+    use tokio::{fs::File, io::AsyncReadExt as _};
+
+    let mut buf = Vec::with_capacity(SNIFF_LEN);
+    File::open(path)
+        .await?
+        .take(SNIFF_LEN as u64)
+        .read_to_end(&mut buf)
+        .await?;
+
+    Ok(sniff(&buf))
+}
+
+//
+// https://www.w3.org/Graphics/GIF/spec-gif89a.txt
+fn is_animated_gif(bytes: &[u8]) -> bool {
+    fn skip_sub_blocks(bytes: &[u8], mut i: usize) -> Option<usize> {
+        loop {
+            let n = *bytes.get(i)? as usize;
+            i += 1;
+            if n == 0 {
+                return Some(i);
+            }
+            i += n;
+        }
+    }
+
+    let walk = || -> Option<bool> {
+        let flags = *bytes.get(10)?;
+        let mut i = 13;
+        if flags & 0x80 != 0 {
+            i += 3 * (1 << ((flags & 0x07) + 1));
+        }
+
+        let mut frames = 0;
+        loop {
+            match *bytes.get(i)? {
+                // Extension
+                0x21 => {
+                    i = skip_sub_blocks(bytes, i + 2)?;
+                }
+                // Image Descriptor
+                0x2C => {
+                    frames += 1;
+                    if frames > 1 {
+                        return Some(true);
+                    }
+                    let flags = *bytes.get(i + 9)?;
+                    i += 10;
+                    if flags & 0x80 != 0 {
+                        i += 3 * (1 << ((flags & 0x07) + 1));
+                    }
+                    // LZW minimum code size
+                    i = skip_sub_blocks(bytes, i + 1)?;
+                }
+                // Trailer
+                0x3B => return Some(false),
+                _ => return None,
+            }
+        }
+    };
+
+    match walk() {
+        Some(animated) => animated,
+        None => bytes
+            .windows(b"NETSCAPE2.0".len())
+            .any(|x| x == b"NETSCAPE2.0"),
+    }
+}
+
+fn is_srt(bytes: &[u8]) -> bool {
+    let bytes = bytes.strip_prefix(b"\xEF\xBB\xBF").unwrap_or(bytes);
+    let text = match core::str::from_utf8(&bytes[..core::cmp::min(bytes.len(), 1024)]) {
+        Ok(x) => x,
+        // Cut in the middle of a multi-byte char.
+        Err(err) => match core::str::from_utf8(&bytes[..err.valid_up_to()]) {
+            Ok(x) => x,
+            Err(_) => return false,
+        },
+    };
+
+    let mut lines = text.lines().map(|x| x.trim()).skip_while(|x| x.is_empty());
+    let index_ok =
+        matches!(lines.next(), Some(x) if !x.is_empty() && x.chars().all(|c| c.is_ascii_digit()));
+    let timing_ok = matches!(lines.next(), Some(x) if is_srt_timing(x));
+    index_ok && timing_ok
+}
+
+// 00:00:01,000 --> 00:00:04,000
+pub(crate) fn is_srt_timing(line: &str) -> bool {
+    fn is_timestamp(s: &str) -> bool {
+        let b = s.as_bytes();
+        b.len() == 12
+            && b.iter().enumerate().all(|(i, c)| match i {
+                2 | 5 => *c == b':',
+                8 => *c == b',' || *c == b'.',
+                _ => c.is_ascii_digit(),
+            })
+    }
+
+    match line.split_once("-->") {
+        Some((start, end)) => {
+            is_timestamp(start.trim())
+                && is_timestamp(end.split_whitespace().next().unwrap_or_default())
+        }
+        None => false,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn gif(frames: usize, netscape: bool) -> Vec<u8> {
+        let mut bytes = b"GIF89a\x01\x00\x01\x00\x80\x00\x00".to_vec();
+        bytes.extend_from_slice(&[0, 0, 0, 0xFF, 0xFF, 0xFF]);
+        if netscape {
+            bytes.extend_from_slice(b"\x21\xFF\x0BNETSCAPE2.0\x03\x01\x00\x00\x00");
+        }
+        for _ in 0..frames {
+            bytes.extend_from_slice(b"\x21\xF9\x04\x00\x0A\x00\x00\x00");
+            bytes.extend_from_slice(b"\x2C\x00\x00\x00\x00\x01\x00\x01\x00\x00");
+            bytes.extend_from_slice(b"\x02\x02\x44\x01\x00");
+        }
+        bytes.push(0x3B);
+        bytes
+    }
+
+    #[test]
+    fn test_sniff() {
+        assert_eq!(
+            sniff(b"\xFF\xD8\xFF\xE0\x00\x10JFIF"),
+            Some(MediaKind::Jpeg)
+        );
+        assert_eq!(sniff(b"\x89PNG\r\n\x1A\n\x00\x00"), Some(MediaKind::Png));
+        assert_eq!(
+            sniff(b"RIFF\x00\x00\x00\x00WEBPVP8 "),
+            Some(MediaKind::Webp)
+        );
+        assert_eq!(
+            sniff(b"\x00\x00\x00\x20ftypisom\x00\x00\x02\x00"),
+            Some(MediaKind::Mp4)
+        );
+        assert_eq!(
+            sniff(b"\x00\x00\x00\x14ftypqt  \x00\x00\x00\x00"),
+            Some(MediaKind::Mov)
+        );
+        assert_eq!(sniff(b"hello"), None);
+
+        //
+        assert_eq!(
+            sniff(&gif(1, false)),
+            Some(MediaKind::Gif { animated: false })
+        );
+        assert_eq!(
+            sniff(&gif(2, false)),
+            Some(MediaKind::Gif { animated: true })
+        );
+        let truncated = gif(1, true);
+        assert_eq!(
+            sniff(&truncated[..truncated.len() - 8]),
+            Some(MediaKind::Gif { animated: true })
+        );
+
+        //
+        assert_eq!(
+            sniff("\u{feff}1\r\n00:00:01,000 --> 00:00:04,000\r\nHello\r\n".as_bytes()),
+            Some(MediaKind::Srt)
+        );
+        assert_eq!(sniff(b"1\nHello\n"), None);
+
+        //
+        assert_eq!(
+            MediaKind::Gif { animated: true }.media_category(MediaTarget::Dm),
+            MediaCategory::DmGif
+        );
+        assert_eq!(
+            MediaKind::Gif { animated: false }.media_category(MediaTarget::Tweet),
+            MediaCategory::TweetImage
+        );
+        assert_eq!(MediaKind::Mov.media_type(), "video/quicktime");
+    }
+}
@@ -0,0 +1,112 @@
+// https://en.wikipedia.org/wiki/SubRip#Format
+
+use super::sniff::is_srt_timing;
+
+//
+pub fn validate_srt(bytes: &[u8]) -> Result<(), String> {
+    let bytes = bytes.strip_prefix(b"\xEF\xBB\xBF").unwrap_or(bytes);
+    let text = core::str::from_utf8(bytes).map_err(|_| "srt is not utf-8".to_owned())?;
+
+    let mut lines = text.lines().map(|x| x.trim_end()).peekable();
+    let mut count = 0_usize;
+    let mut last_index = 0_u64;
+    loop {
+        while matches!(lines.peek(), Some(x) if x.is_empty()) {
+            lines.next();
+        }
+        let index_line = match lines.next() {
+            Some(x) => x.trim_start(),
+            None => break,
+        };
+        count += 1;
+
+        let index = index_line
+            .parse::<u64>()
+            .map_err(|_| format!("srt cue {count}: invalid index {index_line:?}"))?;
+        if index <= last_index {
+            return Err(format!("srt cue {count}: index {index} out of order"));
+        }
+        last_index = index;
+
+        let timing_line = lines.next().unwrap_or_default();
+        let (start, end) = parse_timing(timing_line)
+            .ok_or_else(|| format!("srt cue {count}: invalid timing {timing_line:?}"))?;
+        if start >= end {
+            return Err(format!("srt cue {count}: end not after start"));
+        }
+
+        let mut text_lines = 0;
+        while matches!(lines.peek(), Some(x) if !x.is_empty()) {
+            lines.next();
+            text_lines += 1;
+        }
+        if text_lines == 0 {
+            return Err(format!("srt cue {count}: text is empty"));
+        }
+    }
+
+    if count == 0 {
+        return Err("srt has no cues".into());
+    }
+    Ok(())
+}
+
+// Start and end in milliseconds.
+fn parse_timing(line: &str) -> Option<(u64, u64)> {
+    fn millis(s: &str) -> u64 {
+        let b = s.as_bytes();
+        let num = |r: core::ops::Range<usize>| {
+            b[r].iter()
+                .fold(0_u64, |acc, c| acc * 10 + u64::from(c - b'0'))
+        };
+        ((num(0..2) * 60 + num(3..5)) * 60 + num(6..8)) * 1000 + num(9..12)
+    }
+
+    if !is_srt_timing(line) {
+        return None;
+    }
+    let (start, end) = line.split_once("-->")?;
+    Some((millis(start.trim()), millis(end.split_whitespace().next()?)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_validate_srt() {
+        let srt = "\u{feff}1\r\n00:00:01,000 --> 00:00:04,000\r\nHello\r\n\r\n2\r\n00:00:05,000 --> 00:00:06,500 X1:0\r\nWorld\r\nAgain\r\n";
+        assert!(validate_srt(srt.as_bytes()).is_ok());
+
+        assert_eq!(validate_srt(b"").unwrap_err(), "srt has no cues");
+        assert_eq!(validate_srt(b"\xff\xfe").unwrap_err(), "srt is not utf-8");
+        assert_eq!(
+            validate_srt(b"a\n00:00:01,000 --> 00:00:04,000\nHello\n").unwrap_err(),
+            r#"srt cue 1: invalid index "a""#
+        );
+        assert_eq!(
+            validate_srt(b"1\n00:00:01,000 -> 00:00:04,000\nHello\n").unwrap_err(),
+            r#"srt cue 1: invalid timing "00:00:01,000 -> 00:00:04,000""#
+        );
+        assert_eq!(
+            validate_srt(b"1\n00:00:04,000 --> 00:00:01,000\nHello\n").unwrap_err(),
+            "srt cue 1: end not after start"
+        );
+        assert_eq!(
+            validate_srt(b"1\n00:00:01,000 --> 00:00:04,000\n\n").unwrap_err(),
+            "srt cue 1: text is empty"
+        );
+        assert_eq!(
+            validate_srt(
+                b"2\n00:00:01,000 --> 00:00:02,000\nA\n\n1\n00:00:03,000 --> 00:00:04,000\nB\n"
+            )
+            .unwrap_err(),
+            "srt cue 2: index 1 out of order"
+        );
+
+        assert_eq!(
+            parse_timing("01:02:03.004 --> 01:02:04,000"),
+            Some((3_723_004, 3_724_000))
+        );
+    }
+}
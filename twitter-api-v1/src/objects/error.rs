@@ -1,10 +1,20 @@
+// https://developer.twitter.com/en/support/twitter-api/error-troubleshooting
+
 use serde::{Deserialize, Serialize};
-use twitter_api_v2::objects::ResponseBodyErrJson as V2ResponseBodyErrJson;
+use twitter_api_v2::objects::{
+    PartialError as V2PartialError, ProblemType, ResponseBodyErrJson as V2ResponseBodyErrJson,
+};
 
 //
 #[derive(Deserialize, Serialize, Debug, Clone)]
 pub struct Error {
     pub errors: Vec<ErrorErrorsItem>,
+    // Only present when converted from a v2 problem
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub problem: Option<Box<V2ResponseBodyErrJson>>,
+    // Only present when converted from v2 partial errors
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub partial_errors: Vec<V2PartialError>,
 }
 
 #[derive(Deserialize, Serialize, Debug, Clone)]
@@ -13,14 +23,165 @@ pub struct ErrorErrorsItem {
     pub message: String,
 }
 
+impl Error {
+    pub fn first_error(&self) -> Option<&ErrorErrorsItem> {
+        self.errors.first()
+    }
+
+    pub fn problem_type(&self) -> Option<&ProblemType> {
+        self.problem
+            .as_ref()
+            .map(|x| &x.r#type)
+            .or_else(|| self.partial_errors.first().map(|x| &x.r#type))
+    }
+}
+
+impl ErrorErrorsItem {
+    pub fn error_code(&self) -> ErrorCode {
+        ErrorCode::from(self.code)
+    }
+}
+
 //
 impl From<V2ResponseBodyErrJson> for Error {
     fn from(value: V2ResponseBodyErrJson) -> Self {
         Self {
             errors: vec![ErrorErrorsItem {
                 code: 0,
-                message: value.detail,
+                message: value.detail.to_owned(),
             }],
+            problem: Some(Box::new(value)),
+            partial_errors: vec![],
+        }
+    }
+}
+
+impl From<Vec<V2PartialError>> for Error {
+    fn from(value: Vec<V2PartialError>) -> Self {
+        Self {
+            errors: value
+                .iter()
+                .map(|x| ErrorErrorsItem {
+                    code: partial_error_code(x).map(i64::from).unwrap_or_default(),
+                    message: if x.detail.is_empty() {
+                        x.title.to_owned()
+                    } else {
+                        x.detail.to_owned()
+                    },
+                })
+                .collect(),
+            problem: None,
+            partial_errors: value,
+        }
+    }
+}
+
+// The v1.1 code of the equivalent error, when there is one.
+fn partial_error_code(err: &V2PartialError) -> Option<ErrorCode> {
+    match (&err.r#type, err.resource_type.as_deref()) {
+        (ProblemType::ResourceNotFound, Some("user")) => Some(ErrorCode::UserNotFound),
+        (ProblemType::ResourceNotFound, Some("tweet")) => Some(ErrorCode::NoStatusFound),
+        (ProblemType::NotAuthorizedForResource, Some("tweet")) => {
+            Some(ErrorCode::NotAuthorizedToSeeStatus)
+        }
+        _ => None,
+    }
+}
+
+//
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum ErrorCode {
+    NoUserMatches,
+    CouldNotAuthenticate,
+    PageNotExist,
+    ParameterMissing,
+    UserNotFound,
+    UserSuspended,
+    AccountSuspended,
+    ClientNotPermitted,
+    RateLimitExceeded,
+    InvalidOrExpiredToken,
+    OverCapacity,
+    InternalError,
+    TimestampOutOfBounds,
+    NoStatusFound,
+    NotAuthorizedToSeeStatus,
+    DailyStatusUpdateLimit,
+    StatusTooLong,
+    DuplicateStatus,
+    BadAuthenticationData,
+    AutomatedRequest,
+    AppCannotWrite,
+    MediaIdValidationFailed,
+    AccountTemporarilyLocked,
+    ReplyToDeletedOrInvisibleTweet,
+    TooManyAttachmentTypes,
+    Other(i64),
+}
+
+impl From<i64> for ErrorCode {
+    fn from(code: i64) -> Self {
+        match code {
+            17 => Self::NoUserMatches,
+            32 => Self::CouldNotAuthenticate,
+            34 => Self::PageNotExist,
+            38 => Self::ParameterMissing,
+            50 => Self::UserNotFound,
+            63 => Self::UserSuspended,
+            64 => Self::AccountSuspended,
+            87 => Self::ClientNotPermitted,
+            88 => Self::RateLimitExceeded,
+            89 => Self::InvalidOrExpiredToken,
+            130 => Self::OverCapacity,
+            131 => Self::InternalError,
+            135 => Self::TimestampOutOfBounds,
+            144 => Self::NoStatusFound,
+            179 => Self::NotAuthorizedToSeeStatus,
+            185 => Self::DailyStatusUpdateLimit,
+            186 => Self::StatusTooLong,
+            187 => Self::DuplicateStatus,
+            215 => Self::BadAuthenticationData,
+            226 => Self::AutomatedRequest,
+            261 => Self::AppCannotWrite,
+            324 => Self::MediaIdValidationFailed,
+            326 => Self::AccountTemporarilyLocked,
+            385 => Self::ReplyToDeletedOrInvisibleTweet,
+            386 => Self::TooManyAttachmentTypes,
+            code => Self::Other(code),
+        }
+    }
+}
+
+impl From<ErrorCode> for i64 {
+    fn from(code: ErrorCode) -> Self {
+        match code {
+            ErrorCode::NoUserMatches => 17,
+            ErrorCode::CouldNotAuthenticate => 32,
+            ErrorCode::PageNotExist => 34,
+            ErrorCode::ParameterMissing => 38,
+            ErrorCode::UserNotFound => 50,
+            ErrorCode::UserSuspended => 63,
+            ErrorCode::AccountSuspended => 64,
+            ErrorCode::ClientNotPermitted => 87,
+            ErrorCode::RateLimitExceeded => 88,
+            ErrorCode::InvalidOrExpiredToken => 89,
+            ErrorCode::OverCapacity => 130,
+            ErrorCode::InternalError => 131,
+            ErrorCode::TimestampOutOfBounds => 135,
+            ErrorCode::NoStatusFound => 144,
+            ErrorCode::NotAuthorizedToSeeStatus => 179,
+            ErrorCode::DailyStatusUpdateLimit => 185,
+            ErrorCode::StatusTooLong => 186,
+            ErrorCode::DuplicateStatus => 187,
+            ErrorCode::BadAuthenticationData => 215,
+            ErrorCode::AutomatedRequest => 226,
+            ErrorCode::AppCannotWrite => 261,
+            ErrorCode::MediaIdValidationFailed => 324,
+            ErrorCode::AccountTemporarilyLocked => 326,
+            ErrorCode::ReplyToDeletedOrInvisibleTweet => 385,
+            ErrorCode::TooManyAttachmentTypes => 386,
+            ErrorCode::Other(code) => code,
         }
     }
 }
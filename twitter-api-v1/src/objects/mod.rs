@@ -8,4 +8,4 @@ pub use user::*;
 //
 pub mod error;
 
-pub use error::Error as ResponseBodyErrJson;
+pub use error::{Error as ResponseBodyErrJson, ErrorCode};
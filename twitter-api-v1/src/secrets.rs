@@ -29,3 +29,17 @@ impl TokenSecrets {
             .token(&self.oauth_token, &self.oauth_token_secret)
     }
 }
+
+// OAuth 2.0 user access token (Authorization Code with PKCE), sent as a bearer token.
+#[derive(Debug, Clone)]
+#[non_exhaustive]
+pub struct UserAccessToken {
+    pub access_token: String,
+}
+impl UserAccessToken {
+    pub fn new(access_token: impl AsRef<str>) -> Self {
+        Self {
+            access_token: access_token.as_ref().into(),
+        }
+    }
+}
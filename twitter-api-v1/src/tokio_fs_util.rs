@@ -30,3 +30,26 @@ pub async fn info(path: &PathBuf) -> Result<Info, IoError> {
         file_name,
     })
 }
+
+//
+pub async fn sha256(path: &PathBuf) -> Result<String, IoError> {
+    use sha2::{Digest as _, Sha256};
+    use tokio::{fs::File, io::AsyncReadExt as _};
+
+    let mut file = File::open(path).await?;
+    let mut hasher = Sha256::new();
+    let mut buf = vec![0; 64 * 1024];
+    loop {
+        let n = file.read(&mut buf).await?;
+        if n == 0 {
+            break;
+        }
+        hasher.update(&buf[..n]);
+    }
+
+    Ok(hasher
+        .finalize()
+        .iter()
+        .map(|x| format!("{x:02x}"))
+        .collect())
+}
@@ -0,0 +1,14 @@
+// Not every helper is used under every feature set.
+#![allow(dead_code)]
+
+use std::path::PathBuf;
+
+use twitter_api_v1::TokenSecrets;
+
+pub fn token_secrets() -> TokenSecrets {
+    TokenSecrets::new("consumer_key", "consumer_secret", "token", "token_secret")
+}
+
+pub fn temp_path(file_name: &str) -> PathBuf {
+    std::env::temp_dir().join(format!("twitter-api-v1-{}-{file_name}", std::process::id()))
+}
@@ -0,0 +1,134 @@
+use std::sync::{Arc, Mutex};
+
+use twitter_api_mock::MockServer;
+use twitter_api_v1::{
+    endpoints::media::download::{
+        download_media_to_file, download_media_to_writer, DownloadOptions, DownloadProgress,
+    },
+    TwitterError,
+};
+use twitter_api_v2::objects::Media;
+
+use crate::common::temp_path;
+
+#[tokio::test]
+async fn test_download_media() -> Result<(), Box<dyn std::error::Error>> {
+    let server = MockServer::builder().start().await?;
+    let media = serde_json::from_str::<Vec<Media>>(
+        r#"[
+            {"media_key":"3_1","type":"photo","url":"https://pbs.twimg.com/media/FoUkFj6XgAEPtnd.jpg"},
+            {"media_key":"7_2","type":"video","variants":[
+                {"bit_rate":632000,"content_type":"video/mp4","url":"https://video.twimg.com/ext_tw_video/2/pu/vid/480x270/a.mp4"},
+                {"bit_rate":2176000,"content_type":"video/mp4","url":"https://video.twimg.com/ext_tw_video/2/pu/vid/1280x720/b.mp4"}
+            ]},
+            {"media_key":"16_3","type":"animated_gif","variants":[
+                {"bit_rate":0,"content_type":"video/mp4","url":"https://video.twimg.com/tweet_video/c.mp4"}
+            ]}
+        ]"#,
+    )?;
+    let video = (0..300 * 1024).map(|x| (x % 251) as u8).collect::<Vec<_>>();
+    server.serve_asset(
+        "https://pbs.twimg.com/media/FoUkFj6XgAEPtnd.jpg",
+        Some("image/jpeg"),
+        b"\xFF\xD8\xFFjpeg".to_vec(),
+    );
+    server.serve_asset(
+        "https://video.twimg.com/ext_tw_video/2/pu/vid/1280x720/b.mp4",
+        Some("video/mp4"),
+        video.to_owned(),
+    );
+    server.serve_asset_chunked(
+        "https://video.twimg.com/tweet_video/c.mp4",
+        Some("video/mp4"),
+        video.to_owned(),
+    );
+
+    //
+    let mut buf = vec![];
+    let downloaded = download_media_to_writer(
+        server.client(),
+        &media[0],
+        &mut buf,
+        0,
+        &DownloadOptions::default(),
+    )
+    .await?;
+    assert_eq!(buf, b"\xFF\xD8\xFFjpeg");
+    assert_eq!(downloaded.content_type.as_deref(), Some("image/jpeg"));
+    assert_eq!(
+        server.received_requests()[0].query_param("name"),
+        Some("orig")
+    );
+
+    // Resumed from a partial file.
+    let file_path = temp_path("download-media.mp4");
+    tokio::fs::write(&file_path, &video[..1000]).await?;
+    let events = Arc::new(Mutex::new(vec![]));
+    let events_for_progress = events.clone();
+    let options = DownloadOptions::default().progress(Arc::new(move |x| {
+        events_for_progress.lock().expect("").push(x)
+    }));
+    let downloaded =
+        download_media_to_file(server.client(), &media[1], &file_path, &options).await?;
+    assert!(downloaded.resumed);
+    assert_eq!(downloaded.bytes_written, video.len() as u64 - 1000);
+    assert_eq!(downloaded.total_bytes, Some(video.len() as u64));
+    assert_eq!(tokio::fs::read(&file_path).await?, video);
+    match events.lock().expect("").as_slice() {
+        [DownloadProgress::Started {
+            resumed_from: 1000, ..
+        }, .., DownloadProgress::BytesWritten {
+            bytes_completed, ..
+        }] => assert_eq!(*bytes_completed, video.len() as u64),
+        x => panic!("{x:?}"),
+    }
+
+    // Already complete.
+    let downloaded = download_media_to_file(
+        server.client(),
+        &media[1],
+        &file_path,
+        &DownloadOptions::default(),
+    )
+    .await?;
+    assert_eq!(downloaded.bytes_written, 0);
+    assert_eq!(tokio::fs::read(&file_path).await?, video);
+    tokio::fs::remove_file(&file_path).await?;
+
+    // The range is ignored, the bytes the writer holds are skipped.
+    let mut buf = video[..10].to_vec();
+    let downloaded = download_media_to_writer(
+        server.client(),
+        &media[2],
+        &mut buf,
+        10,
+        &DownloadOptions::default(),
+    )
+    .await?;
+    assert!(!downloaded.resumed);
+    assert_eq!(downloaded.bytes_written, video.len() as u64 - 10);
+    assert_eq!(buf, video);
+
+    //
+    server.serve_asset(
+        "https://pbs.twimg.com/media/FoUkFj6XgAEPtnd.jpg",
+        Some("text/html"),
+        b"<html>".to_vec(),
+    );
+    match download_media_to_writer(
+        server.client(),
+        &media[0],
+        &mut vec![],
+        0,
+        &DownloadOptions::default(),
+    )
+    .await
+    {
+        Err(TwitterError::Validation(msg)) => {
+            assert_eq!(msg, r#"content_type "text/html" does not match photo"#)
+        }
+        x => panic!("{x:?}"),
+    }
+
+    Ok(())
+}
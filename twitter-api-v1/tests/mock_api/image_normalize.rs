@@ -0,0 +1,100 @@
+use twitter_api_mock::{MockServer, Route};
+use twitter_api_v1::{
+    endpoints::{media::upload_media::upload_image_from_file_normalized, EndpointRet},
+    media_util::{ImageTransform, NormalizeOptions},
+    objects::MediaCategory,
+};
+
+use crate::common::{temp_path, token_secrets};
+
+#[tokio::test]
+async fn test_upload_image_normalized() -> Result<(), Box<dyn std::error::Error>> {
+    let server = MockServer::builder().start().await?;
+    let secrets = token_secrets();
+
+    // 48x16 JPEG with an EXIF orientation of 6 (rotated 90 degrees clockwise).
+    let mut jpeg = vec![];
+    image::codecs::jpeg::JpegEncoder::new(&mut jpeg).encode_image(&image::RgbImage::new(48, 16))?;
+    let exif = b"Exif\0\0MM\0\x2a\0\0\0\x08\0\x01\x01\x12\0\x03\0\0\0\x01\0\x06\0\0\0\0\0\0";
+    let mut app1 = b"\xFF\xE1".to_vec();
+    app1.extend_from_slice(&((exif.len() + 2) as u16).to_be_bytes());
+    app1.extend_from_slice(exif);
+    let jpeg = [&jpeg[..2], &app1, &jpeg[2..]].concat();
+
+    let file_path = temp_path("normalized.jpeg");
+    tokio::fs::write(&file_path, &jpeg).await?;
+
+    //
+    let (ret, transforms) = upload_image_from_file_normalized(
+        &secrets,
+        server.client(),
+        MediaCategory::TweetImage,
+        &file_path,
+        Some("A rotated photo"),
+        &NormalizeOptions::default(),
+    )
+    .await?;
+    tokio::fs::remove_file(&file_path).await?;
+    assert!(matches!(ret, EndpointRet::Ok(_)));
+    assert_eq!(transforms[0], ImageTransform::MetadataStripped);
+    assert_eq!(
+        transforms[1],
+        ImageTransform::OrientationApplied {
+            exif_orientation: 6
+        }
+    );
+
+    let requests = server.received_requests_for(Route::MediaUpload);
+    let media = requests[0].multipart_part("media").unwrap();
+    assert!(!media.windows(4).any(|x| x == b"Exif"));
+    let uploaded = image::load_from_memory(&media)?;
+    assert_eq!((uploaded.width(), uploaded.height()), (16, 48));
+    server.assert_received(Route::MediaMetadataCreate, 1);
+
+    // 16x16 PNG with only a tEXt chunk, small enough for the lossless path.
+    let mut png = std::io::Cursor::new(vec![]);
+    image::RgbImage::new(16, 16).write_to(&mut png, image::ImageFormat::Png)?;
+    let png = png.into_inner();
+    let text = png_chunk(b"tEXt", b"Location\0Home");
+    let png = [&png[..33], &text, &png[33..]].concat();
+
+    let file_path = temp_path("normalized.png");
+    tokio::fs::write(&file_path, &png).await?;
+
+    server.clear_received_requests();
+    let (ret, transforms) = upload_image_from_file_normalized(
+        &secrets,
+        server.client(),
+        MediaCategory::TweetImage,
+        &file_path,
+        None,
+        &NormalizeOptions::default(),
+    )
+    .await?;
+    tokio::fs::remove_file(&file_path).await?;
+    assert!(matches!(ret, EndpointRet::Ok(_)));
+    assert_eq!(transforms, vec![ImageTransform::MetadataStripped]);
+
+    let requests = server.received_requests_for(Route::MediaUpload);
+    let media = requests[0].multipart_part("media").unwrap();
+    assert_eq!(media.len(), png.len() - text.len());
+    assert!(!media.windows(4).any(|x| x == b"tEXt"));
+
+    Ok(())
+}
+
+fn png_chunk(chunk_type: &[u8; 4], data: &[u8]) -> Vec<u8> {
+    let mut crc = !0_u32;
+    for byte in chunk_type.iter().chain(data) {
+        crc ^= *byte as u32;
+        for _ in 0..8 {
+            crc = (crc >> 1) ^ (0xEDB8_8320 & (crc & 1).wrapping_neg());
+        }
+    }
+
+    let mut chunk = (data.len() as u32).to_be_bytes().to_vec();
+    chunk.extend_from_slice(chunk_type);
+    chunk.extend_from_slice(data);
+    chunk.extend_from_slice(&(!crc).to_be_bytes());
+    chunk
+}
@@ -0,0 +1,27 @@
+mod common;
+
+#[cfg(feature = "with_tokio_fs")]
+mod download;
+#[cfg(all(feature = "with_tokio_fs", feature = "with_image"))]
+mod image_normalize;
+#[cfg(feature = "with_tokio")]
+mod manage_tweets;
+#[cfg(feature = "with_tokio_fs")]
+mod metadata;
+mod mock_server;
+#[cfg(feature = "with_tokio_fs")]
+mod subtitles;
+mod tweets_lookup;
+#[cfg(feature = "with_tokio_fs")]
+mod upload;
+#[cfg(feature = "with_tokio")]
+mod upload_cache;
+#[cfg(feature = "with_tokio")]
+mod upload_media;
+#[cfg(feature = "with_tokio_fs")]
+mod upload_progress;
+#[cfg(feature = "with_tokio_fs")]
+mod upload_session;
+#[cfg(feature = "with_tokio")]
+mod upload_v2;
+mod users_lookup;
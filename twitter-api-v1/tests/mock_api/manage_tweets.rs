@@ -0,0 +1,131 @@
+use twitter_api_mock::{Failure, MockServer, Route};
+use twitter_api_v1::{
+    endpoints::{
+        media::upload::{upload_media_from_bytes, UploadOptions},
+        tweets::manage_tweets::{create_tweet, create_tweet_with_request, delete_tweet},
+        EndpointRet,
+    },
+    objects::MediaCategory,
+};
+use twitter_api_v2::endpoints::tweets::manage_tweets::{CreateTweetRequest, ReplySettings};
+
+use crate::common::token_secrets;
+
+#[tokio::test]
+async fn test_create_tweet_with_request() -> Result<(), Box<dyn std::error::Error>> {
+    let server = MockServer::builder().processing_steps(0).start().await?;
+    let secrets = token_secrets();
+
+    let ok_json = upload_media_from_bytes(
+        &secrets,
+        server.client(),
+        b"logo".to_vec(),
+        "image/png",
+        MediaCategory::TweetImage,
+        &UploadOptions::default(),
+    )
+    .await?;
+
+    //
+    let request = CreateTweetRequest::new()
+        .text("logo")
+        .media_keys([ok_json.media_key.as_str()])
+        .tagged_user_ids([2244994945])
+        .reply_settings(ReplySettings::Following);
+    let tweet_id = match create_tweet_with_request(&secrets, server.client(), &request).await? {
+        EndpointRet::Ok(ok_json) => ok_json.id,
+        x => panic!("{x:?}"),
+    };
+    let requests = server.received_requests_for(Route::TweetsCreate);
+    assert_eq!(
+        requests[0].json().unwrap(),
+        serde_json::json!({
+            "text": "logo",
+            "media": {
+                "media_ids": [ok_json.media_id.to_string()],
+                "tagged_user_ids": ["2244994945"]
+            },
+            "reply_settings": "following"
+        })
+    );
+
+    let request = CreateTweetRequest::new()
+        .text("which one?")
+        .in_reply_to_tweet_id(tweet_id)
+        .poll(["this", "that"], 60);
+    match create_tweet_with_request(&secrets, server.client(), &request).await? {
+        EndpointRet::Ok(_) => {}
+        x => panic!("{x:?}"),
+    }
+    let requests = server.received_requests_for(Route::TweetsCreate);
+    assert_eq!(
+        requests[1].json().unwrap()["reply"]["in_reply_to_tweet_id"],
+        serde_json::json!(tweet_id.to_string())
+    );
+
+    // Rejected before sending.
+    let request = request.media_ids([ok_json.media_id]);
+    match create_tweet_with_request(&secrets, server.client(), &request).await {
+        Err(twitter_api_v1::endpoints::EndpointError::ValidateFailed(msg)) => {
+            assert_eq!(msg, "poll and media are mutually exclusive")
+        }
+        x => panic!("{x:?}"),
+    }
+    server.assert_received(Route::TweetsCreate, 2);
+
+    //
+    let mut other_parameters = serde_json::Map::new();
+    other_parameters.insert(
+        "poll".into(),
+        serde_json::json!({ "options": ["a", "b"], "duration_minutes": 5 }),
+    );
+    match create_tweet(
+        &secrets,
+        server.client(),
+        None,
+        Some(vec![ok_json.media_id]),
+        Some(other_parameters),
+    )
+    .await?
+    {
+        EndpointRet::Other((status, _)) => assert_eq!(status, 400),
+        x => panic!("{x:?}"),
+    }
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_delete_tweet() -> Result<(), Box<dyn std::error::Error>> {
+    let server = MockServer::start().await?;
+    let secrets = token_secrets();
+
+    let tweet_id = match create_tweet(&secrets, server.client(), Some("oops"), None, None).await? {
+        EndpointRet::Ok(ok_json) => ok_json.id,
+        x => panic!("{x:?}"),
+    };
+
+    match delete_tweet(&secrets, server.client(), tweet_id).await? {
+        EndpointRet::Ok(ok_json) => assert!(ok_json.deleted),
+        x => panic!("{x:?}"),
+    }
+    let requests = server.received_requests_for(Route::TweetsDelete);
+    assert_eq!(requests[0].path, format!("/2/tweets/{tweet_id}"));
+    assert!(requests[0].is_oauth1_signed());
+
+    match delete_tweet(&secrets, server.client(), tweet_id).await? {
+        EndpointRet::Other((status, Ok(_))) => assert_eq!(status, 404),
+        x => panic!("{x:?}"),
+    }
+
+    //
+    server.fail_next(Route::TweetsDelete, Failure::TooManyRequests { reset: 1 });
+    match delete_tweet(&secrets, server.client(), tweet_id).await? {
+        EndpointRet::RateLimited((rate_limit, _)) => {
+            assert_eq!(rate_limit.unwrap().reset, 1)
+        }
+        x => panic!("{x:?}"),
+    }
+
+    Ok(())
+}
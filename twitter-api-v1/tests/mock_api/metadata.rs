@@ -0,0 +1,128 @@
+use twitter_api_mock::{Failure, MockServer, Route};
+use twitter_api_v1::{
+    endpoints::{
+        media::upload_media::{
+            upload_image_from_file, upload_image_from_file_with_alt_text,
+            upload_image_with_alt_text, UploadWithMetadata,
+        },
+        EndpointError, EndpointRet,
+    },
+    objects::MediaCategory,
+};
+
+use crate::common::{temp_path, token_secrets};
+
+#[tokio::test]
+async fn test_upload_image_with_alt_text() -> Result<(), Box<dyn std::error::Error>> {
+    let server = MockServer::start().await?;
+    let secrets = token_secrets();
+
+    let file_path = temp_path("upload-image-alt-text.png");
+    tokio::fs::write(&file_path, b"\x89PNG\r\n\x1A\n").await?;
+
+    //
+    match upload_image_from_file(
+        &secrets,
+        server.client(),
+        MediaCategory::TweetImage,
+        &file_path,
+    )
+    .await?
+    {
+        EndpointRet::Ok(_) => {}
+        x => panic!("{x:?}"),
+    }
+    server.assert_received(Route::MediaMetadataCreate, 0);
+
+    //
+    let media_id = match upload_image_from_file_with_alt_text(
+        &secrets,
+        server.client(),
+        MediaCategory::TweetImage,
+        &file_path,
+        "A cat on a sofa",
+    )
+    .await?
+    {
+        EndpointRet::Ok(UploadWithMetadata {
+            upload,
+            metadata: Some(Ok(EndpointRet::Ok(()))),
+        }) => upload.media_id,
+        x => panic!("{x:?}"),
+    };
+    let requests = server.received_requests_for(Route::MediaMetadataCreate);
+    assert_eq!(
+        requests[0].json().unwrap(),
+        serde_json::json!({
+            "media_id": media_id.to_string(),
+            "alt_text": { "text": "A cat on a sofa" }
+        })
+    );
+
+    //
+    server.clear_received_requests();
+    match upload_image_with_alt_text(
+        &secrets,
+        server.client(),
+        MediaCategory::TweetImage,
+        b"\x89PNG\r\n\x1A\n".to_vec(),
+        Some(8),
+        Some("x.png".into()),
+        "A dog",
+    )
+    .await?
+    {
+        EndpointRet::Ok(UploadWithMetadata {
+            metadata: Some(Ok(EndpointRet::Ok(()))),
+            ..
+        }) => {}
+        x => panic!("{x:?}"),
+    }
+    server.assert_received(Route::MediaMetadataCreate, 1);
+
+    // The upload is kept when setting the alt text fails.
+    server.clear_received_requests();
+    server.fail_next(Route::MediaMetadataCreate, Failure::ServerError(503));
+    match upload_image_with_alt_text(
+        &secrets,
+        server.client(),
+        MediaCategory::TweetImage,
+        b"\x89PNG\r\n\x1A\n".to_vec(),
+        Some(8),
+        None,
+        "A dog",
+    )
+    .await?
+    {
+        EndpointRet::Ok(UploadWithMetadata {
+            upload,
+            metadata: Some(Ok(EndpointRet::Other((status, _)))),
+        }) => {
+            assert_eq!(status, 503);
+            assert!(upload.media_id > 0);
+        }
+        x => panic!("{x:?}"),
+    }
+
+    // Rejected before uploading.
+    server.clear_received_requests();
+    match upload_image_with_alt_text(
+        &secrets,
+        server.client(),
+        MediaCategory::TweetImage,
+        b"\x89PNG\r\n\x1A\n".to_vec(),
+        Some(8),
+        None,
+        &"x".repeat(1001),
+    )
+    .await
+    {
+        Err(EndpointError::ValidateFailed(_)) => {}
+        x => panic!("{x:?}"),
+    }
+    assert!(server.received_requests().is_empty());
+
+    tokio::fs::remove_file(&file_path).await?;
+
+    Ok(())
+}
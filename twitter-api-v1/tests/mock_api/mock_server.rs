@@ -0,0 +1,315 @@
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use http_api_client_endpoint::Endpoint as _;
+use oauth1_twitter::{
+    endpoints::{request_token::RequestTokenEndpoint, EndpointRet as Oauth1EndpointRet},
+    objects::consumer_key::ConsumerKey,
+};
+use twitter_api_mock::{Cassette, Failure, MockServer, Route};
+use twitter_api_v1::{
+    endpoints::{
+        media::upload_media::{
+            get_upload_status, upload_append, upload_finalize, upload_image, upload_init,
+        },
+        tweets::manage_tweets::create_tweet,
+        users::lookup::show_user_by_id,
+        EndpointRet,
+    },
+    objects::{MediaCategory, MediaProcessingInfoState},
+};
+
+use crate::common::{temp_path, token_secrets};
+
+#[tokio::test]
+async fn test_media_upload_flow() -> Result<(), Box<dyn std::error::Error>> {
+    let server = MockServer::builder()
+        .processing_steps(2)
+        .check_after_secs(0)
+        .start()
+        .await?;
+    let secrets = token_secrets();
+
+    //
+    let media_id = match upload_init(
+        &secrets,
+        server.client(),
+        8,
+        "video/mp4",
+        MediaCategory::TweetVideo,
+    )
+    .await?
+    {
+        EndpointRet::Ok(ok_json) => ok_json.media_id,
+        x => panic!("{x:?}"),
+    };
+
+    for (segment_index, segment) in [b"\x00\x01\x02\x03", b"\x04\x05\x06\x07"]
+        .into_iter()
+        .enumerate()
+    {
+        match upload_append(
+            &secrets,
+            server.client(),
+            media_id,
+            segment_index,
+            segment.to_vec(),
+            Some(4),
+        )
+        .await?
+        {
+            EndpointRet::Ok(_) => {}
+            x => panic!("{x:?}"),
+        }
+    }
+
+    match upload_finalize(&secrets, server.client(), media_id).await? {
+        EndpointRet::Ok(ok_json) => {
+            let processing_info = ok_json.processing_info.unwrap();
+            assert_eq!(processing_info.state, MediaProcessingInfoState::Pending);
+            assert_eq!(processing_info.check_after_secs, Some(0));
+        }
+        x => panic!("{x:?}"),
+    }
+
+    //
+    match create_tweet(
+        &secrets,
+        server.client(),
+        Some("x"),
+        Some(vec![media_id]),
+        None,
+    )
+    .await?
+    {
+        EndpointRet::Other((status, _)) => assert_eq!(status, 400),
+        x => panic!("{x:?}"),
+    }
+
+    for state in [
+        MediaProcessingInfoState::InProgress,
+        MediaProcessingInfoState::Succeeded,
+    ] {
+        match get_upload_status(&secrets, server.client(), media_id).await? {
+            EndpointRet::Ok(ok_json) => {
+                assert_eq!(ok_json.media_id, media_id);
+                assert_eq!(ok_json.processing_info.unwrap().state, state);
+            }
+            x => panic!("{x:?}"),
+        }
+    }
+
+    match create_tweet(
+        &secrets,
+        server.client(),
+        Some("x"),
+        Some(vec![media_id]),
+        None,
+    )
+    .await?
+    {
+        EndpointRet::Ok(ok_json) => assert!(ok_json.id > media_id),
+        x => panic!("{x:?}"),
+    }
+
+    //
+    server.assert_received(Route::MediaUploadInit, 1);
+    server.assert_received(Route::MediaUploadAppend, 2);
+    server.assert_received(Route::MediaUploadFinalize, 1);
+    server.assert_received(Route::MediaUploadStatus, 2);
+    server.assert_received(Route::TweetsCreate, 2);
+
+    let requests = server.received_requests_for(Route::MediaUploadAppend);
+    assert!(requests.iter().all(|x| x.is_oauth1_signed()));
+    assert_eq!(requests[1].query_param("segment_index"), Some("1"));
+    assert_eq!(
+        requests[1].multipart_part("media"),
+        Some(b"\x04\x05\x06\x07".to_vec())
+    );
+
+    let requests = server.received_requests_for(Route::TweetsCreate);
+    assert_eq!(
+        requests[1].json().unwrap()["media"]["media_ids"][0],
+        media_id.to_string()
+    );
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_failures() -> Result<(), Box<dyn std::error::Error>> {
+    let server = MockServer::builder().check_after_secs(0).start().await?;
+    let secrets = token_secrets();
+
+    //
+    let reset = SystemTime::now().duration_since(UNIX_EPOCH)?.as_secs() + 60;
+    server.fail_next(Route::MediaUpload, Failure::TooManyRequests { reset });
+
+    match upload_image(
+        &secrets,
+        server.client(),
+        MediaCategory::TweetImage,
+        b"\xff\xd8\xff".to_vec(),
+        Some(3),
+        None,
+    )
+    .await?
+    {
+        EndpointRet::RateLimited((rate_limit, Ok(err_json))) => {
+            assert_eq!(rate_limit.unwrap().reset, reset);
+            assert_eq!(err_json.errors[0].code, 88);
+        }
+        x => panic!("{x:?}"),
+    }
+
+    match upload_image(
+        &secrets,
+        server.client(),
+        MediaCategory::TweetImage,
+        b"\xff\xd8\xff".to_vec(),
+        Some(3),
+        None,
+    )
+    .await?
+    {
+        EndpointRet::Ok(ok_json) => assert_eq!(ok_json.size, Some(3)),
+        x => panic!("{x:?}"),
+    }
+
+    //
+    let media_id = match upload_init(
+        &secrets,
+        server.client(),
+        1,
+        "video/mp4",
+        MediaCategory::TweetVideo,
+    )
+    .await?
+    {
+        EndpointRet::Ok(ok_json) => ok_json.media_id,
+        x => panic!("{x:?}"),
+    };
+
+    server.fail_times(Route::MediaUploadAppend, Failure::ServerError(503), 2);
+    for _ in 0..2 {
+        match upload_append(&secrets, server.client(), media_id, 0, vec![0], Some(1)).await? {
+            EndpointRet::Other((status, _)) => assert_eq!(status, 503),
+            x => panic!("{x:?}"),
+        }
+    }
+    match upload_append(&secrets, server.client(), media_id, 0, vec![0], Some(1)).await? {
+        EndpointRet::Ok(_) => {}
+        x => panic!("{x:?}"),
+    }
+
+    match upload_finalize(&secrets, server.client(), media_id).await? {
+        EndpointRet::Ok(_) => {}
+        x => panic!("{x:?}"),
+    }
+
+    server.fail_next(Route::MediaUploadStatus, Failure::ProcessingFailed);
+    match get_upload_status(&secrets, server.client(), media_id).await? {
+        EndpointRet::Ok(ok_json) => {
+            let processing_info = ok_json.processing_info.unwrap();
+            assert_eq!(processing_info.state, MediaProcessingInfoState::Failed);
+            assert_eq!(processing_info.error.unwrap().name, "InvalidMedia");
+        }
+        x => panic!("{x:?}"),
+    }
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_users_and_oauth() -> Result<(), Box<dyn std::error::Error>> {
+    let server = MockServer::start().await?;
+
+    //
+    match show_user_by_id(&token_secrets(), server.client(), 783214, None).await? {
+        EndpointRet::Ok(user) => assert_eq!(user.id, 783214),
+        x => panic!("{x:?}"),
+    }
+
+    //
+    let endpoint = RequestTokenEndpoint::new(
+        ConsumerKey::new("consumer_key", "consumer_secret"),
+        "http://localhost/callback",
+    );
+    let response = server.send(endpoint.render_request()?).await?;
+    match endpoint.parse_response(response)? {
+        Oauth1EndpointRet::Ok(body) => assert!(body.oauth_callback_confirmed),
+        x => panic!("{x:?}"),
+    }
+
+    let requests = server.received_requests_for(Route::OauthRequestToken);
+    assert!(requests[0]
+        .authorization()
+        .unwrap()
+        .contains("oauth_callback="));
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_cassette() -> Result<(), Box<dyn std::error::Error>> {
+    let path = temp_path("cassette.json");
+
+    // Record through another mock server standing in for the real API.
+    let upstream = MockServer::start().await?;
+    let server = MockServer::builder()
+        .record(&path)
+        .upstream(upstream.client())
+        .start()
+        .await?;
+
+    match show_user_by_id(&token_secrets(), server.client(), 783214, None).await? {
+        EndpointRet::Ok(user) => assert_eq!(user.id, 783214),
+        x => panic!("{x:?}"),
+    }
+    let endpoint = RequestTokenEndpoint::new(
+        ConsumerKey::new("consumer_key", "consumer_secret"),
+        "http://localhost/callback",
+    );
+    let response = server.send(endpoint.render_request()?).await?;
+    assert!(matches!(
+        endpoint.parse_response(response)?,
+        Oauth1EndpointRet::Ok(_)
+    ));
+    upstream.assert_received(Route::UsersLookupById, 1);
+    drop(server);
+
+    let cassette = Cassette::load(&path)?;
+    assert_eq!(cassette.interactions.len(), 2);
+    let authorization = &cassette.interactions[0].request.headers["authorization"];
+    assert!(authorization.contains(r#"oauth_signature="REDACTED""#));
+    assert!(!authorization.contains("consumer_key\""));
+    let body = String::from_utf8(cassette.interactions[1].response.body.to_vec())?;
+    assert!(body.contains("oauth_token=REDACTED"));
+    assert!(body.contains("oauth_callback_confirmed=true"));
+
+    // Replay without the upstream.
+    drop(upstream);
+    let server = MockServer::builder().replay(&path).start().await?;
+
+    match show_user_by_id(&token_secrets(), server.client(), 783214, None).await? {
+        EndpointRet::Ok(user) => assert_eq!(user.id, 783214),
+        x => panic!("{x:?}"),
+    }
+    assert_eq!(server.unused_interactions().len(), 1);
+
+    match show_user_by_id(&token_secrets(), server.client(), 783214, None).await? {
+        EndpointRet::Other((status, _)) => assert_eq!(status, 500),
+        x => panic!("{x:?}"),
+    }
+
+    let response = server.send(endpoint.render_request()?).await?;
+    assert!(matches!(
+        endpoint.parse_response(response)?,
+        Oauth1EndpointRet::Ok(_)
+    ));
+    server.assert_cassette_exhausted();
+    server.assert_received(Route::UsersLookupById, 2);
+
+    std::fs::remove_file(&path)?;
+
+    Ok(())
+}
@@ -0,0 +1,111 @@
+use twitter_api_mock::{MockServer, Route};
+use twitter_api_v1::{
+    endpoints::{
+        media::{
+            subtitles::{create_subtitles, delete_subtitles, Subtitle},
+            upload::{upload_media_from_bytes, upload_media_from_reader, UploadOptions},
+        },
+        EndpointRet,
+    },
+    objects::MediaCategory,
+    TwitterError,
+};
+
+use crate::common::token_secrets;
+
+#[tokio::test]
+async fn test_subtitles() -> Result<(), Box<dyn std::error::Error>> {
+    let server = MockServer::builder()
+        .processing_steps(0)
+        .check_after_secs(0)
+        .start()
+        .await?;
+    let secrets = token_secrets();
+
+    //
+    let video = upload_media_from_bytes(
+        &secrets,
+        server.client(),
+        b"0123456789".to_vec(),
+        "video/mp4",
+        MediaCategory::TweetVideo,
+        &UploadOptions::default(),
+    )
+    .await?;
+    let mut reader: &[u8] = b"1\n00:00:01,000 --> 00:00:02,000\nHello\n";
+    let srt = upload_media_from_reader(
+        &secrets,
+        server.client(),
+        &mut reader,
+        Some(40),
+        "application/x-subrip",
+        MediaCategory::Subtitles,
+        &UploadOptions::default(),
+    )
+    .await?;
+
+    // Invalid SRT is rejected before INIT.
+    server.clear_received_requests();
+    match upload_media_from_bytes(
+        &secrets,
+        server.client(),
+        b"1\n00:00:02,000 --> 00:00:01,000\nHello\n".to_vec(),
+        "application/x-subrip",
+        MediaCategory::Subtitles,
+        &UploadOptions::default(),
+    )
+    .await
+    {
+        Err(TwitterError::Validation(msg)) => {
+            assert_eq!(msg, "srt cue 1: end not after start")
+        }
+        x => panic!("{x:?}"),
+    }
+    assert!(server.received_requests().is_empty());
+
+    //
+    match create_subtitles(
+        &secrets,
+        server.client(),
+        video.media_id,
+        &[Subtitle::new(srt.media_id, "EN", "English")],
+    )
+    .await?
+    {
+        EndpointRet::Ok(()) => {}
+        x => panic!("{x:?}"),
+    }
+    let requests = server.received_requests_for(Route::MediaSubtitlesCreate);
+    assert_eq!(
+        requests[0].json().unwrap()["subtitle_info"]["subtitles"][0]["media_id"],
+        srt.media_id.to_string()
+    );
+
+    // A video is not a subtitle.
+    match create_subtitles(
+        &secrets,
+        server.client(),
+        video.media_id,
+        &[Subtitle::new(video.media_id, "FR", "Français")],
+    )
+    .await?
+    {
+        EndpointRet::Other((status, Ok(err_json))) => {
+            assert_eq!(status, 400);
+            assert_eq!(err_json.errors[0].code, 324);
+        }
+        x => panic!("{x:?}"),
+    }
+
+    //
+    match delete_subtitles(&secrets, server.client(), video.media_id, &["EN"]).await? {
+        EndpointRet::Ok(()) => {}
+        x => panic!("{x:?}"),
+    }
+    match delete_subtitles(&secrets, server.client(), video.media_id, &["EN"]).await? {
+        EndpointRet::Other((status, _)) => assert_eq!(status, 404),
+        x => panic!("{x:?}"),
+    }
+
+    Ok(())
+}
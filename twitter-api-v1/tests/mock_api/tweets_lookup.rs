@@ -0,0 +1,111 @@
+use twitter_api_mock::{Failure, MockServer, Route};
+use twitter_api_v1::{
+    endpoints::{
+        tweets::{
+            lookup::{lookup_tweets, show_tweet_by_id},
+            manage_tweets::create_tweet,
+        },
+        EndpointRet,
+    },
+    objects::ErrorCode,
+};
+
+use crate::common::token_secrets;
+
+#[tokio::test]
+async fn test_lookup_tweets() -> Result<(), Box<dyn std::error::Error>> {
+    let server = MockServer::start().await?;
+    let secrets = token_secrets();
+
+    let mut tweet_ids = vec![];
+    for text in ["first", "second"] {
+        match create_tweet(&secrets, server.client(), Some(text), None, None).await? {
+            EndpointRet::Ok(ok_json) => tweet_ids.push(ok_json.id),
+            x => panic!("{x:?}"),
+        }
+    }
+
+    match show_tweet_by_id(&secrets, server.client(), tweet_ids[1]).await? {
+        EndpointRet::Ok(tweet) => {
+            assert_eq!(tweet.id, Some(tweet_ids[1]));
+            assert_eq!(tweet.text.as_deref(), Some("second"));
+        }
+        x => panic!("{x:?}"),
+    }
+    let requests = server.received_requests_for(Route::TweetsLookupById);
+    assert_eq!(requests[0].path, format!("/2/tweets/{}", tweet_ids[1]));
+    assert!(requests[0].query_param("tweet.fields").is_some());
+    assert!(requests[0].is_oauth1_signed());
+
+    match show_tweet_by_id(&secrets, server.client(), 1).await? {
+        EndpointRet::PartialErrors(err_json) => {
+            assert_eq!(err_json.errors.len(), 1);
+            assert_eq!(
+                err_json.first_error().unwrap().error_code(),
+                ErrorCode::NoStatusFound
+            );
+            assert_eq!(err_json.partial_errors[0].resource_id.as_deref(), Some("1"));
+        }
+        x => panic!("{x:?}"),
+    }
+
+    //
+    let mut ids = vec![tweet_ids[0], 1, tweet_ids[1], tweet_ids[0]];
+    ids.extend(2..150);
+    match lookup_tweets(&secrets, server.client(), &ids).await? {
+        EndpointRet::Ok(lookup) => {
+            assert_eq!(
+                lookup.tweets.iter().map(|x| x.id).collect::<Vec<_>>(),
+                vec![Some(tweet_ids[0]), Some(tweet_ids[1])]
+            );
+            assert_eq!(lookup.not_found_ids, (1..150).collect::<Vec<_>>());
+            assert!(lookup.errors.is_empty());
+            assert!(lookup.failure.is_none());
+        }
+        x => panic!("{x:?}"),
+    }
+    let requests = server.received_requests_for(Route::TweetsLookup);
+    assert_eq!(requests.len(), 2);
+    assert_eq!(
+        requests[0].query_param("ids").unwrap().split(',').count(),
+        100
+    );
+    assert_eq!(
+        requests[1].query_param("ids").unwrap().split(',').count(),
+        51
+    );
+
+    assert!(matches!(
+        lookup_tweets(&secrets, server.client(), &[]).await,
+        Err(twitter_api_v1::endpoints::EndpointError::ValidateFailed(_))
+    ));
+
+    //
+    server.fail_next(Route::TweetsLookup, Failure::TooManyRequests { reset: 1 });
+    match lookup_tweets(&secrets, server.client(), &tweet_ids).await? {
+        EndpointRet::RateLimited((rate_limit, _)) => {
+            assert_eq!(rate_limit.unwrap().reset, 1)
+        }
+        x => panic!("{x:?}"),
+    }
+
+    // A later chunk failing keeps the earlier results.
+    server.clear_received_requests();
+    server.fail_next(Route::TweetsLookup, Failure::Pass);
+    server.fail_next(Route::TweetsLookup, Failure::TooManyRequests { reset: 1 });
+    let mut ids = tweet_ids.clone();
+    ids.extend(1..250);
+    match lookup_tweets(&secrets, server.client(), &ids).await? {
+        EndpointRet::Ok(lookup) => {
+            assert_eq!(lookup.tweets.len(), 2);
+            assert_eq!(lookup.not_found_ids, (1..99).collect::<Vec<_>>());
+            let failure = lookup.failure.unwrap();
+            assert_eq!(failure.ids, (99..250).collect::<Vec<_>>());
+            assert!(matches!(failure.ret, EndpointRet::RateLimited(_)));
+        }
+        x => panic!("{x:?}"),
+    }
+    server.assert_received(Route::TweetsLookup, 2);
+
+    Ok(())
+}
@@ -0,0 +1,394 @@
+use twitter_api_mock::{Failure, MockServer, Route};
+use twitter_api_v1::{
+    endpoints::{
+        media::{
+            metadata::create_media_metadata,
+            upload::{
+                upload_media, upload_media_from_bytes, upload_media_from_reader,
+                upload_media_from_url, UploadOptions,
+            },
+            upload_media::UploadInitOptions,
+        },
+        EndpointRet,
+    },
+    objects::{MediaCategory, MediaProcessingInfoState},
+    TwitterError,
+};
+
+use crate::common::{temp_path, token_secrets};
+
+#[tokio::test]
+async fn test_upload_media() -> Result<(), Box<dyn std::error::Error>> {
+    let server = MockServer::builder()
+        .processing_steps(2)
+        .check_after_secs(0)
+        .start()
+        .await?;
+    let secrets = token_secrets();
+
+    let file_path = temp_path("upload-media.mp4");
+    tokio::fs::write(&file_path, b"0123456789").await?;
+
+    //
+    let ok_json = upload_media(
+        &secrets,
+        server.client(),
+        &file_path,
+        "video/mp4",
+        MediaCategory::TweetVideo,
+        &UploadOptions::default(),
+    )
+    .await?;
+    assert_eq!(
+        ok_json.processing_info.unwrap().state,
+        MediaProcessingInfoState::Succeeded
+    );
+    server.assert_received(Route::MediaUploadStatus, 2);
+
+    // Alt text is set once processing succeeded.
+    server.clear_received_requests();
+    let ok_json = upload_media(
+        &secrets,
+        server.client(),
+        &file_path,
+        "image/gif",
+        MediaCategory::TweetGif,
+        &UploadOptions::default().alt_text("A dancing cat"),
+    )
+    .await?;
+    let requests = server.received_requests_for(Route::MediaMetadataCreate);
+    assert_eq!(
+        requests[0].json().unwrap(),
+        serde_json::json!({
+            "media_id": ok_json.media_id.to_string(),
+            "alt_text": { "text": "A dancing cat" }
+        })
+    );
+    assert!(requests[0].is_oauth1_signed());
+
+    match create_media_metadata(&secrets, server.client(), 1, "x").await? {
+        EndpointRet::Other((status, Ok(err_json))) => {
+            assert_eq!(status, 400);
+            assert_eq!(err_json.errors[0].code, 324);
+        }
+        x => panic!("{x:?}"),
+    }
+    match create_media_metadata(&secrets, server.client(), 1, &"x".repeat(1001)).await {
+        Err(twitter_api_v1::endpoints::EndpointError::ValidateFailed(_)) => {}
+        x => panic!("{x:?}"),
+    }
+
+    //
+    server.fail_next(Route::MediaUploadStatus, Failure::ProcessingFailed);
+    match upload_media(
+        &secrets,
+        server.client(),
+        &file_path,
+        "video/mp4",
+        MediaCategory::TweetVideo,
+        &UploadOptions::default(),
+    )
+    .await
+    {
+        Err(TwitterError::ProcessingFailed { error, .. }) => {
+            assert_eq!(error.unwrap().name, "InvalidMedia")
+        }
+        x => panic!("{x:?}"),
+    }
+
+    //
+    server.fail_next(Route::MediaUploadFinalize, Failure::ServerError(500));
+    match upload_media(
+        &secrets,
+        server.client(),
+        &file_path,
+        "video/mp4",
+        MediaCategory::TweetVideo,
+        &UploadOptions::default(),
+    )
+    .await
+    {
+        Err(err @ TwitterError::Api { .. }) => assert!(err.is_retryable()),
+        x => panic!("{x:?}"),
+    }
+
+    tokio::fs::remove_file(&file_path).await?;
+
+    //
+    let server = MockServer::builder().check_after_secs(5).start().await?;
+    let file_path = temp_path("upload-media-timeout.mp4");
+    tokio::fs::write(&file_path, b"0123456789").await?;
+    match upload_media(
+        &secrets,
+        server.client(),
+        &file_path,
+        "video/mp4",
+        MediaCategory::TweetVideo,
+        &UploadOptions::default().timeout(std::time::Duration::from_secs(2)),
+    )
+    .await
+    {
+        Err(TwitterError::Timeout { media_id }) => assert!(media_id.is_some()),
+        x => panic!("{x:?}"),
+    }
+    server.assert_received(Route::MediaUploadStatus, 0);
+
+    tokio::fs::remove_file(&file_path).await?;
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_upload_media_from_memory() -> Result<(), Box<dyn std::error::Error>> {
+    let server = MockServer::builder().processing_steps(0).start().await?;
+    let secrets = token_secrets();
+    let segment_size = twitter_api_v1::endpoints::media::upload_media::SEGMENT_SIZE;
+
+    //
+    let bytes = vec![1_u8; segment_size + 3];
+    let ok_json = upload_media_from_bytes(
+        &secrets,
+        server.client(),
+        bytes,
+        "video/mp4",
+        MediaCategory::TweetVideo,
+        &UploadOptions::default(),
+    )
+    .await?;
+    assert_eq!(ok_json.size, Some(segment_size + 3));
+    let requests = server.received_requests_for(Route::MediaUploadAppend);
+    assert_eq!(requests.len(), 2);
+    assert_eq!(requests[1].multipart_part("media"), Some(vec![1; 3]));
+
+    // Unknown length, spooled before INIT.
+    server.clear_received_requests();
+    let mut reader: &[u8] = b"0123456789";
+    let ok_json = upload_media_from_reader(
+        &secrets,
+        server.client(),
+        &mut reader,
+        None,
+        "video/mp4",
+        MediaCategory::TweetVideo,
+        &UploadOptions::default(),
+    )
+    .await?;
+    assert_eq!(ok_json.size, Some(10));
+    let requests = server.received_requests_for(Route::MediaUploadInit);
+    assert_eq!(requests[0].form_param("total_bytes").as_deref(), Some("10"));
+
+    // Known length, read segment by segment.
+    server.clear_received_requests();
+    let mut reader: &[u8] = b"0123456789";
+    upload_media_from_reader(
+        &secrets,
+        server.client(),
+        &mut reader,
+        Some(10),
+        "video/mp4",
+        MediaCategory::TweetVideo,
+        &UploadOptions::default(),
+    )
+    .await?;
+    let requests = server.received_requests_for(Route::MediaUploadAppend);
+    assert_eq!(
+        requests[0].multipart_part("media"),
+        Some(b"0123456789".to_vec())
+    );
+
+    // The reader ends before total_bytes.
+    let mut reader: &[u8] = b"01234";
+    match upload_media_from_reader(
+        &secrets,
+        server.client(),
+        &mut reader,
+        Some(10),
+        "video/mp4",
+        MediaCategory::TweetVideo,
+        &UploadOptions::default(),
+    )
+    .await
+    {
+        Err(TwitterError::Transport(
+            twitter_api_v1::endpoints::EndpointError::ReadSourceFailed(_),
+        )) => {}
+        x => panic!("{x:?}"),
+    }
+
+    // MP4 content is inspected before INIT.
+    server.clear_received_requests();
+    let mut mvhd = vec![0_u8; 100];
+    mvhd[12..16].copy_from_slice(&600_u32.to_be_bytes());
+    mvhd[16..20].copy_from_slice(&(150 * 600_u32).to_be_bytes());
+    let mp4 = [
+        &b"\0\0\0\x10ftypisom\0\0\0\0\0\0\0\x74moov\0\0\0\x6cmvhd"[..],
+        &mvhd,
+    ]
+    .concat();
+    match upload_media_from_bytes(
+        &secrets,
+        server.client(),
+        mp4.to_owned(),
+        "video/mp4",
+        MediaCategory::TweetVideo,
+        &UploadOptions::default(),
+    )
+    .await
+    {
+        Err(TwitterError::Validation(msg)) => assert_eq!(
+            msg,
+            "video invalid: duration 150.000s out of range 0.5s..=140s; video track missing"
+        ),
+        x => panic!("{x:?}"),
+    }
+    let file_path = temp_path("inspect.mp4");
+    tokio::fs::write(&file_path, &mp4).await?;
+    match upload_media(
+        &secrets,
+        server.client(),
+        &file_path,
+        "video/mp4",
+        MediaCategory::DmVideo,
+        &UploadOptions::default(),
+    )
+    .await
+    {
+        Err(TwitterError::Validation(msg)) => assert!(msg.contains("video track missing")),
+        x => panic!("{x:?}"),
+    }
+    tokio::fs::remove_file(&file_path).await?;
+    assert!(server.received_requests().is_empty());
+
+    // Too large for the category, rejected while spooling when asked to.
+    server.clear_received_requests();
+    let mut reader = tokio::io::AsyncReadExt::take(tokio::io::repeat(0), 6 * 1024 * 1024);
+    match upload_media_from_reader(
+        &secrets,
+        server.client(),
+        &mut reader,
+        None,
+        "image/png",
+        MediaCategory::TweetImage,
+        &UploadOptions::default().init(UploadInitOptions::default().validate_media(true)),
+    )
+    .await
+    {
+        Err(TwitterError::Validation(msg)) => {
+            assert!(msg.starts_with("media too large for tweet_image"))
+        }
+        x => panic!("{x:?}"),
+    }
+    assert!(server.received_requests().is_empty());
+
+    let mut reader = tokio::io::AsyncReadExt::take(tokio::io::repeat(0), 6 * 1024 * 1024);
+    let ok_json = upload_media_from_reader(
+        &secrets,
+        server.client(),
+        &mut reader,
+        None,
+        "image/png",
+        MediaCategory::TweetImage,
+        &UploadOptions::default(),
+    )
+    .await?;
+    assert_eq!(ok_json.size, Some(6 * 1024 * 1024));
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_upload_media_from_url() -> Result<(), Box<dyn std::error::Error>> {
+    let server = MockServer::builder().processing_steps(0).start().await?;
+    let secrets = token_secrets();
+
+    //
+    server.serve_asset(
+        "https://cdn.example.com/videos/1.mp4",
+        Some("video/mp4"),
+        b"0123456789".to_vec(),
+    );
+    upload_media_from_url(
+        &secrets,
+        server.client(),
+        "https://cdn.example.com/videos/1.mp4",
+        MediaCategory::TweetVideo,
+        &UploadOptions::default(),
+    )
+    .await?;
+    let requests = server.received_requests_for(Route::MediaUploadInit);
+    assert_eq!(requests[0].param("total_bytes").as_deref(), Some("10"));
+    assert_eq!(
+        requests[0].param("media_type").as_deref(),
+        Some("video/mp4")
+    );
+    server.assert_received(Route::MediaUploadAppend, 1);
+
+    // Neither Content-Length nor Content-Type.
+    server.clear_received_requests();
+    let mut png = b"\x89PNG\r\n\x1A\n".to_vec();
+    png.resize(200 * 1024, 0);
+    server.serve_asset_chunked("https://cdn.example.com/logo", None, png);
+    upload_media_from_url(
+        &secrets,
+        server.client(),
+        "https://cdn.example.com/logo",
+        MediaCategory::TweetImage,
+        &UploadOptions::default(),
+    )
+    .await?;
+    let requests = server.received_requests_for(Route::MediaUploadInit);
+    assert_eq!(requests[0].param("total_bytes").as_deref(), Some("204800"));
+    assert_eq!(
+        requests[0].param("media_type").as_deref(),
+        Some("image/png")
+    );
+
+    //
+    server.clear_received_requests();
+    let too_large = vec![0; twitter_api_v1::media_util::limits::IMAGE_SIZE_MAX as usize + 1];
+    server.serve_asset(
+        "https://cdn.example.com/large.jpg",
+        Some("image/jpeg"),
+        too_large.to_owned(),
+    );
+    server.serve_asset_chunked(
+        "https://cdn.example.com/large-chunked.jpg",
+        Some("image/jpeg"),
+        too_large,
+    );
+    for url in [
+        "https://cdn.example.com/large.jpg",
+        "https://cdn.example.com/large-chunked.jpg",
+    ] {
+        match upload_media_from_url(
+            &secrets,
+            server.client(),
+            url,
+            MediaCategory::TweetImage,
+            &UploadOptions::default().init(UploadInitOptions::default().validate_media(true)),
+        )
+        .await
+        {
+            Err(TwitterError::Validation(msg)) => {
+                assert!(msg.starts_with("media too large"), "{msg}")
+            }
+            x => panic!("{x:?}"),
+        }
+    }
+
+    match upload_media_from_url(
+        &secrets,
+        server.client(),
+        "https://cdn.example.com/missing.jpg",
+        MediaCategory::TweetImage,
+        &UploadOptions::default(),
+    )
+    .await
+    {
+        Err(TwitterError::Transport(_)) => {}
+        x => panic!("{x:?}"),
+    }
+    server.assert_received(Route::MediaUploadInit, 0);
+
+    Ok(())
+}
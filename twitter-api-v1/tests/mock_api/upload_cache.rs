@@ -0,0 +1,122 @@
+use twitter_api_mock::{MockServer, Route};
+use twitter_api_v1::{
+    endpoints::{
+        media::{
+            upload::UploadOptions,
+            upload_cache::{
+                create_tweet_with_media_cache, create_tweet_with_request_and_media_cache,
+                upload_media_from_bytes_cached, MediaCache,
+            },
+        },
+        EndpointRet,
+    },
+    objects::MediaCategory,
+    TokenSecrets,
+};
+use twitter_api_v2::endpoints::tweets::manage_tweets::CreateTweetRequest;
+
+use crate::common::token_secrets;
+
+#[tokio::test]
+async fn test_upload_cache() -> Result<(), Box<dyn std::error::Error>> {
+    let server = MockServer::builder().start().await?;
+    let secrets = token_secrets();
+    let cache = MediaCache::default();
+
+    //
+    let mut media_ids = vec![];
+    for _ in 0..2 {
+        let ret = upload_media_from_bytes_cached(
+            &secrets,
+            server.client(),
+            &cache,
+            b"logo".to_vec(),
+            "image/png",
+            MediaCategory::TweetImage,
+            &UploadOptions::default(),
+        )
+        .await?;
+        media_ids.push((ret.media_id(), ret.is_hit()));
+    }
+    assert_eq!(media_ids[0].0, media_ids[1].0);
+    assert_eq!((media_ids[0].1, media_ids[1].1), (false, true));
+    server.assert_received(Route::MediaUploadInit, 1);
+
+    // Another category is another upload.
+    let ret = upload_media_from_bytes_cached(
+        &secrets,
+        server.client(),
+        &cache,
+        b"logo".to_vec(),
+        "image/png",
+        MediaCategory::DmImage,
+        &UploadOptions::default(),
+    )
+    .await?;
+    assert!(!ret.is_hit());
+    server.assert_received(Route::MediaUploadInit, 2);
+
+    // Another user is another upload.
+    let ret = upload_media_from_bytes_cached(
+        &TokenSecrets::new("consumer_key", "consumer_secret", "2-token", "token_secret"),
+        server.client(),
+        &cache,
+        b"logo".to_vec(),
+        "image/png",
+        MediaCategory::TweetImage,
+        &UploadOptions::default(),
+    )
+    .await?;
+    assert!(!ret.is_hit());
+    server.assert_received(Route::MediaUploadInit, 3);
+
+    //
+    let media_id = media_ids[0].0;
+    match create_tweet_with_media_cache(
+        &secrets,
+        server.client(),
+        &cache,
+        Some("logo"),
+        Some(vec![media_id]),
+        None,
+    )
+    .await?
+    {
+        EndpointRet::Ok(_) => {}
+        x => panic!("{x:?}"),
+    }
+
+    // Treated as expired once inside the safety margin.
+    let cache = cache.safety_margin(std::time::Duration::from_secs(60 * 60 * 24 * 2));
+    match create_tweet_with_media_cache(
+        &secrets,
+        server.client(),
+        &cache,
+        Some("logo"),
+        Some(vec![media_id]),
+        None,
+    )
+    .await
+    {
+        Err(twitter_api_v1::endpoints::EndpointError::ValidateFailed(msg)) => {
+            assert_eq!(msg, format!("media_id {media_id} expired"))
+        }
+        x => panic!("{x:?}"),
+    }
+    match create_tweet_with_request_and_media_cache(
+        &secrets,
+        server.client(),
+        &cache,
+        &CreateTweetRequest::default().media_ids([media_id]),
+    )
+    .await
+    {
+        Err(twitter_api_v1::endpoints::EndpointError::ValidateFailed(msg)) => {
+            assert_eq!(msg, format!("media_id {media_id} expired"))
+        }
+        x => panic!("{x:?}"),
+    }
+    server.assert_received(Route::TweetsCreate, 1);
+
+    Ok(())
+}
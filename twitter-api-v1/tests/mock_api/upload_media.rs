@@ -0,0 +1,147 @@
+use twitter_api_mock::{MockServer, Route};
+use twitter_api_v1::{
+    endpoints::{
+        media::{
+            upload::{upload_media_from_bytes, UploadOptions},
+            upload_media::{
+                upload_image_with_options, upload_init, upload_init_with_options, UploadInitOptions,
+            },
+        },
+        EndpointError, EndpointRet,
+    },
+    objects::MediaCategory,
+    TwitterError, UserAccessToken,
+};
+
+use crate::common::token_secrets;
+
+#[tokio::test]
+async fn test_upload_init_options() -> Result<(), Box<dyn std::error::Error>> {
+    let server = MockServer::builder().processing_steps(0).start().await?;
+    let secrets = token_secrets();
+    let init = UploadInitOptions::default()
+        .additional_owners([783214, 2244994945])
+        .shared(true);
+
+    //
+    upload_media_from_bytes(
+        &secrets,
+        server.client(),
+        b"0123456789".to_vec(),
+        "video/mp4",
+        MediaCategory::DmVideo,
+        &UploadOptions::default().init(init.to_owned()),
+    )
+    .await?;
+    let requests = server.received_requests_for(Route::MediaUploadInit);
+    assert_eq!(
+        requests[0].param("additional_owners").as_deref(),
+        Some("783214,2244994945")
+    );
+    assert_eq!(requests[0].param("shared").as_deref(), Some("true"));
+
+    upload_media_from_bytes(
+        &UserAccessToken::new("user_access_token"),
+        server.client(),
+        b"0123456789".to_vec(),
+        "video/mp4",
+        MediaCategory::DmVideo,
+        &UploadOptions::default().init(init.to_owned()),
+    )
+    .await?;
+    let requests = server.received_requests_for(Route::V2MediaUploadInitialize);
+    let json = requests[0].json().unwrap();
+    assert_eq!(
+        json["additional_owners"],
+        serde_json::json!(["783214", "2244994945"])
+    );
+    assert_eq!(json["shared"], serde_json::json!(true));
+
+    match upload_image_with_options(
+        &secrets,
+        server.client(),
+        MediaCategory::DmImage,
+        b"logo".to_vec(),
+        None,
+        None,
+        &init,
+    )
+    .await?
+    {
+        EndpointRet::Ok(_) => {}
+        x => panic!("{x:?}"),
+    }
+    let requests = server.received_requests_for(Route::MediaUpload);
+    assert_eq!(requests[0].param("shared").as_deref(), Some("true"));
+
+    //
+    server.clear_received_requests();
+    match upload_media_from_bytes(
+        &secrets,
+        server.client(),
+        b"0123456789".to_vec(),
+        "video/mp4",
+        MediaCategory::TweetVideo,
+        &UploadOptions::default().init(UploadInitOptions::default().shared(true)),
+    )
+    .await
+    {
+        Err(TwitterError::Validation(_)) => {}
+        x => panic!("{x:?}"),
+    }
+    match upload_init_with_options(
+        &secrets,
+        server.client(),
+        10,
+        "video/mp4",
+        MediaCategory::TweetVideo,
+        &UploadInitOptions::default().additional_owners(0..101),
+    )
+    .await
+    {
+        Err(twitter_api_v1::endpoints::EndpointError::ValidateFailed(_)) => {}
+        x => panic!("{x:?}"),
+    }
+    assert!(server.received_requests().is_empty());
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_upload_init_validate_media() -> Result<(), Box<dyn std::error::Error>> {
+    let server = MockServer::start().await?;
+    let secrets = token_secrets();
+
+    // Rejected before any request is sent, only when asked to.
+    match upload_init_with_options(
+        &secrets,
+        server.client(),
+        6 * 1024 * 1024,
+        "image/png",
+        MediaCategory::TweetImage,
+        &UploadInitOptions::default().validate_media(true),
+    )
+    .await
+    {
+        Err(EndpointError::ValidateFailed(msg)) => {
+            assert!(msg.starts_with("media too large for tweet_image"))
+        }
+        x => panic!("{x:?}"),
+    }
+    assert!(server.received_requests().is_empty());
+    match upload_init(
+        &secrets,
+        server.client(),
+        6 * 1024 * 1024,
+        "image/png",
+        MediaCategory::TweetImage,
+    )
+    .await?
+    {
+        EndpointRet::Ok(_) => {}
+        x => panic!("{x:?}"),
+    }
+    assert_eq!(server.received_requests().len(), 1);
+
+    Ok(())
+}
@@ -0,0 +1,126 @@
+use std::sync::{Arc, Mutex};
+
+use twitter_api_mock::MockServer;
+use twitter_api_v1::{
+    endpoints::{
+        media::{
+            upload_media::{
+                get_upload_status_with_progress, upload_append_all_from_file_with_options,
+                upload_finalize_with_progress, upload_init,
+            },
+            upload_progress::{ProgressCallback, UploadProgress},
+            upload_session::AppendOptions,
+        },
+        EndpointRet,
+    },
+    objects::{MediaCategory, MediaProcessingInfoState},
+};
+
+use crate::common::{temp_path, token_secrets};
+
+#[tokio::test]
+async fn test_upload_progress() -> Result<(), Box<dyn std::error::Error>> {
+    let server = MockServer::builder()
+        .processing_steps(1)
+        .check_after_secs(0)
+        .start()
+        .await?;
+    let secrets = token_secrets();
+
+    let file_path = temp_path("progress.mp4");
+    let file_size = twitter_api_v1::endpoints::media::upload_media::SEGMENT_SIZE + 10;
+    tokio::fs::write(&file_path, vec![1_u8; file_size]).await?;
+
+    let events = Arc::new(Mutex::new(vec![]));
+    let progress: ProgressCallback = {
+        let events = events.clone();
+        Arc::new(move |event| events.lock().expect("").push(event))
+    };
+
+    //
+    let media_id = match upload_init(
+        &secrets,
+        server.client(),
+        file_size,
+        "video/mp4",
+        MediaCategory::TweetVideo,
+    )
+    .await?
+    {
+        EndpointRet::Ok(ok_json) => ok_json.media_id,
+        x => panic!("{x:?}"),
+    };
+    upload_append_all_from_file_with_options(
+        &secrets,
+        server.client(),
+        media_id,
+        &file_path,
+        &AppendOptions::default().progress(progress.clone()),
+    )
+    .await?
+    .unwrap();
+    upload_finalize_with_progress(&secrets, server.client(), media_id, &progress).await?;
+    get_upload_status_with_progress(&secrets, server.client(), media_id, &progress).await?;
+
+    //
+    let events = events.lock().expect("").to_owned();
+
+    let bytes_sent = events
+        .iter()
+        .filter_map(|x| match x {
+            UploadProgress::SegmentBytesSent {
+                segment_index: 1,
+                bytes_sent,
+                segment_bytes,
+            } => Some((*bytes_sent, *segment_bytes)),
+            _ => None,
+        })
+        .collect::<Vec<_>>();
+    assert_eq!(bytes_sent.last(), Some(&(10, 10)));
+
+    let completed = events
+        .iter()
+        .filter_map(|x| match x {
+            UploadProgress::SegmentCompleted {
+                completed_segments,
+                total_segments,
+                bytes_completed,
+                total_bytes,
+                ..
+            } => Some((
+                *completed_segments,
+                *total_segments,
+                *bytes_completed,
+                *total_bytes,
+            )),
+            _ => None,
+        })
+        .collect::<Vec<_>>();
+    assert_eq!(
+        completed,
+        vec![
+            (1, 2, file_size as u64 - 10, file_size as u64),
+            (2, 2, file_size as u64, file_size as u64)
+        ]
+    );
+
+    match &events[events.len() - 2..] {
+        [UploadProgress::Finalized {
+            processing_info: Some(processing_info),
+            ..
+        }, UploadProgress::Processing {
+            state,
+            progress_percent,
+            ..
+        }] => {
+            assert_eq!(processing_info.state, MediaProcessingInfoState::Pending);
+            assert_eq!(*state, MediaProcessingInfoState::Succeeded);
+            assert_eq!(*progress_percent, Some(100));
+        }
+        x => panic!("{x:?}"),
+    }
+
+    tokio::fs::remove_file(&file_path).await?;
+
+    Ok(())
+}
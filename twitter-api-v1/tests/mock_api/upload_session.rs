@@ -0,0 +1,289 @@
+use twitter_api_mock::{Failure, MockServer, Route};
+use twitter_api_v1::{
+    endpoints::{
+        media::{
+            upload_media::{
+                upload_append, upload_append_all_from_file_with_options, upload_finalize,
+                upload_init,
+            },
+            upload_session::{
+                upload_append_all_from_file_resumable, upload_append_all_from_reader_resumable,
+                upload_session_init_from_file, AppendOptions, UploadSession,
+                DEFAULT_EXPIRES_AFTER_SECS,
+            },
+        },
+        EndpointRet,
+    },
+    objects::MediaCategory,
+};
+
+use crate::common::{temp_path, token_secrets};
+
+#[tokio::test]
+async fn test_resumable_upload_from_reader() -> Result<(), Box<dyn std::error::Error>> {
+    let server = MockServer::builder().processing_steps(0).start().await?;
+    let secrets = token_secrets();
+    let bytes = b"0123456789";
+
+    let media_id = match upload_init(
+        &secrets,
+        server.client(),
+        bytes.len(),
+        "video/mp4",
+        MediaCategory::TweetVideo,
+    )
+    .await?
+    {
+        EndpointRet::Ok(ok_json) => ok_json.media_id,
+        x => panic!("{x:?}"),
+    };
+    let mut session =
+        UploadSession::new(media_id, bytes.len() as u64, "", DEFAULT_EXPIRES_AFTER_SECS)
+            .with_segment_size(4);
+
+    // Segment 0 went through before the upload was interrupted.
+    match upload_append(
+        &secrets,
+        server.client(),
+        media_id,
+        0,
+        bytes[session.segment_range(0)].to_vec(),
+        Some(4),
+    )
+    .await?
+    {
+        EndpointRet::Ok(_) => session.mark_completed(0),
+        x => panic!("{x:?}"),
+    }
+
+    // Resumed with a reader from the first byte, segment 0's bytes are skipped.
+    server.clear_received_requests();
+    let mut reader = &bytes[..];
+    upload_append_all_from_reader_resumable(
+        &secrets,
+        server.client(),
+        &mut session,
+        &mut reader,
+        &AppendOptions::default(),
+    )
+    .await?
+    .unwrap();
+    assert!(session.is_complete());
+
+    let requests = server.received_requests_for(Route::MediaUploadAppend);
+    assert_eq!(requests.len(), 2);
+    for (request, segment_index) in requests.iter().zip([1, 2]) {
+        assert_eq!(
+            request.param("segment_index").as_deref(),
+            Some(segment_index.to_string().as_str())
+        );
+        assert_eq!(
+            request.multipart_part("media").as_deref(),
+            Some(&bytes[session.segment_range(segment_index)])
+        );
+    }
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_resumable_upload() -> Result<(), Box<dyn std::error::Error>> {
+    let server = MockServer::builder().processing_steps(0).start().await?;
+    let secrets = token_secrets();
+
+    let dir = temp_path("resumable");
+    tokio::fs::create_dir_all(&dir).await?;
+    let file_path = dir.join("video.mp4");
+    let session_path = dir.join("video.mp4.session");
+    tokio::fs::write(&file_path, b"0123456789").await?;
+
+    //
+    let session = match upload_session_init_from_file(
+        &secrets,
+        server.client(),
+        &file_path,
+        "video/mp4",
+        MediaCategory::TweetVideo,
+    )
+    .await?
+    {
+        EndpointRet::Ok(session) => session.with_segment_size(4),
+        x => panic!("{x:?}"),
+    };
+    assert_eq!(session.segment_count(), 3);
+
+    // Segment 0 goes through, then segment 1 fails.
+    let mut session = session;
+    match upload_append(
+        &secrets,
+        server.client(),
+        session.media_id,
+        0,
+        b"0123".to_vec(),
+        Some(4),
+    )
+    .await?
+    {
+        EndpointRet::Ok(_) => session.mark_completed(0),
+        x => panic!("{x:?}"),
+    }
+    session.save(&session_path).await?;
+
+    server.fail_next(Route::MediaUploadAppend, Failure::ServerError(503));
+    match upload_append_all_from_file_resumable(
+        &secrets,
+        server.client(),
+        &mut session,
+        &file_path,
+        Some(&session_path),
+        &AppendOptions::default(),
+    )
+    .await?
+    {
+        Err(EndpointRet::Other((status, _))) => assert_eq!(status, 503),
+        x => panic!("{x:?}"),
+    }
+
+    // Resume from the persisted session, only the pending segments are sent.
+    let mut session = UploadSession::load(&session_path).await?;
+    assert_eq!(session.pending_segments(), vec![1, 2]);
+    server.clear_received_requests();
+    upload_append_all_from_file_resumable(
+        &secrets,
+        server.client(),
+        &mut session,
+        &file_path,
+        Some(&session_path),
+        &AppendOptions::default(),
+    )
+    .await?
+    .unwrap();
+    assert!(session.is_complete());
+    let requests = server.received_requests_for(Route::MediaUploadAppend);
+    assert_eq!(
+        requests
+            .iter()
+            .map(|x| x.query_param("segment_index").unwrap())
+            .collect::<Vec<_>>(),
+        vec!["1", "2"]
+    );
+    assert_eq!(requests[1].multipart_part("media"), Some(b"89".to_vec()));
+
+    match upload_finalize(&secrets, server.client(), session.media_id).await? {
+        EndpointRet::Ok(ok_json) => assert!(ok_json.processing_info.is_none()),
+        x => panic!("{x:?}"),
+    }
+
+    //
+    tokio::fs::write(&file_path, b"0123456780").await?;
+    match upload_append_all_from_file_resumable(
+        &secrets,
+        server.client(),
+        &mut session,
+        &file_path,
+        None,
+        &AppendOptions::default(),
+    )
+    .await
+    {
+        Err(twitter_api_v1::endpoints::EndpointError::ValidateFailed(msg)) => {
+            assert_eq!(msg, "file does not match upload session")
+        }
+        x => panic!("{x:?}"),
+    }
+
+    session.expires_at = 0;
+    match upload_append_all_from_file_resumable(
+        &secrets,
+        server.client(),
+        &mut session,
+        &file_path,
+        None,
+        &AppendOptions::default(),
+    )
+    .await
+    {
+        Err(twitter_api_v1::endpoints::EndpointError::ValidateFailed(msg)) => {
+            assert_eq!(msg, "upload session expired")
+        }
+        x => panic!("{x:?}"),
+    }
+
+    tokio::fs::remove_dir_all(&dir).await?;
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_concurrent_append() -> Result<(), Box<dyn std::error::Error>> {
+    let server = MockServer::builder().processing_steps(0).start().await?;
+    let secrets = token_secrets();
+
+    let file_path = temp_path("concurrent.mp4");
+    let file_size = twitter_api_v1::endpoints::media::upload_media::SEGMENT_SIZE * 3 + 1;
+    tokio::fs::write(&file_path, vec![1_u8; file_size]).await?;
+
+    let media_id = match upload_init(
+        &secrets,
+        server.client(),
+        file_size,
+        "video/mp4",
+        MediaCategory::TweetVideo,
+    )
+    .await?
+    {
+        EndpointRet::Ok(ok_json) => ok_json.media_id,
+        x => panic!("{x:?}"),
+    };
+
+    server.fail_times(Route::MediaUploadAppend, Failure::ServerError(503), 2);
+    let options = AppendOptions::default()
+        .concurrency(3)
+        .max_retries(2)
+        .retry_delay(std::time::Duration::from_millis(10));
+    upload_append_all_from_file_with_options(
+        &secrets,
+        server.client(),
+        media_id,
+        &file_path,
+        &options,
+    )
+    .await?
+    .unwrap();
+
+    let requests = server.received_requests_for(Route::MediaUploadAppend);
+    assert_eq!(requests.len(), 6);
+    let mut segment_indexes = requests
+        .iter()
+        .map(|x| x.query_param("segment_index").unwrap().parse::<usize>())
+        .collect::<Result<Vec<_>, _>>()?;
+    segment_indexes.sort_unstable();
+    segment_indexes.dedup();
+    assert_eq!(segment_indexes, vec![0, 1, 2, 3]);
+
+    match upload_finalize(&secrets, server.client(), media_id).await? {
+        EndpointRet::Ok(ok_json) => assert_eq!(ok_json.media_id, media_id),
+        x => panic!("{x:?}"),
+    }
+
+    // Retries exhausted, the failure is returned.
+    server.fail_times(Route::MediaUploadAppend, Failure::ServerError(500), 3);
+    match upload_append_all_from_file_with_options(
+        &secrets,
+        server.client(),
+        media_id,
+        &file_path,
+        &AppendOptions::default()
+            .max_retries(2)
+            .retry_delay(Default::default()),
+    )
+    .await?
+    {
+        Err(EndpointRet::Other((status, _))) => assert_eq!(status, 500),
+        x => panic!("{x:?}"),
+    }
+
+    tokio::fs::remove_file(&file_path).await?;
+
+    Ok(())
+}
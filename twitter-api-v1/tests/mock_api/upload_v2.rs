@@ -0,0 +1,69 @@
+use twitter_api_mock::{MockServer, Route};
+use twitter_api_v1::{
+    endpoints::{
+        media::{
+            upload::{upload_media_from_bytes, UploadOptions},
+            upload_v2::upload_finalize_v2,
+        },
+        EndpointRet,
+    },
+    objects::{MediaCategory, MediaProcessingInfoState},
+    UserAccessToken,
+};
+
+#[tokio::test]
+async fn test_upload_media_v2() -> Result<(), Box<dyn std::error::Error>> {
+    let server = MockServer::builder()
+        .processing_steps(2)
+        .check_after_secs(0)
+        .start()
+        .await?;
+    let token = UserAccessToken::new("user_access_token");
+    let segment_size = twitter_api_v1::endpoints::media::upload_media::SEGMENT_SIZE;
+
+    //
+    let ok_json = upload_media_from_bytes(
+        &token,
+        server.client(),
+        vec![0; segment_size + 1],
+        "video/mp4",
+        MediaCategory::TweetVideo,
+        &UploadOptions::default().alt_text("A dancing cat"),
+    )
+    .await?;
+    assert_eq!(
+        ok_json.processing_info.unwrap().state,
+        MediaProcessingInfoState::Succeeded
+    );
+    assert_eq!(ok_json.media_key, format!("7_{}", ok_json.media_id));
+
+    server.assert_received(Route::V2MediaUploadInitialize, 1);
+    server.assert_received(Route::V2MediaUploadAppend, 2);
+    server.assert_received(Route::V2MediaUploadFinalize, 1);
+    server.assert_received(Route::V2MediaUploadStatus, 2);
+    server.assert_received(Route::MediaUploadInit, 0);
+    for request in server.received_requests() {
+        assert_eq!(
+            request.authorization(),
+            Some("Bearer user_access_token"),
+            "{}",
+            request.path
+        );
+    }
+    let requests = server.received_requests_for(Route::V2MediaMetadataCreate);
+    assert_eq!(
+        requests[0].json().unwrap(),
+        serde_json::json!({
+            "id": ok_json.media_id.to_string(),
+            "metadata": { "alt_text": { "text": "A dancing cat" } }
+        })
+    );
+
+    //
+    match upload_finalize_v2(&token, server.client(), 1).await? {
+        EndpointRet::Other((status, _)) => assert_eq!(status, 400),
+        x => panic!("{x:?}"),
+    }
+
+    Ok(())
+}
@@ -0,0 +1,78 @@
+use twitter_api_mock::{MockServer, Route};
+use twitter_api_v1::endpoints::{
+    tweets::lookup::lookup_tweets_with_fields,
+    users::lookup::{show_user_by_id, show_user_by_screen_name_with_fields},
+    EndpointRet,
+};
+use twitter_api_v2::endpoints::{
+    fields::{Expansion, MediaField, TweetField, UserField},
+    FieldsQuery,
+};
+
+use crate::common::token_secrets;
+
+#[tokio::test]
+async fn test_fields_query() -> Result<(), Box<dyn std::error::Error>> {
+    let server = MockServer::start().await?;
+    let secrets = token_secrets();
+
+    match show_user_by_id(&secrets, server.client(), 783214, Some(true)).await? {
+        EndpointRet::Ok(user) => assert_eq!(user.id, 783214),
+        x => panic!("{x:?}"),
+    }
+    let requests = server.received_requests_for(Route::UsersLookupById);
+    assert_eq!(
+        requests[0].query_param("user.fields"),
+        Some("id,name,username,profile_image_url")
+    );
+    assert_eq!(
+        requests[0].query_param("expansions"),
+        Some("pinned_tweet_id")
+    );
+
+    let fields = FieldsQuery::new()
+        .user_fields([UserField::CreatedAt, UserField::PublicMetrics])
+        .expansions([Expansion::PinnedTweetId])
+        .tweet_fields([TweetField::CreatedAt]);
+    match show_user_by_screen_name_with_fields(&secrets, server.client(), "TwitterDev", &fields)
+        .await?
+    {
+        EndpointRet::Ok(ret) => {
+            assert_eq!(ret.data.username.as_deref(), Some("TwitterDev"));
+            assert_eq!(ret.includes.tweets[0].id, ret.data.pinned_tweet_id);
+        }
+        x => panic!("{x:?}"),
+    }
+    let requests = server.received_requests_for(Route::UsersLookupByUsername);
+    assert_eq!(
+        requests[0].query_param("user.fields"),
+        Some("created_at,public_metrics")
+    );
+    assert_eq!(
+        requests[0].query_param("expansions"),
+        Some("pinned_tweet_id")
+    );
+    assert_eq!(requests[0].query_param("tweet.fields"), Some("created_at"));
+    assert_eq!(requests[0].query_param("media.fields"), None);
+
+    //
+    let fields = FieldsQuery::new()
+        .expansions([Expansion::AttachmentsMediaKeys])
+        .media_fields([MediaField::Type, MediaField::Variants]);
+    match lookup_tweets_with_fields(&secrets, server.client(), &[1], &fields).await? {
+        EndpointRet::Ok(lookup) => assert_eq!(lookup.not_found_ids, vec![1]),
+        x => panic!("{x:?}"),
+    }
+    let requests = server.received_requests_for(Route::TweetsLookup);
+    assert_eq!(
+        requests[0].query_param("expansions"),
+        Some("attachments.media_keys")
+    );
+    assert_eq!(
+        requests[0].query_param("media.fields"),
+        Some("type,variants")
+    );
+    assert_eq!(requests[0].query_param("tweet.fields"), None);
+
+    Ok(())
+}
@@ -0,0 +1,75 @@
+use serde::{Deserialize, Serialize};
+use serde_aux::field_attributes::deserialize_option_number_from_string;
+use serde_json::{Map, Value};
+
+use crate::objects::{Media, PartialError, Tweet, User};
+
+//
+#[derive(Deserialize, Serialize, Debug, Clone)]
+pub struct ResponseBody<T> {
+    pub data: Option<T>,
+    #[serde(default)]
+    pub errors: Vec<PartialError>,
+    pub includes: Option<ResponseBodyIncludes>,
+    pub meta: Option<ResponseBodyMeta>,
+}
+
+impl<T> ResponseBody<T> {
+    pub fn partial_errors(&self) -> &[PartialError] {
+        &self.errors
+    }
+
+    pub fn is_partial(&self) -> bool {
+        self.data.is_some() && !self.errors.is_empty()
+    }
+
+    pub fn into_parts(self) -> (Option<T>, Vec<PartialError>) {
+        (self.data, self.errors)
+    }
+}
+
+impl<T> ResponseBody<Vec<T>> {
+    pub fn successes(&self) -> &[T] {
+        self.data.as_deref().unwrap_or_default()
+    }
+}
+
+//
+#[derive(Deserialize, Serialize, Debug, Clone, Default)]
+pub struct ResponseBodyIncludes {
+    #[serde(default)]
+    pub users: Vec<User>,
+    #[serde(default)]
+    pub tweets: Vec<Tweet>,
+    #[serde(default)]
+    pub media: Vec<Media>,
+    #[serde(default)]
+    pub places: Vec<Map<String, Value>>,
+    #[serde(default)]
+    pub polls: Vec<Map<String, Value>>,
+}
+
+impl ResponseBodyIncludes {
+    // Used when merging the responses of a request split into several.
+    pub fn extend(&mut self, other: Self) {
+        self.users.extend(other.users);
+        self.tweets.extend(other.tweets);
+        self.media.extend(other.media);
+        self.places.extend(other.places);
+        self.polls.extend(other.polls);
+    }
+}
+
+//
+#[derive(Deserialize, Serialize, Debug, Clone, Default)]
+pub struct ResponseBodyMeta {
+    pub result_count: Option<usize>,
+    pub next_token: Option<String>,
+    pub previous_token: Option<String>,
+    #[serde(default, deserialize_with = "deserialize_option_number_from_string")]
+    pub newest_id: Option<u64>,
+    #[serde(default, deserialize_with = "deserialize_option_number_from_string")]
+    pub oldest_id: Option<u64>,
+    #[serde(flatten)]
+    pub _others: Map<String, Value>,
+}
@@ -0,0 +1,255 @@
+// https://developer.twitter.com/en/docs/twitter-api/fields
+// https://developer.twitter.com/en/docs/twitter-api/expansions
+
+use serde_enum_str::{Deserialize_enum_str, Serialize_enum_str};
+
+//
+#[derive(Deserialize_enum_str, Serialize_enum_str, Debug, Clone, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum TweetField {
+    Attachments,
+    AuthorId,
+    ContextAnnotations,
+    ConversationId,
+    CreatedAt,
+    EditControls,
+    EditHistoryTweetIds,
+    Entities,
+    Geo,
+    Id,
+    InReplyToUserId,
+    Lang,
+    NonPublicMetrics,
+    NoteTweet,
+    OrganicMetrics,
+    PossiblySensitive,
+    PromotedMetrics,
+    PublicMetrics,
+    ReferencedTweets,
+    ReplySettings,
+    Source,
+    Text,
+    Withheld,
+    #[serde(other)]
+    Other(String),
+}
+
+#[derive(Deserialize_enum_str, Serialize_enum_str, Debug, Clone, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum UserField {
+    CreatedAt,
+    Description,
+    Entities,
+    Id,
+    Location,
+    MostRecentTweetId,
+    Name,
+    PinnedTweetId,
+    ProfileImageUrl,
+    Protected,
+    PublicMetrics,
+    Url,
+    Username,
+    Verified,
+    VerifiedType,
+    Withheld,
+    #[serde(other)]
+    Other(String),
+}
+
+#[derive(Deserialize_enum_str, Serialize_enum_str, Debug, Clone, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum MediaField {
+    AltText,
+    DurationMs,
+    Height,
+    MediaKey,
+    NonPublicMetrics,
+    OrganicMetrics,
+    PreviewImageUrl,
+    PromotedMetrics,
+    PublicMetrics,
+    Type,
+    Url,
+    Variants,
+    Width,
+    #[serde(other)]
+    Other(String),
+}
+
+#[derive(Deserialize_enum_str, Serialize_enum_str, Debug, Clone, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum PollField {
+    DurationMinutes,
+    EndDatetime,
+    Id,
+    Options,
+    VotingStatus,
+    #[serde(other)]
+    Other(String),
+}
+
+#[derive(Deserialize_enum_str, Serialize_enum_str, Debug, Clone, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum PlaceField {
+    ContainedWithin,
+    Country,
+    CountryCode,
+    FullName,
+    Geo,
+    Id,
+    Name,
+    PlaceType,
+    #[serde(other)]
+    Other(String),
+}
+
+// Expanded objects are returned in `includes`.
+#[derive(Deserialize_enum_str, Serialize_enum_str, Debug, Clone, PartialEq, Eq)]
+pub enum Expansion {
+    #[serde(rename = "attachments.media_keys")]
+    AttachmentsMediaKeys,
+    #[serde(rename = "attachments.poll_ids")]
+    AttachmentsPollIds,
+    #[serde(rename = "author_id")]
+    AuthorId,
+    #[serde(rename = "edit_history_tweet_ids")]
+    EditHistoryTweetIds,
+    #[serde(rename = "entities.mentions.username")]
+    EntitiesMentionsUsername,
+    #[serde(rename = "geo.place_id")]
+    GeoPlaceId,
+    #[serde(rename = "in_reply_to_user_id")]
+    InReplyToUserId,
+    #[serde(rename = "referenced_tweets.id")]
+    ReferencedTweetsId,
+    #[serde(rename = "referenced_tweets.id.author_id")]
+    ReferencedTweetsIdAuthorId,
+    // Users lookup only.
+    #[serde(rename = "pinned_tweet_id")]
+    PinnedTweetId,
+    #[serde(other)]
+    Other(String),
+}
+
+//
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct FieldsQuery {
+    pub expansions: Vec<Expansion>,
+    pub tweet_fields: Vec<TweetField>,
+    pub user_fields: Vec<UserField>,
+    pub media_fields: Vec<MediaField>,
+    pub poll_fields: Vec<PollField>,
+    pub place_fields: Vec<PlaceField>,
+}
+
+impl FieldsQuery {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn expansions(mut self, expansions: impl IntoIterator<Item = Expansion>) -> Self {
+        self.expansions.extend(expansions);
+        self
+    }
+
+    pub fn tweet_fields(mut self, tweet_fields: impl IntoIterator<Item = TweetField>) -> Self {
+        self.tweet_fields.extend(tweet_fields);
+        self
+    }
+
+    pub fn user_fields(mut self, user_fields: impl IntoIterator<Item = UserField>) -> Self {
+        self.user_fields.extend(user_fields);
+        self
+    }
+
+    pub fn media_fields(mut self, media_fields: impl IntoIterator<Item = MediaField>) -> Self {
+        self.media_fields.extend(media_fields);
+        self
+    }
+
+    pub fn poll_fields(mut self, poll_fields: impl IntoIterator<Item = PollField>) -> Self {
+        self.poll_fields.extend(poll_fields);
+        self
+    }
+
+    pub fn place_fields(mut self, place_fields: impl IntoIterator<Item = PlaceField>) -> Self {
+        self.place_fields.extend(place_fields);
+        self
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.query_pairs().is_empty()
+    }
+
+    // Empty selections are left out so the endpoint defaults apply, duplicates are dropped.
+    pub fn query_pairs(&self) -> Vec<(&'static str, String)> {
+        [
+            ("expansions", join(&self.expansions)),
+            ("tweet.fields", join(&self.tweet_fields)),
+            ("user.fields", join(&self.user_fields)),
+            ("media.fields", join(&self.media_fields)),
+            ("poll.fields", join(&self.poll_fields)),
+            ("place.fields", join(&self.place_fields)),
+        ]
+        .into_iter()
+        .filter(|(_, value)| !value.is_empty())
+        .collect()
+    }
+}
+
+fn join(items: &[impl ToString]) -> String {
+    let mut values = Vec::<String>::with_capacity(items.len());
+    for value in items.iter().map(|x| x.to_string()) {
+        if !value.is_empty() && !values.contains(&value) {
+            values.push(value);
+        }
+    }
+    values.join(",")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_query_pairs() {
+        assert!(FieldsQuery::new().is_empty());
+
+        let query = FieldsQuery::new()
+            .expansions([
+                Expansion::AuthorId,
+                Expansion::AttachmentsMediaKeys,
+                Expansion::ReferencedTweetsIdAuthorId,
+            ])
+            .tweet_fields([
+                TweetField::CreatedAt,
+                TweetField::EditHistoryTweetIds,
+                TweetField::CreatedAt,
+            ])
+            .user_fields([UserField::ProfileImageUrl])
+            .media_fields([MediaField::Type, MediaField::Variants])
+            .place_fields([PlaceField::Other("".into())]);
+        assert_eq!(
+            query.query_pairs(),
+            vec![
+                (
+                    "expansions",
+                    "author_id,attachments.media_keys,referenced_tweets.id.author_id".to_owned()
+                ),
+                (
+                    "tweet.fields",
+                    "created_at,edit_history_tweet_ids".to_owned()
+                ),
+                ("user.fields", "profile_image_url".to_owned()),
+                ("media.fields", "type,variants".to_owned()),
+            ]
+        );
+
+        assert_eq!("end_datetime".parse(), Ok(PollField::EndDatetime));
+        assert_eq!(
+            "geo.place_id".parse::<Expansion>(),
+            Ok(Expansion::GeoPlaceId)
+        );
+    }
+}
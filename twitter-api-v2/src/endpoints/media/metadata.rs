@@ -0,0 +1,24 @@
+// https://developer.x.com/en/docs/x-api/media/metadata/introduction
+
+use serde::{Deserialize, Serialize};
+
+//
+pub const URL_FOR_MEDIA_METADATA_CREATE: &str = "https://api.twitter.com/2/media/metadata";
+
+//
+#[derive(Deserialize, Serialize, Debug, Clone)]
+pub struct MediaMetadataCreateRequestBody {
+    pub id: String,
+    pub metadata: MediaMetadata,
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone, Default)]
+pub struct MediaMetadata {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub alt_text: Option<MediaMetadataAltText>,
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone)]
+pub struct MediaMetadataAltText {
+    pub text: String,
+}
@@ -0,0 +1,96 @@
+// https://developer.x.com/en/docs/x-api/media/upload-media/introduction
+
+use serde::{Deserialize, Serialize};
+use serde_aux::field_attributes::deserialize_number_from_string;
+use serde_json::{Map, Value};
+
+use crate::endpoints::common::ResponseBody;
+
+//
+pub const URL_FOR_MEDIA_UPLOAD_INITIALIZE: &str =
+    "https://api.twitter.com/2/media/upload/initialize";
+
+pub fn url_for_media_upload_append(media_id: u64) -> String {
+    format!("https://api.twitter.com/2/media/upload/{media_id}/append")
+}
+
+pub fn url_for_media_upload_finalize(media_id: u64) -> String {
+    format!("https://api.twitter.com/2/media/upload/{media_id}/finalize")
+}
+
+// GET, with query command=STATUS&media_id=
+pub const URL_FOR_MEDIA_UPLOAD_STATUS: &str = "https://api.twitter.com/2/media/upload";
+
+//
+#[derive(Deserialize, Serialize, Debug, Clone)]
+pub struct MediaUploadInitializeRequestBody {
+    pub media_type: String,
+    pub total_bytes: usize,
+    pub media_category: String,
+    // User ids, as strings.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub additional_owners: Option<Vec<String>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub shared: Option<bool>,
+}
+
+//
+pub type MediaUploadResponseBody = ResponseBody<MediaUploadResponseBodyData>;
+
+#[derive(Deserialize, Serialize, Debug, Clone)]
+pub struct MediaUploadResponseBodyData {
+    #[serde(deserialize_with = "deserialize_number_from_string")]
+    pub id: u64,
+    pub media_key: Option<String>,
+    pub size: Option<usize>,
+    pub expires_after_secs: Option<usize>,
+    // Same shape as the v1.1 processing_info.
+    pub processing_info: Option<Map<String, Value>>,
+    #[serde(flatten)]
+    pub _others: Map<String, Value>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_de_response_body() {
+        match serde_json::from_str::<MediaUploadResponseBody>(include_str!(
+            "../../../tests/response_body_json_files/media__upload__finalize.json"
+        )) {
+            Ok(body) => {
+                let data = body.data.unwrap();
+                assert_eq!(data.id, 1880028106020515840);
+                assert_eq!(
+                    data.processing_info.unwrap()["state"],
+                    Value::from("pending")
+                );
+            }
+            Err(err) => panic!("{err}"),
+        }
+
+        assert_eq!(
+            serde_json::to_string(&MediaUploadInitializeRequestBody {
+                media_type: "video/mp4".into(),
+                total_bytes: 10,
+                media_category: "tweet_video".into(),
+                additional_owners: None,
+                shared: None,
+            })
+            .unwrap(),
+            r#"{"media_type":"video/mp4","total_bytes":10,"media_category":"tweet_video"}"#
+        );
+        assert_eq!(
+            serde_json::to_string(&MediaUploadInitializeRequestBody {
+                media_type: "video/mp4".into(),
+                total_bytes: 10,
+                media_category: "dm_video".into(),
+                additional_owners: Some(vec!["1".into()]),
+                shared: Some(true),
+            })
+            .unwrap(),
+            r#"{"media_type":"video/mp4","total_bytes":10,"media_category":"dm_video","additional_owners":["1"],"shared":true}"#
+        );
+    }
+}
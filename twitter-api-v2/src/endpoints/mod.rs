@@ -1,3 +1,11 @@
 //
+pub mod media;
 pub mod tweets;
 pub mod users;
+
+//
+pub mod common;
+pub use common::ResponseBody;
+
+pub mod fields;
+pub use fields::FieldsQuery;
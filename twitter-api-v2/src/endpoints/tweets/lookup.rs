@@ -0,0 +1,62 @@
+// https://developer.twitter.com/en/docs/twitter-api/tweets/lookup/introduction
+
+use crate::{endpoints::common::ResponseBody, objects::Tweet};
+
+//
+pub const URL_FOR_TWEETS_LOOKUP: &str = "https://api.twitter.com/2/tweets";
+
+pub const TWEETS_LOOKUP_IDS_MAX: usize = 100;
+
+pub fn url_for_tweet_by_id(id: u64) -> String {
+    format!("https://api.twitter.com/2/tweets/{id}")
+}
+
+//
+pub type SingleTweetResponseBody = ResponseBody<Tweet>;
+
+pub type MultipleTweetsResponseBody = ResponseBody<Vec<Tweet>>;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_de_response_body() {
+        match serde_json::from_str::<SingleTweetResponseBody>(include_str!(
+            "../../../tests/response_body_json_files/tweets__lookup__single__default_fields.json"
+        )) {
+            Ok(body) => {
+                let tweet = body.data.unwrap();
+                assert_eq!(tweet.id, Some(1460323737035677698));
+                assert_eq!(tweet.edit_history_tweet_ids, vec![1460323737035677698]);
+            }
+            Err(err) => panic!("{err}"),
+        }
+
+        //
+        match serde_json::from_str::<MultipleTweetsResponseBody>(include_str!(
+            "../../../tests/response_body_json_files/tweets__lookup__multiple__partial_errors.json"
+        )) {
+            Ok(body) => {
+                assert!(body.is_partial());
+                assert_eq!(body.successes().len(), 2);
+                assert_eq!(body.successes()[1].author_id, Some(783214));
+                assert!(body.partial_errors()[0].is_resource_not_found());
+                assert_eq!(body.partial_errors()[0].resource_id.as_deref(), Some("1"));
+                assert_eq!(body.includes.unwrap().users.len(), 2);
+            }
+            Err(err) => panic!("{err}"),
+        }
+
+        match serde_json::from_str::<MultipleTweetsResponseBody>(include_str!(
+            "../../../tests/response_body_json_files/tweets__lookup__multiple__all_not_found.json"
+        )) {
+            Ok(body) => {
+                assert!(body.data.is_none());
+                assert!(body.successes().is_empty());
+                assert!(body.partial_errors()[0].is_resource_not_found());
+            }
+            Err(err) => panic!("{err}"),
+        }
+    }
+}
@@ -3,21 +3,400 @@
 use serde::{Deserialize, Serialize};
 use serde_aux::field_attributes::deserialize_number_from_string;
 
-//
-// TODO,
+use crate::{
+    endpoints::common::ResponseBody,
+    objects::{tweet::deserialize_vec_number_from_string, MediaType},
+};
+
+pub use crate::objects::ReplySettings;
 
 //
 pub const URL_FOR_TWEETS_CREATE: &str = "https://api.twitter.com/2/tweets";
 
+pub fn url_for_tweets_delete(tweet_id: u64) -> String {
+    format!("https://api.twitter.com/2/tweets/{tweet_id}")
+}
+
+pub const TWEETS_CREATE_MEDIA_IDS_MAX: usize = 4;
+pub const TWEETS_CREATE_TAGGED_USER_IDS_MAX: usize = 10;
+pub const TWEETS_CREATE_POLL_OPTIONS_RANGE: core::ops::RangeInclusive<usize> = 2..=4;
+pub const TWEETS_CREATE_POLL_OPTION_LEN_MAX: usize = 25;
+pub const TWEETS_CREATE_POLL_DURATION_MINUTES_RANGE: core::ops::RangeInclusive<u32> = 5..=10080;
+
 //
+#[derive(Deserialize, Serialize, Debug, Clone, Default)]
+pub struct TweetsCreateRequestBody {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub text: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub media: Option<TweetsCreateRequestBodyMedia>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub poll: Option<TweetsCreateRequestBodyPoll>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub reply: Option<TweetsCreateRequestBodyReply>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub quote_tweet_id: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub reply_settings: Option<ReplySettings>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub geo: Option<TweetsCreateRequestBodyGeo>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub for_super_followers_only: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub direct_message_deep_link: Option<String>,
+    // Known from media keys, one per media id (None when unknown), only used by validate.
+    #[serde(skip)]
+    pub(crate) media_types: Vec<Option<MediaType>>,
+}
+
+pub type CreateTweetRequest = TweetsCreateRequestBody;
+
+#[derive(Deserialize, Serialize, Debug, Clone, Default)]
+pub struct TweetsCreateRequestBodyMedia {
+    pub media_ids: Vec<String>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub tagged_user_ids: Vec<String>,
+}
+
 #[derive(Deserialize, Serialize, Debug, Clone)]
-pub struct TweetsCreateResponseBody {
-    pub data: TweetsCreateResponseBodyData,
+pub struct TweetsCreateRequestBodyPoll {
+    pub options: Vec<String>,
+    pub duration_minutes: u32,
 }
 
+#[derive(Deserialize, Serialize, Debug, Clone)]
+pub struct TweetsCreateRequestBodyReply {
+    pub in_reply_to_tweet_id: String,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub exclude_reply_user_ids: Vec<String>,
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone)]
+pub struct TweetsCreateRequestBodyGeo {
+    pub place_id: String,
+}
+
+impl TweetsCreateRequestBody {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn text(mut self, text: impl Into<String>) -> Self {
+        self.text = Some(text.into());
+        self
+    }
+
+    pub fn media_ids(mut self, media_ids: impl IntoIterator<Item = u64>) -> Self {
+        self.media.get_or_insert_with(Default::default).media_ids =
+            media_ids.into_iter().map(|x| x.to_string()).collect();
+        self.media_types.clear();
+        self
+    }
+
+    // Like media_ids, the media types are kept so the mixing rules can be checked.
+    pub fn media_keys<'a>(mut self, media_keys: impl IntoIterator<Item = &'a str>) -> Self {
+        let (media_ids, media_types): (Vec<_>, Vec<_>) = media_keys
+            .into_iter()
+            .map(|x| {
+                let media_id = x.split_once('_').map(|(_, id)| id).unwrap_or(x);
+                (media_id.to_owned(), MediaType::from_media_key(x))
+            })
+            .unzip();
+        self.media.get_or_insert_with(Default::default).media_ids = media_ids;
+        self.media_types = media_types;
+        self
+    }
+
+    pub fn tagged_user_ids(mut self, tagged_user_ids: impl IntoIterator<Item = u64>) -> Self {
+        self.media
+            .get_or_insert_with(Default::default)
+            .tagged_user_ids = tagged_user_ids.into_iter().map(|x| x.to_string()).collect();
+        self
+    }
+
+    pub fn poll(
+        mut self,
+        options: impl IntoIterator<Item = impl Into<String>>,
+        duration_minutes: u32,
+    ) -> Self {
+        self.poll = Some(TweetsCreateRequestBodyPoll {
+            options: options.into_iter().map(Into::into).collect(),
+            duration_minutes,
+        });
+        self
+    }
+
+    pub fn in_reply_to_tweet_id(mut self, tweet_id: u64) -> Self {
+        match &mut self.reply {
+            Some(reply) => reply.in_reply_to_tweet_id = tweet_id.to_string(),
+            None => {
+                self.reply = Some(TweetsCreateRequestBodyReply {
+                    in_reply_to_tweet_id: tweet_id.to_string(),
+                    exclude_reply_user_ids: vec![],
+                })
+            }
+        }
+        self
+    }
+
+    // Takes effect only with in_reply_to_tweet_id.
+    pub fn exclude_reply_user_ids(mut self, user_ids: impl IntoIterator<Item = u64>) -> Self {
+        let user_ids = user_ids.into_iter().map(|x| x.to_string()).collect();
+        match &mut self.reply {
+            Some(reply) => reply.exclude_reply_user_ids = user_ids,
+            None => {
+                self.reply = Some(TweetsCreateRequestBodyReply {
+                    in_reply_to_tweet_id: "".into(),
+                    exclude_reply_user_ids: user_ids,
+                })
+            }
+        }
+        self
+    }
+
+    pub fn quote_tweet_id(mut self, tweet_id: u64) -> Self {
+        self.quote_tweet_id = Some(tweet_id.to_string());
+        self
+    }
+
+    pub fn reply_settings(mut self, reply_settings: ReplySettings) -> Self {
+        self.reply_settings = Some(reply_settings);
+        self
+    }
+
+    pub fn place_id(mut self, place_id: impl Into<String>) -> Self {
+        self.geo = Some(TweetsCreateRequestBodyGeo {
+            place_id: place_id.into(),
+        });
+        self
+    }
+
+    pub fn for_super_followers_only(mut self, for_super_followers_only: bool) -> Self {
+        self.for_super_followers_only = Some(for_super_followers_only);
+        self
+    }
+
+    pub fn direct_message_deep_link(mut self, direct_message_deep_link: impl Into<String>) -> Self {
+        self.direct_message_deep_link = Some(direct_message_deep_link.into());
+        self
+    }
+
+    //
+    pub fn validate(&self) -> Result<(), String> {
+        let media_ids = self.media.as_ref().map(|x| x.media_ids.len()).unwrap_or(0);
+        if self.text.is_none() && media_ids == 0 {
+            return Err("text is required if media is not present".into());
+        }
+
+        if let Some(media) = &self.media {
+            if media.media_ids.is_empty() && !media.tagged_user_ids.is_empty() {
+                return Err("tagged_user_ids requires media_ids".into());
+            }
+            if media.media_ids.len() > TWEETS_CREATE_MEDIA_IDS_MAX {
+                return Err(format!(
+                    "media_ids too many, count: {}, max: {TWEETS_CREATE_MEDIA_IDS_MAX}",
+                    media.media_ids.len()
+                ));
+            }
+            if media.tagged_user_ids.len() > TWEETS_CREATE_TAGGED_USER_IDS_MAX {
+                return Err(format!(
+                    "tagged_user_ids too many, count: {}, max: {TWEETS_CREATE_TAGGED_USER_IDS_MAX}",
+                    media.tagged_user_ids.len()
+                ));
+            }
+        }
+        // Up to 4 photos, or a single video or GIF.
+        if media_ids > 1
+            && self
+                .media_types
+                .iter()
+                .any(|x| matches!(x, Some(MediaType::Video | MediaType::AnimatedGif)))
+        {
+            return Err("a video or GIF can not be mixed with other media".into());
+        }
+
+        if let Some(poll) = &self.poll {
+            if media_ids > 0 {
+                return Err("poll and media are mutually exclusive".into());
+            }
+            if self.quote_tweet_id.is_some() {
+                return Err("poll and quote_tweet_id are mutually exclusive".into());
+            }
+            if self.direct_message_deep_link.is_some() {
+                return Err("poll and direct_message_deep_link are mutually exclusive".into());
+            }
+            if !TWEETS_CREATE_POLL_OPTIONS_RANGE.contains(&poll.options.len()) {
+                return Err(format!(
+                    "poll options count {} out of range {TWEETS_CREATE_POLL_OPTIONS_RANGE:?}",
+                    poll.options.len()
+                ));
+            }
+            if let Some(option) = poll
+                .options
+                .iter()
+                .find(|x| x.is_empty() || x.chars().count() > TWEETS_CREATE_POLL_OPTION_LEN_MAX)
+            {
+                return Err(format!(
+                    "poll option {option:?} length out of range 1..={TWEETS_CREATE_POLL_OPTION_LEN_MAX}"
+                ));
+            }
+            if !TWEETS_CREATE_POLL_DURATION_MINUTES_RANGE.contains(&poll.duration_minutes) {
+                return Err(format!(
+                    "poll duration_minutes {} out of range {TWEETS_CREATE_POLL_DURATION_MINUTES_RANGE:?}",
+                    poll.duration_minutes
+                ));
+            }
+        }
+
+        if let Some(reply) = &self.reply {
+            if reply.in_reply_to_tweet_id.is_empty() {
+                return Err("exclude_reply_user_ids requires in_reply_to_tweet_id".into());
+            }
+        }
+
+        Ok(())
+    }
+}
+
+//
+pub type TweetsCreateResponseBody = ResponseBody<TweetsCreateResponseBodyData>;
+
 #[derive(Deserialize, Serialize, Debug, Clone)]
 pub struct TweetsCreateResponseBodyData {
     #[serde(deserialize_with = "deserialize_number_from_string")]
     pub id: u64,
     pub text: Option<String>,
+    #[serde(default, deserialize_with = "deserialize_vec_number_from_string")]
+    pub edit_history_tweet_ids: Vec<u64>,
+}
+
+//
+pub type TweetsDeleteResponseBody = ResponseBody<TweetsDeleteResponseBodyData>;
+
+#[derive(Deserialize, Serialize, Debug, Clone)]
+pub struct TweetsDeleteResponseBodyData {
+    pub deleted: bool,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_ser_request_body() {
+        let body = CreateTweetRequest::new()
+            .text("Are you excited for the weekend?")
+            .in_reply_to_tweet_id(1455953449422516226)
+            .exclude_reply_user_ids([6253282])
+            .poll(["yes", "maybe"], 120)
+            .reply_settings(ReplySettings::MentionedUsers)
+            .place_id("5a110d312052166f")
+            .for_super_followers_only(false);
+        assert!(body.validate().is_ok());
+        assert_eq!(
+            serde_json::to_value(&body).unwrap(),
+            serde_json::json!({
+                "text": "Are you excited for the weekend?",
+                "poll": { "options": ["yes", "maybe"], "duration_minutes": 120 },
+                "reply": {
+                    "in_reply_to_tweet_id": "1455953449422516226",
+                    "exclude_reply_user_ids": ["6253282"]
+                },
+                "reply_settings": "mentionedUsers",
+                "geo": { "place_id": "5a110d312052166f" },
+                "for_super_followers_only": false
+            })
+        );
+
+        let body = CreateTweetRequest::new()
+            .media_keys(["3_1455952740635586573"])
+            .tagged_user_ids([2244994945])
+            .quote_tweet_id(1455953449422516226);
+        assert!(body.validate().is_ok());
+        assert_eq!(
+            serde_json::to_value(&body).unwrap(),
+            serde_json::json!({
+                "media": {
+                    "media_ids": ["1455952740635586573"],
+                    "tagged_user_ids": ["2244994945"]
+                },
+                "quote_tweet_id": "1455953449422516226"
+            })
+        );
+    }
+
+    #[test]
+    fn test_de_delete_response_body() {
+        let body = serde_json::from_str::<TweetsDeleteResponseBody>(r#"{"data":{"deleted":true}}"#)
+            .unwrap();
+        assert!(body.data.unwrap().deleted);
+    }
+
+    #[test]
+    fn test_validate() {
+        assert_eq!(
+            CreateTweetRequest::new().validate().unwrap_err(),
+            "text is required if media is not present"
+        );
+        assert_eq!(
+            CreateTweetRequest::new()
+                .media_ids([1])
+                .poll(["a", "b"], 5)
+                .validate()
+                .unwrap_err(),
+            "poll and media are mutually exclusive"
+        );
+        assert!(CreateTweetRequest::new()
+            .text("x")
+            .poll(["a"], 5)
+            .validate()
+            .is_err());
+        assert!(CreateTweetRequest::new()
+            .text("x")
+            .poll(["a", &"b".repeat(26)], 5)
+            .validate()
+            .is_err());
+        assert!(CreateTweetRequest::new()
+            .text("x")
+            .poll(["a", "b"], 4)
+            .validate()
+            .is_err());
+        assert!(CreateTweetRequest::new()
+            .media_ids(1..=5)
+            .validate()
+            .is_err());
+        assert!(CreateTweetRequest::new()
+            .media_keys(["3_1", "3_2", "3_3", "3_4"])
+            .validate()
+            .is_ok());
+        assert_eq!(
+            CreateTweetRequest::new()
+                .media_keys(["3_1", "16_2"])
+                .validate()
+                .unwrap_err(),
+            "a video or GIF can not be mixed with other media"
+        );
+        // Media keys of an unknown type still count.
+        assert_eq!(
+            CreateTweetRequest::new()
+                .media_keys(["16_1", "99_2"])
+                .validate()
+                .unwrap_err(),
+            "a video or GIF can not be mixed with other media"
+        );
+        assert!(CreateTweetRequest::new()
+            .media_keys(["16_1"])
+            .validate()
+            .is_ok());
+        assert!(CreateTweetRequest::new()
+            .text("x")
+            .tagged_user_ids([1])
+            .validate()
+            .is_err());
+        assert!(CreateTweetRequest::new()
+            .text("x")
+            .exclude_reply_user_ids([1])
+            .validate()
+            .is_err());
+    }
 }
@@ -1,8 +1,6 @@
 // https://developer.twitter.com/en/docs/twitter-api/users/lookup/introduction
 
-use serde::{Deserialize, Serialize};
-
-use crate::objects::User;
+use crate::{endpoints::common::ResponseBody, objects::User};
 
 //
 // TODO,
@@ -18,27 +16,23 @@ pub fn url_for_user_by_username(username: impl AsRef<str>) -> String {
 }
 
 //
-#[derive(Deserialize, Serialize, Debug, Clone)]
-pub struct SingleUserResponseBody {
-    pub data: User,
-}
+pub type SingleUserResponseBody = ResponseBody<User>;
 
-#[derive(Deserialize, Serialize, Debug, Clone)]
-pub struct MultipleUsersResponseBody {
-    pub data: Vec<User>,
-}
+pub type MultipleUsersResponseBody = ResponseBody<Vec<User>>;
 
 #[cfg(test)]
 mod tests {
     use super::*;
 
+    use crate::objects::{ProblemType, VerifiedType};
+
     #[test]
     fn test_de_response_body() {
         match serde_json::from_str::<SingleUserResponseBody>(include_str!(
             "../../../tests/response_body_json_files/users__lookup__me__default_fields.json"
         )) {
             Ok(body) => {
-                assert_eq!(body.data.id.unwrap(), 2244994945);
+                assert_eq!(body.data.unwrap().id.unwrap(), 2244994945);
             }
             Err(err) => panic!("{err}"),
         }
@@ -48,7 +42,34 @@ mod tests {
         )) {
             Ok(body) => {
                 println!("{body:?}");
-                assert_eq!(body.data.id.unwrap(), 2244994945);
+                let user = body.data.unwrap();
+                assert_eq!(user.id.unwrap(), 2244994945);
+                assert_eq!(user.most_recent_tweet_id, Some(1460323737035677698));
+                assert_eq!(user.verified_type, Some(VerifiedType::Business));
+                assert_eq!(user._others["subscription_type"], "None");
+            }
+            Err(err) => panic!("{err}"),
+        }
+
+        match serde_json::from_str::<SingleUserResponseBody>(include_str!(
+            "../../../tests/response_body_json_files/users__lookup__by_username__not_found.json"
+        )) {
+            Ok(body) => {
+                assert!(body.data.is_none());
+                assert_eq!(body.errors[0].r#type, ProblemType::ResourceNotFound);
+            }
+            Err(err) => panic!("{err}"),
+        }
+
+        //
+        match serde_json::from_str::<MultipleUsersResponseBody>(include_str!(
+            "../../../tests/response_body_json_files/users__lookup__multiple__partial_errors.json"
+        )) {
+            Ok(body) => {
+                assert!(body.is_partial());
+                assert_eq!(body.successes().len(), 2);
+                assert_eq!(body.partial_errors().len(), 1);
+                assert_eq!(body.partial_errors()[0].resource_id.as_deref(), Some("1"));
             }
             Err(err) => panic!("{err}"),
         }
@@ -1,18 +1,116 @@
 // https://developer.twitter.com/en/support/twitter-api/error-troubleshooting
 
 use serde::{Deserialize, Serialize};
+use serde_enum_str::{Deserialize_enum_str, Serialize_enum_str};
 use serde_json::{Map, Value};
 
 //
 #[derive(Deserialize, Serialize, Debug, Clone)]
 pub struct Error {
-    pub r#type: String,
+    pub r#type: ProblemType,
     pub title: String,
+    #[serde(default)]
     pub detail: String,
+    pub status: Option<u16>,
+    pub reason: Option<String>,
+    pub client_id: Option<String>,
+    pub required_enrollment: Option<String>,
+    pub registration_url: Option<String>,
+    #[serde(default)]
+    pub errors: Vec<ErrorErrorsItem>,
     #[serde(flatten)]
     pub _others: Map<String, Value>,
 }
 
+#[derive(Deserialize, Serialize, Debug, Clone)]
+pub struct ErrorErrorsItem {
+    pub message: String,
+    pub parameters: Option<Map<String, Value>>,
+    pub value: Option<Value>,
+    #[serde(flatten)]
+    pub _others: Map<String, Value>,
+}
+
+impl Error {
+    pub fn is_client_not_enrolled(&self) -> bool {
+        self.r#type == ProblemType::ClientForbidden
+            && self.reason.as_deref() == Some("client-not-enrolled")
+    }
+
+    // Duplicate Tweets have no problem type of their own, they are an "about:blank" 403
+    // that only the detail text tells apart from other forbidden requests.
+    pub fn is_duplicate_content(&self) -> bool {
+        self.r#type == ProblemType::AboutBlank
+            && self.status == Some(403)
+            && self.detail.to_lowercase().contains("duplicate content")
+    }
+}
+
+//
+// https://developer.twitter.com/en/support/twitter-api/error-troubleshooting#partial-errors
+#[derive(Deserialize, Serialize, Debug, Clone)]
+pub struct PartialError {
+    pub r#type: ProblemType,
+    pub title: String,
+    #[serde(default)]
+    pub detail: String,
+    pub value: Option<Value>,
+    pub parameter: Option<String>,
+    pub resource_type: Option<String>,
+    pub resource_id: Option<String>,
+    pub section: Option<String>,
+    #[serde(flatten)]
+    pub _others: Map<String, Value>,
+}
+
+impl PartialError {
+    pub fn is_resource_not_found(&self) -> bool {
+        self.r#type == ProblemType::ResourceNotFound
+    }
+}
+
+//
+// https://developer.twitter.com/en/support/twitter-api/error-troubleshooting#error-types
+#[derive(Deserialize_enum_str, Serialize_enum_str, Debug, Clone, PartialEq, Eq)]
+pub enum ProblemType {
+    #[serde(rename = "about:blank")]
+    AboutBlank,
+    #[serde(rename = "https://api.twitter.com/2/problems/client-disconnected")]
+    ClientDisconnected,
+    #[serde(rename = "https://api.twitter.com/2/problems/client-forbidden")]
+    ClientForbidden,
+    #[serde(rename = "https://api.twitter.com/2/problems/disallowed-resource")]
+    DisallowedResource,
+    #[serde(rename = "https://api.twitter.com/2/problems/duplicate-rules")]
+    DuplicateRules,
+    #[serde(rename = "https://api.twitter.com/2/problems/invalid-request")]
+    InvalidRequest,
+    #[serde(rename = "https://api.twitter.com/2/problems/invalid-rules")]
+    InvalidRules,
+    #[serde(rename = "https://api.twitter.com/2/problems/noncompliant-rules")]
+    NoncompliantRules,
+    #[serde(rename = "https://api.twitter.com/2/problems/not-authorized-for-field")]
+    NotAuthorizedForField,
+    #[serde(rename = "https://api.twitter.com/2/problems/not-authorized-for-resource")]
+    NotAuthorizedForResource,
+    #[serde(rename = "https://api.twitter.com/2/problems/operational-disconnect")]
+    OperationalDisconnect,
+    #[serde(rename = "https://api.twitter.com/2/problems/resource-not-found")]
+    ResourceNotFound,
+    #[serde(rename = "https://api.twitter.com/2/problems/resource-unavailable")]
+    ResourceUnavailable,
+    #[serde(rename = "https://api.twitter.com/2/problems/rule-cap")]
+    RuleCap,
+    #[serde(rename = "https://api.twitter.com/2/problems/streaming-connection")]
+    StreamingConnection,
+    #[serde(rename = "https://api.twitter.com/2/problems/unsupported-authentication")]
+    UnsupportedAuthentication,
+    #[serde(rename = "https://api.twitter.com/2/problems/usage-capped")]
+    UsageCapped,
+    #[serde(other)]
+    Other(String),
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -33,6 +131,9 @@ mod tests {
         match serde_json::from_str::<Error>(json) {
             Ok(error) => {
                 assert_eq!(error.title, "Client Forbidden");
+                assert_eq!(error.r#type, ProblemType::ClientForbidden);
+                assert_eq!(error.required_enrollment.as_deref(), Some("Standard Basic"));
+                assert!(error.is_client_not_enrolled());
             }
             Err(err) => panic!("{err}"),
         }
@@ -43,6 +144,67 @@ mod tests {
         )) {
             Ok(error) => {
                 assert_eq!(error.title, "Invalid Request");
+                assert_eq!(error.r#type, ProblemType::InvalidRequest);
+                assert_eq!(
+                    error.errors[0].message,
+                    "Requests with bodies must have content-type of application/json."
+                );
+            }
+            Err(err) => panic!("{err}"),
+        }
+
+        //
+        let json = r#"
+        {
+            "detail": "You are not allowed to create a Tweet with duplicate content.",
+            "type": "about:blank",
+            "title": "Forbidden",
+            "status": 403
+        }
+        "#;
+        match serde_json::from_str::<Error>(json) {
+            Ok(error) => {
+                assert_eq!(error.r#type, ProblemType::AboutBlank);
+                assert_eq!(error.status, Some(403));
+                assert!(error.is_duplicate_content());
+            }
+            Err(err) => panic!("{err}"),
+        }
+        let json = r#"{"detail": "Forbidden.", "type": "about:blank", "title": "Forbidden", "status": 403}"#;
+        assert!(!serde_json::from_str::<Error>(json)
+            .unwrap()
+            .is_duplicate_content());
+        let json = r#"{"detail": "Too Many Requests", "type": "about:blank", "title": "Too Many Requests", "status": 429}"#;
+        assert!(!serde_json::from_str::<Error>(json)
+            .unwrap()
+            .is_duplicate_content());
+
+        //
+        let json = r#"
+        {
+            "errors": [
+                {
+                    "parameters": {
+                        "ids": ["x"]
+                    },
+                    "message": "The `ids` query parameter value [x] does not match ^[0-9]{1,19}$"
+                }
+            ],
+            "title": "Invalid Request",
+            "detail": "One or more parameters to your request was invalid.",
+            "type": "https://api.twitter.com/2/problems/foo"
+        }
+        "#;
+        match serde_json::from_str::<Error>(json) {
+            Ok(error) => {
+                assert_eq!(
+                    error.r#type,
+                    ProblemType::Other("https://api.twitter.com/2/problems/foo".into())
+                );
+                assert_eq!(
+                    error.errors[0].parameters.as_ref().unwrap()["ids"],
+                    Value::from(vec!["x"])
+                );
             }
             Err(err) => panic!("{err}"),
         }
@@ -0,0 +1,134 @@
+// https://developer.twitter.com/en/docs/twitter-api/data-dictionary/object-model/media
+
+use serde::{Deserialize, Serialize};
+use serde_enum_str::{Deserialize_enum_str, Serialize_enum_str};
+use serde_json::{Map, Value};
+
+//
+#[derive(Deserialize, Serialize, Debug, Clone)]
+pub struct Media {
+    pub media_key: Option<String>,
+    pub r#type: Option<MediaType>,
+    // Photos only.
+    pub url: Option<String>,
+    pub preview_image_url: Option<String>,
+    pub width: Option<u32>,
+    pub height: Option<u32>,
+    pub duration_ms: Option<u64>,
+    pub alt_text: Option<String>,
+    pub public_metrics: Option<Map<String, Value>>,
+    // Videos and GIFs only.
+    #[serde(default)]
+    pub variants: Vec<MediaVariant>,
+    #[serde(flatten)]
+    pub _others: Map<String, Value>,
+}
+
+#[derive(Deserialize_enum_str, Serialize_enum_str, Debug, Clone, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum MediaType {
+    Photo,
+    Video,
+    AnimatedGif,
+    #[serde(other)]
+    Other(String),
+}
+
+impl MediaType {
+    // The media_key prefix, e.g. 3_1880028106020515840 is a photo.
+    pub fn from_media_key(media_key: &str) -> Option<Self> {
+        match media_key.split_once('_')?.0 {
+            "3" => Some(Self::Photo),
+            "7" | "13" => Some(Self::Video),
+            "16" => Some(Self::AnimatedGif),
+            _ => None,
+        }
+    }
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone)]
+pub struct MediaVariant {
+    pub bit_rate: Option<u64>,
+    pub content_type: String,
+    pub url: String,
+}
+
+impl Media {
+    // The MP4 variant with the highest bit rate, GIFs have a single one without bit_rate.
+    pub fn best_mp4_variant(&self) -> Option<&MediaVariant> {
+        self.variants
+            .iter()
+            .filter(|x| x.content_type == "video/mp4")
+            .max_by_key(|x| x.bit_rate.unwrap_or_default())
+    }
+
+    // The original resolution photo, or the best MP4 variant of videos and GIFs.
+    pub fn original_url(&self) -> Option<String> {
+        match self.r#type {
+            Some(MediaType::Photo) => self.url.as_deref().map(photo_original_url),
+            Some(MediaType::Video | MediaType::AnimatedGif) => {
+                self.best_mp4_variant().map(|x| x.url.to_owned())
+            }
+            _ => None,
+        }
+    }
+}
+
+// https://pbs.twimg.com/media/FoUkFj6XgAEPtnd.jpg -> https://pbs.twimg.com/media/FoUkFj6XgAEPtnd.jpg?name=orig
+fn photo_original_url(url: &str) -> String {
+    let (base, query) = url.split_once('?').unwrap_or((url, ""));
+    let mut pairs = query
+        .split('&')
+        .filter(|x| !x.is_empty() && !x.starts_with("name="))
+        .collect::<Vec<_>>();
+    pairs.push("name=orig");
+    format!("{base}?{}", pairs.join("&"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_original_url() {
+        let media = serde_json::from_str::<Vec<Media>>(
+            r#"[
+                {"media_key":"3_1","type":"photo","url":"https://pbs.twimg.com/media/FoUkFj6XgAEPtnd.jpg","width":1200,"height":800},
+                {"media_key":"3_2","type":"photo","url":"https://pbs.twimg.com/media/FoUkFj6XgAEPtne?format=png&name=small"},
+                {"media_key":"7_3","type":"video","duration_ms":46947,"variants":[
+                    {"bit_rate":632000,"content_type":"video/mp4","url":"https://video.twimg.com/ext_tw_video/3/pu/vid/480x270/a.mp4"},
+                    {"content_type":"application/x-mpegURL","url":"https://video.twimg.com/ext_tw_video/3/pu/pl/b.m3u8"},
+                    {"bit_rate":2176000,"content_type":"video/mp4","url":"https://video.twimg.com/ext_tw_video/3/pu/vid/1280x720/c.mp4"}
+                ]},
+                {"media_key":"16_4","type":"animated_gif","variants":[
+                    {"bit_rate":0,"content_type":"video/mp4","url":"https://video.twimg.com/tweet_video/d.mp4"}
+                ]},
+                {"media_key":"99_5","type":"hologram"}
+            ]"#,
+        )
+        .unwrap();
+
+        assert_eq!(
+            media[0].original_url().unwrap(),
+            "https://pbs.twimg.com/media/FoUkFj6XgAEPtnd.jpg?name=orig"
+        );
+        assert_eq!(
+            media[1].original_url().unwrap(),
+            "https://pbs.twimg.com/media/FoUkFj6XgAEPtne?format=png&name=orig"
+        );
+        assert_eq!(
+            media[2].original_url().unwrap(),
+            "https://video.twimg.com/ext_tw_video/3/pu/vid/1280x720/c.mp4"
+        );
+        assert_eq!(
+            media[3].original_url().unwrap(),
+            "https://video.twimg.com/tweet_video/d.mp4"
+        );
+        assert_eq!(media[4].r#type, Some(MediaType::Other("hologram".into())));
+        assert!(media[4].original_url().is_none());
+
+        assert_eq!(MediaType::from_media_key("7_3"), Some(MediaType::Video));
+        assert_eq!(MediaType::from_media_key("99_5"), None);
+        assert_eq!(MediaType::from_media_key("3"), None);
+    }
+}
@@ -2,7 +2,13 @@
 pub mod user;
 pub use user::*;
 
+pub mod media;
+pub use media::*;
+
+pub mod tweet;
+pub use tweet::*;
+
 //
 pub mod error;
 
-pub use error::Error as ResponseBodyErrJson;
+pub use error::{Error as ResponseBodyErrJson, PartialError, ProblemType};
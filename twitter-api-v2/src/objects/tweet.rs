@@ -0,0 +1,265 @@
+// https://developer.twitter.com/en/docs/twitter-api/data-dictionary/object-model/tweet
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Deserializer, Serialize};
+use serde_aux::field_attributes::{
+    deserialize_number_from_string, deserialize_option_number_from_string,
+};
+use serde_enum_str::{Deserialize_enum_str, Serialize_enum_str};
+use serde_json::{Map, Value};
+
+//
+#[derive(Deserialize, Serialize, Debug, Clone)]
+pub struct Tweet {
+    #[serde(default, deserialize_with = "deserialize_option_number_from_string")]
+    pub id: Option<u64>,
+    pub text: Option<String>,
+    #[serde(default, deserialize_with = "deserialize_option_number_from_string")]
+    pub author_id: Option<u64>,
+    #[serde(default, deserialize_with = "deserialize_option_number_from_string")]
+    pub conversation_id: Option<u64>,
+    pub created_at: Option<DateTime<Utc>>,
+    #[serde(default, deserialize_with = "deserialize_option_number_from_string")]
+    pub in_reply_to_user_id: Option<u64>,
+    #[serde(default)]
+    pub referenced_tweets: Vec<ReferencedTweet>,
+    pub attachments: Option<TweetAttachments>,
+    pub entities: Option<TweetEntities>,
+    pub public_metrics: Option<TweetPublicMetrics>,
+    #[serde(default)]
+    pub context_annotations: Vec<ContextAnnotation>,
+    pub lang: Option<String>,
+    pub possibly_sensitive: Option<bool>,
+    pub reply_settings: Option<ReplySettings>,
+    #[serde(default, deserialize_with = "deserialize_vec_number_from_string")]
+    pub edit_history_tweet_ids: Vec<u64>,
+    pub edit_controls: Option<TweetEditControls>,
+    pub note_tweet: Option<NoteTweet>,
+    pub withheld: Option<TweetWithheld>,
+    pub geo: Option<Map<String, Value>>,
+    pub source: Option<String>,
+    #[serde(flatten)]
+    pub _others: Map<String, Value>,
+}
+
+//
+#[derive(Deserialize, Serialize, Debug, Clone)]
+pub struct ReferencedTweet {
+    pub r#type: ReferencedTweetType,
+    #[serde(deserialize_with = "deserialize_number_from_string")]
+    pub id: u64,
+}
+
+#[derive(Deserialize_enum_str, Serialize_enum_str, Debug, Clone, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum ReferencedTweetType {
+    Retweeted,
+    Quoted,
+    RepliedTo,
+    #[serde(other)]
+    Other(String),
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone, Default)]
+pub struct TweetAttachments {
+    #[serde(default)]
+    pub media_keys: Vec<String>,
+    #[serde(default, deserialize_with = "deserialize_vec_number_from_string")]
+    pub poll_ids: Vec<u64>,
+}
+
+//
+#[derive(Deserialize, Serialize, Debug, Clone, Default)]
+pub struct TweetEntities {
+    #[serde(default)]
+    pub annotations: Vec<TweetEntityAnnotation>,
+    #[serde(default)]
+    pub cashtags: Vec<TweetEntityTag>,
+    #[serde(default)]
+    pub hashtags: Vec<TweetEntityTag>,
+    #[serde(default)]
+    pub mentions: Vec<TweetEntityMention>,
+    #[serde(default)]
+    pub urls: Vec<TweetEntityUrl>,
+}
+
+// start and end are offsets in characters, end exclusive.
+#[derive(Deserialize, Serialize, Debug, Clone)]
+pub struct TweetEntityAnnotation {
+    pub start: usize,
+    pub end: usize,
+    pub probability: f64,
+    pub r#type: String,
+    pub normalized_text: String,
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone)]
+pub struct TweetEntityTag {
+    pub start: usize,
+    pub end: usize,
+    pub tag: String,
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone)]
+pub struct TweetEntityMention {
+    pub start: usize,
+    pub end: usize,
+    pub username: String,
+    #[serde(default, deserialize_with = "deserialize_option_number_from_string")]
+    pub id: Option<u64>,
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone)]
+pub struct TweetEntityUrl {
+    pub start: usize,
+    pub end: usize,
+    pub url: String,
+    pub expanded_url: Option<String>,
+    pub display_url: Option<String>,
+    pub unwound_url: Option<String>,
+    pub status: Option<u16>,
+    pub title: Option<String>,
+    pub description: Option<String>,
+    pub media_key: Option<String>,
+    #[serde(flatten)]
+    pub _others: Map<String, Value>,
+}
+
+//
+#[derive(Deserialize, Serialize, Debug, Clone, Default)]
+pub struct TweetPublicMetrics {
+    pub retweet_count: u64,
+    pub reply_count: u64,
+    pub like_count: u64,
+    pub quote_count: u64,
+    pub bookmark_count: Option<u64>,
+    pub impression_count: Option<u64>,
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone)]
+pub struct ContextAnnotation {
+    pub domain: ContextAnnotationItem,
+    pub entity: ContextAnnotationItem,
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone)]
+pub struct ContextAnnotationItem {
+    #[serde(deserialize_with = "deserialize_number_from_string")]
+    pub id: u64,
+    pub name: Option<String>,
+    pub description: Option<String>,
+}
+
+#[derive(Deserialize_enum_str, Serialize_enum_str, Debug, Clone, PartialEq, Eq)]
+pub enum ReplySettings {
+    #[serde(rename = "everyone")]
+    Everyone,
+    #[serde(rename = "following")]
+    Following,
+    #[serde(rename = "mentionedUsers")]
+    MentionedUsers,
+    #[serde(rename = "subscribers")]
+    Subscribers,
+    #[serde(rename = "verified")]
+    Verified,
+    #[serde(other)]
+    Other(String),
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone)]
+pub struct TweetEditControls {
+    pub edits_remaining: u32,
+    pub is_edit_eligible: bool,
+    pub editable_until: DateTime<Utc>,
+}
+
+// The full text of tweets longer than 280 characters, `text` is truncated.
+#[derive(Deserialize, Serialize, Debug, Clone)]
+pub struct NoteTweet {
+    pub text: String,
+    pub entities: Option<TweetEntities>,
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone)]
+pub struct TweetWithheld {
+    pub copyright: Option<bool>,
+    #[serde(default)]
+    pub country_codes: Vec<String>,
+    pub scope: Option<String>,
+}
+
+//
+pub(crate) fn deserialize_vec_number_from_string<'de, D>(
+    deserializer: D,
+) -> Result<Vec<u64>, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    #[derive(Deserialize)]
+    struct Id(#[serde(deserialize_with = "deserialize_number_from_string")] u64);
+
+    Ok(Vec::<Id>::deserialize(deserializer)?
+        .into_iter()
+        .map(|x| x.0)
+        .collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_de_tweet() {
+        match serde_json::from_str::<Tweet>(include_str!(
+            "../../tests/response_body_json_files/objects__tweet.json"
+        )) {
+            Ok(tweet) => {
+                assert_eq!(tweet.id, Some(1460323737035677698));
+                assert_eq!(tweet.author_id, Some(2244994945));
+                assert_eq!(tweet.conversation_id, tweet.id);
+                assert_eq!(tweet.edit_history_tweet_ids, vec![1460323737035677698]);
+                assert_eq!(
+                    tweet.referenced_tweets[0].r#type,
+                    ReferencedTweetType::Quoted
+                );
+                assert_eq!(tweet.referenced_tweets[0].id, 1460321990473355265);
+                assert_eq!(
+                    tweet.attachments.unwrap().media_keys,
+                    vec!["7_1460322142680072196"]
+                );
+                let entities = tweet.entities.unwrap();
+                assert_eq!(entities.hashtags[0].tag, "api");
+                assert_eq!(entities.cashtags[0].tag, "TWTR");
+                assert_eq!(entities.mentions[0].id, Some(2244994945));
+                assert_eq!(entities.urls[0].status, Some(200));
+                assert_eq!(
+                    entities.urls[1].media_key.as_deref(),
+                    Some("7_1460322142680072196")
+                );
+                assert_eq!(entities.annotations[0].r#type, "Product");
+                let public_metrics = tweet.public_metrics.unwrap();
+                assert_eq!(public_metrics.like_count, 857);
+                assert_eq!(public_metrics.impression_count, Some(60213));
+                assert_eq!(tweet.context_annotations[0].domain.id, 46);
+                assert_eq!(tweet.reply_settings, Some(ReplySettings::Everyone));
+                assert_eq!(tweet.edit_controls.unwrap().edits_remaining, 5);
+                assert_eq!(
+                    tweet.note_tweet.unwrap().entities.unwrap().hashtags.len(),
+                    1
+                );
+                assert_eq!(tweet.withheld.unwrap().country_codes, vec!["DE"]);
+                assert_eq!(tweet.source.as_deref(), Some("Twitter Web App"));
+            }
+            Err(err) => panic!("{err}"),
+        }
+
+        // Only the default fields.
+        let tweet = serde_json::from_str::<Tweet>(
+            r#"{"id":"1","text":"hi","edit_history_tweet_ids":["1"]}"#,
+        )
+        .unwrap();
+        assert_eq!(tweet.id, Some(1));
+        assert!(tweet.referenced_tweets.is_empty());
+        assert!(tweet.entities.is_none());
+    }
+}
@@ -3,6 +3,7 @@
 use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
 use serde_aux::field_attributes::deserialize_option_number_from_string;
+use serde_enum_str::{Deserialize_enum_str, Serialize_enum_str};
 use serde_json::{Map, Value};
 
 //
@@ -23,5 +24,21 @@ pub struct User {
     pub public_metrics: Option<Map<String, Value>>,
     pub url: Option<String>,
     pub verified: Option<bool>,
+    pub verified_type: Option<VerifiedType>,
+    #[serde(default, deserialize_with = "deserialize_option_number_from_string")]
+    pub most_recent_tweet_id: Option<u64>,
     pub withheld: Option<Value>,
+    #[serde(flatten)]
+    pub _others: Map<String, Value>,
+}
+
+#[derive(Deserialize_enum_str, Serialize_enum_str, Debug, Clone, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum VerifiedType {
+    Blue,
+    Business,
+    Government,
+    None,
+    #[serde(other)]
+    Other(String),
 }